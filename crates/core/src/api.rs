@@ -137,6 +137,11 @@ impl Api {
         &self,
         filters: &RecipesFilter,
     ) -> Result<Vec<recipe::Recipe>, ApiError> {
+        // tags are not one of the server's filters, see [`RecipesFilter::matches_tags`]
+        let filters = RecipesFilter {
+            tags: None,
+            ..filters.clone()
+        };
         let req_url = format!(
             "{}/recipes/?{}",
             self.base_url,
@@ -152,7 +157,7 @@ impl Api {
     }
 
     pub async fn get_recipe_by_id(&self, id: String) -> Result<recipe::Recipe, ApiError> {
-        let req_url = format!("{}/recipes/{}/", self.base_url, &id);
+        let req_url = format!("{}/recipes/{}/", self.base_url, id);
         let response = ApiError::from_response_result(
             Request::get(&req_url)
                 .header("Authorization", &self.get_authorization_value().unwrap())
@@ -234,6 +239,28 @@ impl Api {
         ApiError::check_json_response_ok::<String>(response).await
     }
 
+    /// Download a recipe's image, so it can be uploaded again (e.g. for a copy of the recipe)
+    pub async fn get_recipe_image(
+        &self,
+        media_url: &str,
+        image_id: &str,
+    ) -> Result<web_sys::File, ApiError> {
+        let req_url = format!("{}/recipe-image/{}", media_url, image_id);
+        let response = ApiError::from_response_result(Request::get(&req_url).send().await)?;
+        let mime_type = response.headers().get("content-type");
+        let content = response
+            .binary()
+            .await
+            .map_err(|_| ApiError::Internal(ApiInternalError::Generic))?;
+        let file = gloo::file::File::new_with_options(
+            image_id,
+            content.as_slice(),
+            mime_type.as_deref(),
+            None,
+        );
+        Ok(AsRef::<web_sys::File>::as_ref(&file).clone())
+    }
+
     pub async fn delete_recipe_image(&self, id: String) -> Result<(), ApiError> {
         let req_url = format!("{}/recipes/{}/image/", self.base_url, id);
         ApiError::from_response_result(
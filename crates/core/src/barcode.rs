@@ -0,0 +1,292 @@
+//! Pure Rust barcode decoder, used when the browser does not provide the `BarcodeDetector` API.
+//!
+//! Supports EAN-13 (including UPC-A, which is returned with a leading zero) and EAN-8,
+//! which covers the barcodes found on most groceries.
+
+// widths of the 4 runs making up a digit in the "L" & "R" code sets,
+// the "G" code set uses the same widths reversed
+const DIGIT_WIDTHS: [[u8; 4]; 10] = [
+    [3, 2, 1, 1],
+    [2, 2, 2, 1],
+    [2, 1, 2, 2],
+    [1, 4, 1, 1],
+    [1, 1, 3, 2],
+    [1, 2, 3, 1],
+    [1, 1, 1, 4],
+    [1, 3, 1, 2],
+    [1, 2, 1, 3],
+    [3, 1, 1, 2],
+];
+// L/G parity of the left half digits for EAN-13, used to find the implied first digit
+// (true == G code set)
+const FIRST_DIGIT_PARITY: [[bool; 6]; 10] = [
+    [false, false, false, false, false, false],
+    [false, false, true, false, true, true],
+    [false, false, true, true, false, true],
+    [false, false, true, true, true, false],
+    [false, true, false, false, true, true],
+    [false, true, true, false, false, true],
+    [false, true, true, true, false, false],
+    [false, true, false, true, false, true],
+    [false, true, false, true, true, false],
+    [false, true, true, false, true, false],
+];
+// rows with less difference between darkest and lightest pixel are ignored
+const MIN_CONTRAST: u8 = 40;
+// maximum summed module error allowed when matching a digit
+const MAX_DIGIT_ERROR: f32 = 1.6;
+// how many rows of the image to try decoding
+const SCAN_ROWS: usize = 32;
+
+/// A bar or space and how many pixels wide it is.
+#[derive(Debug, Clone, Copy)]
+struct Run {
+    is_bar: bool,
+    width: usize,
+}
+
+struct Layout {
+    /// digits encoded in each half
+    half_digits: usize,
+    /// total width in modules, including guards
+    modules: usize,
+}
+
+const EAN_13: Layout = Layout {
+    half_digits: 6,
+    modules: 95,
+};
+const EAN_8: Layout = Layout {
+    half_digits: 4,
+    modules: 67,
+};
+
+/// Convert RGBA pixel data (e.g. from a canvas `ImageData`) into luma.
+pub fn rgba_to_luma(rgba: &[u8]) -> Vec<u8> {
+    rgba.as_chunks::<4>()
+        .0
+        .iter()
+        .map(|p| ((p[0] as u32 * 299 + p[1] as u32 * 587 + p[2] as u32 * 114) / 1000) as u8)
+        .collect()
+}
+
+/// Attempt to decode a barcode from a greyscale image,
+/// returning the barcode digits if one was found.
+///
+/// Rows are scanned outwards from the middle of the image,
+/// barcodes may be upside down but must be roughly horizontal.
+pub fn decode_luma(luma: &[u8], width: usize, height: usize) -> Option<String> {
+    if width == 0 || height == 0 || luma.len() < width * height {
+        return None;
+    }
+    let step = (height / SCAN_ROWS).max(1);
+    let middle = height / 2;
+    (0..=middle / step)
+        .flat_map(|i| [middle.checked_sub(i * step), Some(middle + i * step)])
+        .flatten()
+        .filter(|&y| y < height)
+        .find_map(|y| decode_row(&luma[y * width..(y + 1) * width]))
+}
+
+fn decode_row(row: &[u8]) -> Option<String> {
+    let mut runs = row_to_runs(row)?;
+    decode_runs(&runs).or_else(|| {
+        runs.reverse();
+        decode_runs(&runs)
+    })
+}
+
+fn row_to_runs(row: &[u8]) -> Option<Vec<Run>> {
+    let min = *row.iter().min()?;
+    let max = *row.iter().max()?;
+    if max - min < MIN_CONTRAST {
+        return None;
+    }
+    let threshold = min + (max - min) / 2;
+    let mut runs: Vec<Run> = Vec::new();
+    for &pixel in row {
+        let is_bar = pixel < threshold;
+        match runs.last_mut() {
+            Some(run) if run.is_bar == is_bar => run.width += 1,
+            _ => runs.push(Run { is_bar, width: 1 }),
+        }
+    }
+    Some(runs)
+}
+
+fn decode_runs(runs: &[Run]) -> Option<String> {
+    (0..runs.len())
+        .filter(|&i| runs[i].is_bar)
+        .find_map(|i| decode_at(&runs[i..], &EAN_13).or_else(|| decode_at(&runs[i..], &EAN_8)))
+}
+
+/// Try to decode a barcode with the given layout, where `runs` starts at the start guard.
+fn decode_at(runs: &[Run], layout: &Layout) -> Option<String> {
+    // guard (3) + left digits + middle guard (5) + right digits + guard (3)
+    let half_runs = layout.half_digits * 4;
+    let total_runs = 3 + half_runs + 5 + half_runs + 3;
+    let runs = runs.get(..total_runs)?;
+    let module = runs.iter().map(|r| r.width).sum::<usize>() as f32 / layout.modules as f32;
+
+    let middle_start = 3 + half_runs;
+    let right_start = middle_start + 5;
+    let end_start = right_start + half_runs;
+    let guards = runs[..3]
+        .iter()
+        .chain(&runs[middle_start..right_start])
+        .chain(&runs[end_start..]);
+    if !guards
+        .map(|r| r.width as f32 / module)
+        .all(|m| (0.5..1.5).contains(&m))
+    {
+        return None;
+    }
+
+    let mut digits = Vec::with_capacity(layout.half_digits * 2 + 1);
+    let mut parity = Vec::with_capacity(layout.half_digits);
+    for group in runs[3..middle_start].as_chunks::<4>().0 {
+        let (digit, is_g) = match_digit(group, module, true)?;
+        digits.push(digit);
+        parity.push(is_g);
+    }
+    for group in runs[right_start..end_start].as_chunks::<4>().0 {
+        let (digit, _) = match_digit(group, module, false)?;
+        digits.push(digit);
+    }
+
+    if layout.half_digits == EAN_13.half_digits {
+        let first = FIRST_DIGIT_PARITY
+            .iter()
+            .position(|p| p[..] == parity[..])?;
+        digits.insert(0, first as u8);
+    } else if parity.iter().any(|&is_g| is_g) {
+        return None;
+    }
+
+    if !checksum_valid(&digits) {
+        return None;
+    }
+    Some(digits.iter().map(|d| char::from(b'0' + d)).collect())
+}
+
+/// Match a group of 4 runs to a digit, returning the digit and whether it used the "G" code set.
+fn match_digit(group: &[Run], module: f32, allow_g: bool) -> Option<(u8, bool)> {
+    let total: usize = group.iter().map(|r| r.width).sum();
+    if !(5.0..9.0).contains(&(total as f32 / module)) {
+        return None;
+    }
+    let normalised: Vec<f32> = group
+        .iter()
+        .map(|r| r.width as f32 * 7.0 / total as f32)
+        .collect();
+    let error = |widths: &mut dyn Iterator<Item = &u8>| -> f32 {
+        widths
+            .zip(&normalised)
+            .map(|(&w, n)| (w as f32 - n).abs())
+            .sum()
+    };
+
+    let mut best: Option<(u8, bool, f32)> = None;
+    for (digit, widths) in DIGIT_WIDTHS.iter().enumerate() {
+        let mut candidates = vec![(false, error(&mut widths.iter()))];
+        if allow_g {
+            candidates.push((true, error(&mut widths.iter().rev())));
+        }
+        for (is_g, err) in candidates {
+            if best.is_none_or(|(_, _, best_err)| err < best_err) {
+                best = Some((digit as u8, is_g, err));
+            }
+        }
+    }
+    best.filter(|(_, _, err)| *err <= MAX_DIGIT_ERROR)
+        .map(|(digit, is_g, _)| (digit, is_g))
+}
+
+/// Validate the check digit (last digit) of an EAN/UPC code.
+fn checksum_valid(digits: &[u8]) -> bool {
+    let Some((check, data)) = digits.split_last() else {
+        return false;
+    };
+    let sum: u32 = data
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| d as u32 * if i % 2 == 0 { 3 } else { 1 })
+        .sum();
+    (10 - sum % 10) % 10 == *check as u32
+}
+
+#[cfg(test)]
+mod tests_barcode {
+    use super::*;
+
+    /// Read a binary (P5) PGM image, returning (luma, width, height).
+    fn read_pgm(data: &[u8]) -> (Vec<u8>, usize, usize) {
+        let mut fields = Vec::new();
+        let mut pos = 0;
+        while fields.len() < 4 {
+            while data[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            let start = pos;
+            while !data[pos].is_ascii_whitespace() {
+                pos += 1;
+            }
+            fields.push(std::str::from_utf8(&data[start..pos]).unwrap().to_owned());
+        }
+        assert_eq!(fields[0], "P5");
+        let width = fields[1].parse().unwrap();
+        let height = fields[2].parse().unwrap();
+        (data[pos + 1..].to_vec(), width, height)
+    }
+
+    fn decode_fixture(data: &[u8]) -> Option<String> {
+        let (luma, width, height) = read_pgm(data);
+        decode_luma(&luma, width, height)
+    }
+
+    #[test]
+    fn test_decode_ean13() {
+        let decoded = decode_fixture(include_bytes!("../fixtures/barcode/ean13.pgm"));
+        assert_eq!(decoded.as_deref(), Some("5901234123457"));
+    }
+
+    #[test]
+    fn test_decode_upca() {
+        let decoded = decode_fixture(include_bytes!("../fixtures/barcode/upca.pgm"));
+        assert_eq!(decoded.as_deref(), Some("0036000291452"));
+    }
+
+    #[test]
+    fn test_decode_ean8() {
+        let decoded = decode_fixture(include_bytes!("../fixtures/barcode/ean8.pgm"));
+        assert_eq!(decoded.as_deref(), Some("96385074"));
+    }
+
+    #[test]
+    fn test_decode_upside_down_and_noisy() {
+        let decoded = decode_fixture(include_bytes!(
+            "../fixtures/barcode/ean13_rotated_noisy.pgm"
+        ));
+        assert_eq!(decoded.as_deref(), Some("4006381333931"));
+    }
+
+    #[test]
+    fn test_decode_no_barcode() {
+        let decoded = decode_fixture(include_bytes!("../fixtures/barcode/blank.pgm"));
+        assert_eq!(decoded, None);
+    }
+
+    #[test]
+    fn test_rgba_to_luma() {
+        let luma = rgba_to_luma(&[255, 255, 255, 255, 0, 0, 0, 255]);
+        assert_eq!(luma, vec![255, 0]);
+    }
+
+    #[test]
+    fn test_checksum() {
+        assert!(checksum_valid(&[5, 9, 0, 1, 2, 3, 4, 1, 2, 3, 4, 5, 7]));
+        assert!(!checksum_valid(&[5, 9, 0, 1, 2, 3, 4, 1, 2, 3, 4, 5, 8]));
+        assert!(checksum_valid(&[9, 6, 3, 8, 5, 0, 7, 4]));
+    }
+}
@@ -0,0 +1,96 @@
+//! Pure Rust helpers for preparing images before they are uploaded,
+//! working out crop & resize sizes.
+
+/// Width divided by height of the recipe cards images are shown in
+pub const CARD_ASPECT_RATIO: f64 = 1.0;
+
+/// Part of an image to keep, in pixels of the upright image
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CropRect {
+    pub x: f64,
+    pub y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl CropRect {
+    /// The largest crop of the aspect ratio, scaled by zoom (1 or more), centred on the image
+    pub fn centered(width: f64, height: f64, aspect_ratio: f64, zoom: f64) -> Self {
+        let zoom = zoom.max(1.0);
+        let (crop_width, crop_height) = if width / height > aspect_ratio {
+            (height * aspect_ratio, height)
+        } else {
+            (width, width / aspect_ratio)
+        };
+        let (crop_width, crop_height) = (crop_width / zoom, crop_height / zoom);
+        Self {
+            x: (width - crop_width) / 2.0,
+            y: (height - crop_height) / 2.0,
+            width: crop_width,
+            height: crop_height,
+        }
+    }
+
+    /// Move the crop by an amount, keeping it within an image of the given size
+    pub fn moved(&self, dx: f64, dy: f64, width: f64, height: f64) -> Self {
+        Self {
+            x: (self.x + dx).clamp(0.0, (width - self.width).max(0.0)),
+            y: (self.y + dy).clamp(0.0, (height - self.height).max(0.0)),
+            ..*self
+        }
+    }
+
+    /// Change the zoom keeping the same centre, as far as the image allows
+    pub fn zoomed(&self, width: f64, height: f64, aspect_ratio: f64, zoom: f64) -> Self {
+        let resized = Self::centered(width, height, aspect_ratio, zoom);
+        let (cx, cy) = (self.x + self.width / 2.0, self.y + self.height / 2.0);
+        Self {
+            x: cx - resized.width / 2.0,
+            y: cy - resized.height / 2.0,
+            ..resized
+        }
+        .moved(0.0, 0.0, width, height)
+    }
+}
+
+/// Size to encode an image at, scaled down (never up) so the longest side fits max_size
+pub fn fit_size(width: f64, height: f64, max_size: u32) -> (u32, u32) {
+    let scale = (max_size as f64 / width.max(height)).min(1.0);
+    (
+        ((width * scale).round() as u32).max(1),
+        ((height * scale).round() as u32).max(1),
+    )
+}
+
+#[cfg(test)]
+mod tests_image {
+    use super::*;
+
+    #[test]
+    fn test_crop() {
+        let crop = CropRect::centered(400.0, 200.0, 1.0, 1.0);
+        assert_eq!(
+            crop,
+            CropRect {
+                x: 100.0,
+                y: 0.0,
+                width: 200.0,
+                height: 200.0
+            }
+        );
+        let moved = crop.moved(500.0, -10.0, 400.0, 200.0);
+        assert_eq!((moved.x, moved.y), (200.0, 0.0));
+        let zoomed = moved.zoomed(400.0, 200.0, 1.0, 2.0);
+        assert_eq!((zoomed.x, zoomed.y, zoomed.width), (250.0, 50.0, 100.0));
+        // zooming back out keeps it within the image
+        let unzoomed = zoomed.zoomed(400.0, 200.0, 1.0, 1.0);
+        assert_eq!((unzoomed.x, unzoomed.y), (200.0, 0.0));
+    }
+
+    #[test]
+    fn test_fit_size() {
+        assert_eq!(fit_size(4000.0, 3000.0, 1600), (1600, 1200));
+        assert_eq!(fit_size(300.0, 600.0, 1600), (300, 600));
+        assert_eq!(fit_size(3000.0, 1.0, 100), (100, 1));
+    }
+}
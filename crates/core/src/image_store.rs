@@ -0,0 +1,74 @@
+//! Images kept by the browser (in the Cache Storage), for recipe images beyond the one the server holds
+
+use js_sys::{Date, Math};
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{Blob, Cache, Response};
+
+use crate::api::{ApiError, ApiInternalError};
+
+const IMAGE_CACHE_NAME: &str = "recipe-images";
+
+fn internal_error<T>(_: T) -> ApiError {
+    ApiError::Internal(ApiInternalError::Generic)
+}
+
+/// Images are stored against a url, this is never requested from a server
+fn image_url(key: &str) -> String {
+    format!("/local-recipe-image/{key}")
+}
+
+/// A new unique key for an image
+pub fn new_image_key() -> String {
+    format!(
+        "{:x}-{:x}",
+        Date::now() as u64,
+        (Math::random() * u32::MAX as f64) as u32
+    )
+}
+
+async fn open_cache() -> Result<Cache, ApiError> {
+    let window = web_sys::window().ok_or(ApiError::Internal(ApiInternalError::Generic))?;
+    let cache = JsFuture::from(
+        window
+            .caches()
+            .map_err(internal_error)?
+            .open(IMAGE_CACHE_NAME),
+    )
+    .await
+    .map_err(internal_error)?;
+    Ok(cache.unchecked_into())
+}
+
+pub async fn put_image(key: &str, image: &Blob) -> Result<(), ApiError> {
+    let cache = open_cache().await?;
+    let response = Response::new_with_opt_blob(Some(image)).map_err(internal_error)?;
+    JsFuture::from(cache.put_with_str(&image_url(key), &response))
+        .await
+        .map_err(internal_error)?;
+    Ok(())
+}
+
+/// Read an image, none when it was never stored or the browser has since cleared it
+pub async fn get_image(key: &str) -> Result<Option<Blob>, ApiError> {
+    let cache = open_cache().await?;
+    let response = JsFuture::from(cache.match_with_str(&image_url(key)))
+        .await
+        .map_err(internal_error)?;
+    if response.is_undefined() {
+        return Ok(None);
+    }
+    let response: Response = response.unchecked_into();
+    let blob = JsFuture::from(response.blob().map_err(internal_error)?)
+        .await
+        .map_err(internal_error)?;
+    Ok(Some(blob.unchecked_into()))
+}
+
+pub async fn delete_image(key: &str) -> Result<(), ApiError> {
+    let cache = open_cache().await?;
+    JsFuture::from(cache.delete_with_str(&image_url(key)))
+        .await
+        .map_err(internal_error)?;
+    Ok(())
+}
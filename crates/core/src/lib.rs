@@ -1,5 +1,11 @@
 pub mod api;
+pub mod barcode;
 pub mod constants;
+pub mod image;
+pub mod image_store;
+pub mod qr_code;
+pub mod query;
 pub mod storage;
+pub mod virtual_list;
 
 pub use constants::APP_TITLE;
@@ -0,0 +1,37 @@
+//! QR codes drawn as SVG, e.g. linking a printed recipe back to its page.
+
+use qrcodegen::{QrCode, QrCodeEcc};
+
+/// Blank modules around the code, the 4 module quiet zone the QR code spec requires
+pub const QR_BORDER: usize = 4;
+
+/// Size (in modules, including the border) & SVG path of a QR code for the text,
+/// none when the text is too long to encode
+pub fn qr_code_path(text: &str) -> Option<(usize, String)> {
+    let qr = QrCode::encode_text(text, QrCodeEcc::Medium).ok()?;
+    let size = qr.size();
+    let mut path = String::new();
+    for y in 0..size {
+        for x in 0..size {
+            if qr.get_module(x, y) {
+                let (x, y) = (x as usize + QR_BORDER, y as usize + QR_BORDER);
+                path.push_str(&format!("M{x},{y}h1v1h-1z"));
+            }
+        }
+    }
+    Some((size as usize + QR_BORDER * 2, path))
+}
+
+#[cfg(test)]
+mod tests_qr_code {
+    use super::*;
+
+    #[test]
+    fn test_qr_code_path() {
+        let (size, path) = qr_code_path("https://example.com/recipes/1").unwrap();
+        // fits a version 3 code, 29 modules wide
+        assert_eq!(size, 37);
+        assert!(path.starts_with("M4,4h1v1h-1z"));
+        assert!(qr_code_path(&"a".repeat(5000)).is_none());
+    }
+}
@@ -0,0 +1,138 @@
+//! Encoding of page state (filters, sorting, etc) into the URL query string.
+
+use serde::{de::DeserializeOwned, Serialize};
+
+/// Encode into a query string (without the leading `?`),
+/// sets/lists are encoded as repeated keys.
+pub fn to_query_string<T: Serialize>(value: &T) -> String {
+    serde_html_form::to_string(value).unwrap_or_default()
+}
+
+/// Decode from a query string (with or without the leading `?`),
+/// falling back to the default when invalid.
+pub fn from_query_string<T: DeserializeOwned + Default>(query: &str) -> T {
+    serde_html_form::from_str(query.trim_start_matches('?')).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests_query {
+    use std::collections::HashSet;
+
+    use mcc_frontend_types::{
+        cookbook::{PrintLayout, PrintOptions},
+        query::{PantryColumn, PantryFilter, PantrySort, PantryView, PrintQuery, RecipesFilter},
+    };
+
+    use super::*;
+
+    #[test]
+    fn test_recipes_filter_labels_round_trip() {
+        let filters = RecipesFilter {
+            title: Some("pie & mash".to_owned()),
+            labels: Some(HashSet::from(["vegetarian".to_owned(), "quick".to_owned()])),
+            freezable: Some(true),
+            ..Default::default()
+        };
+        let query = to_query_string(&filters);
+        assert!(query.contains("label=vegetarian"));
+        assert!(query.contains("label=quick"));
+        assert!(query.contains("freezable=true"));
+        assert_eq!(from_query_string::<RecipesFilter>(&query), filters);
+    }
+
+    #[test]
+    fn test_pantry_filter_labels_round_trip() {
+        let filters = PantryFilter {
+            page: 3,
+            labels: Some(HashSet::from(["tins".to_owned()])),
+            location_id: Some("abc".to_owned()),
+            ..Default::default()
+        };
+        let query = to_query_string(&filters);
+        assert!(query.contains("label=tins"));
+        assert!(query.contains("locationId=abc"));
+        assert_eq!(from_query_string::<PantryFilter>(&query), filters);
+    }
+
+    #[test]
+    fn test_filter_missing_fields_use_default() {
+        let filters = from_query_string::<RecipesFilter>("?label=a&label=b&label=a");
+        assert_eq!(
+            filters,
+            RecipesFilter {
+                labels: Some(HashSet::from(["a".to_owned(), "b".to_owned()])),
+                ..Default::default()
+            }
+        );
+        assert_eq!(
+            from_query_string::<PantryFilter>(""),
+            PantryFilter::default()
+        );
+    }
+
+    #[test]
+    fn test_filter_ignores_other_fields() {
+        let filters = from_query_string::<PantryFilter>("name=beans&sort=expiry&column=notes");
+        assert_eq!(filters.name.as_deref(), Some("beans"));
+    }
+
+    #[test]
+    fn test_pantry_view_round_trip() {
+        let view = PantryView {
+            sort: Some(PantrySort::Expiry),
+            columns: [PantryColumn::Notes, PantryColumn::Location].into(),
+        };
+        let query = to_query_string(&view);
+        assert!(query.contains("sort=expiry"));
+        assert!(query.contains("column=notes"));
+        assert!(query.contains("column=location"));
+        assert_eq!(from_query_string::<PantryView>(&format!("?{query}")), view);
+    }
+
+    #[test]
+    fn test_pantry_view_invalid() {
+        assert_eq!(
+            from_query_string::<PantryView>("sort=nope"),
+            PantryView::default()
+        );
+        assert_eq!(from_query_string::<PantryView>(""), PantryView::default());
+    }
+
+    #[test]
+    fn test_print_query_with_filter() {
+        let query = "id=b&id=a&layout=card3x5&label=quick&title=pie";
+        assert_eq!(
+            from_query_string::<PrintQuery>(query),
+            PrintQuery {
+                ids: vec!["b".to_owned(), "a".to_owned()],
+                cookbook: None,
+            }
+        );
+        assert_eq!(
+            from_query_string::<PrintOptions>(query).layout,
+            PrintLayout::Card3x5
+        );
+        let filters = from_query_string::<RecipesFilter>(query);
+        assert_eq!(filters.title.as_deref(), Some("pie"));
+        assert_eq!(from_query_string::<PrintQuery>("id=a").ids, vec!["a"]);
+    }
+
+    #[test]
+    fn test_print_options_round_trip() {
+        let options = PrintOptions {
+            notes: false,
+            qr_code: true,
+            scale: 1.5,
+            font_size: 120,
+            ..Default::default()
+        };
+        let query = to_query_string(&options);
+        assert!(query.contains("qrCode=true"));
+        assert!(query.contains("scale=1.5"));
+        assert_eq!(from_query_string::<PrintOptions>(&query), options);
+        assert_eq!(
+            from_query_string::<PrintOptions>(""),
+            PrintOptions::default()
+        );
+    }
+}
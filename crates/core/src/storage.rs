@@ -1,10 +1,41 @@
-use gloo::storage::{LocalStorage, Storage};
-use mcc_frontend_types::StoredLogin;
+use std::collections::HashMap;
+
+use gloo::storage::{LocalStorage, SessionStorage, Storage};
+use mcc_frontend_types::{
+    collection::Collections, cookbook::PrintOptions, draft::RecipeDrafts, gallery::RecipeGalleries,
+    history::RecipeHistory, image::ImagePlaceholders, label::LabelMetadata, page::PagingMode,
+    pantry::ScannedProduct, query::PantryView, recipe::RecipeVariants, search::SearchIndex,
+    StoredLogin,
+};
+use serde::Serialize;
 
 const LOGIN_DETAILS_KEY: &str = "login-details";
+const SCANNED_PRODUCTS_KEY: &str = "scanned-products";
+const PANTRY_VIEW_KEY: &str = "pantry-view";
+const LABEL_METADATA_KEY: &str = "label-metadata";
+const SCROLL_POSITIONS_KEY: &str = "scroll-positions";
+const SEARCH_INDEX_KEY: &str = "search-index";
+const RECIPE_VARIANTS_KEY: &str = "recipe-variants";
+const RECIPE_HISTORY_KEY: &str = "recipe-history";
+const RECIPE_DRAFTS_KEY: &str = "recipe-drafts";
+const RECIPE_GALLERIES_KEY: &str = "recipe-galleries";
+const IMAGE_MAX_SIZE_KEY: &str = "image-max-size";
+const IMAGE_PLACEHOLDERS_KEY: &str = "image-placeholders";
+const PAGING_MODE_KEY: &str = "paging-mode";
+const COLLECTIONS_KEY: &str = "collections";
+const PRINT_OPTIONS_KEY: &str = "print-options";
+// caches bigger than this are not saved, so they can not crowd out everything else
+const MAX_SEARCH_INDEX_BYTES: usize = 1_000_000;
+const MAX_IMAGE_PLACEHOLDERS_BYTES: usize = 500_000;
+
+pub use gloo::storage::errors::StorageError;
 
+/// Read the stored login, logins stored before the username was recorded are left out
+/// so the user logs in again, as their settings are kept under their username
 pub fn read_login_token() -> Option<StoredLogin> {
-    LocalStorage::get::<StoredLogin>(LOGIN_DETAILS_KEY).ok()
+    LocalStorage::get::<StoredLogin>(LOGIN_DETAILS_KEY)
+        .ok()
+        .filter(|login| !login.username.is_empty())
 }
 
 pub fn set_login_token(login: StoredLogin) {
@@ -14,3 +45,170 @@ pub fn set_login_token(login: StoredLogin) {
 pub fn remove_login_token() {
     LocalStorage::delete(LOGIN_DETAILS_KEY)
 }
+
+/// Read all remembered products, keyed by barcode
+pub fn read_scanned_products() -> HashMap<String, ScannedProduct> {
+    LocalStorage::get(SCANNED_PRODUCTS_KEY).unwrap_or_default()
+}
+
+pub fn read_scanned_product(barcode: &str) -> Option<ScannedProduct> {
+    read_scanned_products().remove(barcode)
+}
+
+/// Remember a product against a barcode, replacing any existing product
+pub fn set_scanned_product(barcode: &str, product: ScannedProduct) -> Result<(), StorageError> {
+    let mut products = read_scanned_products();
+    products.insert(barcode.to_owned(), product);
+    LocalStorage::set(SCANNED_PRODUCTS_KEY, products)
+}
+
+/// Largest size images are resized to before uploading, chosen for this device
+pub fn read_image_max_size() -> Option<u32> {
+    LocalStorage::get(IMAGE_MAX_SIZE_KEY).ok()
+}
+
+pub fn set_image_max_size(size: u32) -> Result<(), StorageError> {
+    LocalStorage::set(IMAGE_MAX_SIZE_KEY, size)
+}
+
+/// Make a key unique to the logged in user (and server)
+fn user_key(key: &str, login: &StoredLogin) -> String {
+    format!("{key}:{}:{}", login.api_url, login.username)
+}
+
+pub fn read_pantry_view(login: &StoredLogin) -> Option<PantryView> {
+    LocalStorage::get(user_key(PANTRY_VIEW_KEY, login)).ok()
+}
+
+pub fn set_pantry_view(login: &StoredLogin, view: &PantryView) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(PANTRY_VIEW_KEY, login), view)
+}
+
+pub fn read_paging_mode(login: &StoredLogin) -> PagingMode {
+    LocalStorage::get(user_key(PAGING_MODE_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_paging_mode(login: &StoredLogin, mode: PagingMode) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(PAGING_MODE_KEY, login), mode)
+}
+
+pub fn read_label_metadata(login: &StoredLogin) -> LabelMetadata {
+    LocalStorage::get(user_key(LABEL_METADATA_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_label_metadata(
+    login: &StoredLogin,
+    metadata: &LabelMetadata,
+) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(LABEL_METADATA_KEY, login), metadata)
+}
+
+pub fn read_recipe_variants(login: &StoredLogin) -> RecipeVariants {
+    LocalStorage::get(user_key(RECIPE_VARIANTS_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_recipe_variants(
+    login: &StoredLogin,
+    variants: &RecipeVariants,
+) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(RECIPE_VARIANTS_KEY, login), variants)
+}
+
+pub fn read_recipe_history(login: &StoredLogin) -> RecipeHistory {
+    LocalStorage::get(user_key(RECIPE_HISTORY_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_recipe_history(
+    login: &StoredLogin,
+    history: &RecipeHistory,
+) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(RECIPE_HISTORY_KEY, login), history)
+}
+
+pub fn read_recipe_drafts(login: &StoredLogin) -> RecipeDrafts {
+    LocalStorage::get(user_key(RECIPE_DRAFTS_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_recipe_drafts(login: &StoredLogin, drafts: &RecipeDrafts) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(RECIPE_DRAFTS_KEY, login), drafts)
+}
+
+pub fn read_recipe_galleries(login: &StoredLogin) -> RecipeGalleries {
+    LocalStorage::get(user_key(RECIPE_GALLERIES_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_recipe_galleries(
+    login: &StoredLogin,
+    galleries: &RecipeGalleries,
+) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(RECIPE_GALLERIES_KEY, login), galleries)
+}
+
+pub fn read_collections(login: &StoredLogin) -> Collections {
+    LocalStorage::get(user_key(COLLECTIONS_KEY, login)).unwrap_or_default()
+}
+
+pub fn set_collections(login: &StoredLogin, collections: &Collections) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(COLLECTIONS_KEY, login), collections)
+}
+
+pub fn read_print_options(login: &StoredLogin) -> Option<PrintOptions> {
+    LocalStorage::get(user_key(PRINT_OPTIONS_KEY, login)).ok()
+}
+
+pub fn set_print_options(login: &StoredLogin, options: &PrintOptions) -> Result<(), StorageError> {
+    LocalStorage::set(user_key(PRINT_OPTIONS_KEY, login), options)
+}
+
+/// Save something that can be made again, unless it is bigger than `max_bytes`.
+/// When too big (or out of space) the saved copy is removed instead,
+/// leaving the space to things that can not be made again.
+fn set_cache<T: Serialize>(key: &str, value: &T, max_bytes: usize) {
+    let saved = serde_json::to_string(value)
+        .ok()
+        .filter(|v| v.len() <= max_bytes)
+        .is_some_and(|v| LocalStorage::raw().set_item(key, &v).is_ok());
+    if !saved {
+        LocalStorage::delete(key);
+    }
+}
+
+pub fn read_image_placeholders(login: &StoredLogin) -> ImagePlaceholders {
+    LocalStorage::get(user_key(IMAGE_PLACEHOLDERS_KEY, login)).unwrap_or_default()
+}
+
+/// Save the image placeholders, they are remade as images are seen (see [`set_cache`])
+pub fn set_image_placeholders(login: &StoredLogin, placeholders: &ImagePlaceholders) {
+    set_cache(
+        &user_key(IMAGE_PLACEHOLDERS_KEY, login),
+        placeholders,
+        MAX_IMAGE_PLACEHOLDERS_BYTES,
+    )
+}
+
+pub fn read_search_index(login: &StoredLogin) -> SearchIndex {
+    LocalStorage::get(user_key(SEARCH_INDEX_KEY, login)).unwrap_or_default()
+}
+
+/// Save the search index, it can always be rebuilt (see [`set_cache`])
+pub fn set_search_index(login: &StoredLogin, index: &SearchIndex) {
+    set_cache(
+        &user_key(SEARCH_INDEX_KEY, login),
+        index,
+        MAX_SEARCH_INDEX_BYTES,
+    )
+}
+
+/// Read a scroll position remembered for this browser session
+pub fn read_scroll_position(key: &str) -> Option<f64> {
+    SessionStorage::get::<HashMap<String, f64>>(SCROLL_POSITIONS_KEY)
+        .ok()?
+        .remove(key)
+}
+
+pub fn set_scroll_position(key: &str, position: f64) -> Result<(), StorageError> {
+    let mut positions: HashMap<String, f64> =
+        SessionStorage::get(SCROLL_POSITIONS_KEY).unwrap_or_default();
+    positions.insert(key.to_owned(), position);
+    SessionStorage::set(SCROLL_POSITIONS_KEY, positions)
+}
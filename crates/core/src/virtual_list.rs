@@ -0,0 +1,102 @@
+//! Working out which rows of a long list are on (or near) the screen,
+//! so only those need to be rendered, with the rest replaced by empty space.
+
+/// Rows rendered above & below the screen, so fast scrolling does not show gaps
+pub const OVERSCAN_ROWS: usize = 3;
+
+/// Items of a list to render, with how many rows are left out either side
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct VisibleItems {
+    /// index of the first item rendered
+    pub start: usize,
+    /// index after the last item rendered
+    pub end: usize,
+    pub rows_before: usize,
+    pub rows_after: usize,
+}
+
+impl VisibleItems {
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Which items are within the viewport (plus [`OVERSCAN_ROWS`]).
+///
+/// `offset` is how far the top of the viewport is past the top of the list,
+/// negative when the list starts further down the page.
+pub fn visible_items(
+    len: usize,
+    columns: usize,
+    row_height: f64,
+    offset: f64,
+    viewport_height: f64,
+) -> VisibleItems {
+    let columns = columns.max(1);
+    let rows = len.div_ceil(columns);
+    if row_height <= 0.0 {
+        return VisibleItems {
+            start: 0,
+            end: len,
+            rows_before: 0,
+            rows_after: 0,
+        };
+    }
+    let first_row = ((offset.max(0.0) / row_height).floor() as usize)
+        .saturating_sub(OVERSCAN_ROWS)
+        .min(rows);
+    let last_row = (((offset + viewport_height).max(0.0) / row_height).ceil() as usize
+        + OVERSCAN_ROWS)
+        .clamp(first_row, rows);
+    VisibleItems {
+        start: (first_row * columns).min(len),
+        end: (last_row * columns).min(len),
+        rows_before: first_row,
+        rows_after: rows - last_row,
+    }
+}
+
+#[cfg(test)]
+mod tests_virtual_list {
+    use super::*;
+
+    #[test]
+    fn test_visible_items_top() {
+        // 10 rows of 100px, the list starts 50px below the top of the screen
+        let visible = visible_items(50, 5, 100.0, -50.0, 300.0);
+        assert_eq!(
+            visible,
+            VisibleItems {
+                start: 0,
+                end: 30,
+                rows_before: 0,
+                rows_after: 4,
+            }
+        );
+    }
+
+    #[test]
+    fn test_visible_items_scrolled() {
+        let visible = visible_items(1000, 1, 50.0, 2000.0, 500.0);
+        assert_eq!(visible.start, 40 - OVERSCAN_ROWS);
+        assert_eq!(visible.end, 50 + OVERSCAN_ROWS);
+        assert_eq!(
+            visible.rows_before + visible.len() + visible.rows_after,
+            1000
+        );
+        // past the end of the list
+        let visible = visible_items(7, 3, 50.0, 10_000.0, 500.0);
+        assert!(visible.is_empty());
+        assert_eq!((visible.rows_before, visible.rows_after), (3, 0));
+    }
+
+    #[test]
+    fn test_visible_items_unmeasured() {
+        let visible = visible_items(7, 3, 0.0, 100.0, 500.0);
+        assert_eq!((visible.start, visible.end), (0, 7));
+    }
+}
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+
+/// A named group of recipes in the order they are shown (e.g. a menu or cookbook),
+/// kept by the client as the server has nowhere to store them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Collection {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    /// recipe whose image is the cover, none for the first recipe with one
+    pub cover_recipe_id: Option<String>,
+    pub recipe_ids: Vec<String>,
+}
+
+impl Collection {
+    pub fn contains(&self, recipe_id: &str) -> bool {
+        self.recipe_ids.iter().any(|v| v == recipe_id)
+    }
+
+    /// Add a recipe to the end, returning false if it was already there
+    pub fn add_recipe(&mut self, recipe_id: &str) -> bool {
+        if self.contains(recipe_id) {
+            return false;
+        }
+        self.recipe_ids.push(recipe_id.to_owned());
+        true
+    }
+
+    pub fn remove_recipe(&mut self, recipe_id: &str) {
+        self.recipe_ids.retain(|v| v != recipe_id);
+        if self.cover_recipe_id.as_deref() == Some(recipe_id) {
+            self.cover_recipe_id = None;
+        }
+    }
+
+    /// Recipe whose image is the cover, the chosen one or else the first with an image
+    pub fn cover_recipe<F>(&self, has_image: F) -> Option<&str>
+    where
+        F: Fn(&str) -> bool,
+    {
+        self.cover_recipe_id.as_deref().or_else(|| {
+            self.recipe_ids
+                .iter()
+                .map(|v| v.as_str())
+                .find(|v| has_image(v))
+        })
+    }
+
+    /// Swap a recipe with the one before (or after) it
+    pub fn move_recipe(&mut self, recipe_id: &str, earlier: bool) {
+        let Some(index) = self.recipe_ids.iter().position(|v| v == recipe_id) else {
+            return;
+        };
+        let other = match earlier {
+            true => index.checked_sub(1),
+            false => Some(index + 1).filter(|v| *v < self.recipe_ids.len()),
+        };
+        if let Some(other) = other {
+            self.recipe_ids.swap(index, other);
+        }
+    }
+}
+
+/// Every collection, in the order they are listed
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct Collections(pub Vec<Collection>);
+
+impl Collections {
+    pub fn get(&self, id: &str) -> Option<&Collection> {
+        self.0.iter().find(|v| v.id == id)
+    }
+
+    pub fn get_mut(&mut self, id: &str) -> Option<&mut Collection> {
+        self.0.iter_mut().find(|v| v.id == id)
+    }
+
+    /// Add an empty collection to the end, returning its id
+    pub fn create(&mut self, name: String, description: Option<String>) -> String {
+        let id = self
+            .0
+            .iter()
+            .filter_map(|v| v.id.parse::<u64>().ok())
+            .max()
+            .map_or(1, |v| v + 1)
+            .to_string();
+        self.0.push(Collection {
+            id: id.clone(),
+            name,
+            description,
+            cover_recipe_id: None,
+            recipe_ids: Vec::new(),
+        });
+        id
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.0.retain(|v| v.id != id);
+    }
+
+    /// Collections a recipe is in
+    pub fn of_recipe(&self, recipe_id: &str) -> Vec<&Collection> {
+        self.0.iter().filter(|v| v.contains(recipe_id)).collect()
+    }
+
+    /// Forget a removed recipe
+    pub fn remove_recipe(&mut self, recipe_id: &str) {
+        for collection in self.0.iter_mut() {
+            collection.remove_recipe(recipe_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_collection {
+    use super::*;
+
+    fn collections() -> Collections {
+        let mut collections = Collections::default();
+        let id = collections.create("Christmas".to_owned(), None);
+        let collection = collections.get_mut(&id).unwrap();
+        for recipe_id in ["a", "b", "c"] {
+            collection.add_recipe(recipe_id);
+        }
+        collections
+    }
+
+    #[test]
+    fn test_create() {
+        let mut collections = collections();
+        assert_eq!(collections.0[0].id, "1");
+        let id = collections.create("Weeknight".to_owned(), Some("Quick".to_owned()));
+        assert_eq!(id, "2");
+        collections.remove("1");
+        assert_eq!(collections.create("Lunch".to_owned(), None), "3");
+        assert!(collections.get("1").is_none());
+    }
+
+    #[test]
+    fn test_recipes() {
+        let mut collections = collections();
+        let collection = collections.get_mut("1").unwrap();
+        assert!(!collection.add_recipe("a"));
+        collection.move_recipe("c", true);
+        assert_eq!(collection.recipe_ids, vec!["a", "c", "b"]);
+        collection.move_recipe("a", true);
+        collection.move_recipe("b", false);
+        assert_eq!(collection.recipe_ids, vec!["a", "c", "b"]);
+        assert_eq!(collection.cover_recipe(|v| v != "a"), Some("c"));
+        collection.cover_recipe_id = Some("b".to_owned());
+        assert_eq!(collection.cover_recipe(|_| true), Some("b"));
+
+        assert_eq!(collections.of_recipe("b").len(), 1);
+        collections.remove_recipe("b");
+        assert!(collections.of_recipe("b").is_empty());
+        let collection = collections.get("1").unwrap();
+        assert_eq!(collection.recipe_ids, vec!["a", "c"]);
+        assert_eq!(collection.cover_recipe_id, None);
+    }
+}
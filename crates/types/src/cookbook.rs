@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    recipe::{Ingredient, Recipe},
+    sub_recipe::{combine_ingredients, expand_ingredients},
+    units::{convert_amount, UnitSystem},
+};
+
+/// How printed recipes are laid out on the page
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PrintLayout {
+    #[default]
+    Standard,
+    /// ingredients & steps side by side in two columns, in smaller text
+    Compact,
+    LargePrint,
+    /// each recipe on 3x5 inch cards
+    Card3x5,
+    /// each recipe on 4x6 inch cards
+    Card4x6,
+}
+
+impl PrintLayout {
+    pub const ALL: [Self; 5] = [
+        Self::Standard,
+        Self::Compact,
+        Self::LargePrint,
+        Self::Card3x5,
+        Self::Card4x6,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Standard => "Standard",
+            Self::Compact => "Compact (Two Columns)",
+            Self::LargePrint => "Large Print",
+            Self::Card3x5 => "Index Card (3x5)",
+            Self::Card4x6 => "Index Card (4x6)",
+        }
+    }
+
+    pub fn is_card(&self) -> bool {
+        matches!(self, Self::Card3x5 | Self::Card4x6)
+    }
+
+    /// Paper size for the CSS `@page` rule, none to leave it to the printer
+    pub fn page_size(&self) -> Option<&'static str> {
+        match self {
+            Self::Card3x5 => Some("5in 3in"),
+            Self::Card4x6 => Some("6in 4in"),
+            _ => None,
+        }
+    }
+}
+
+/// What is printed & how, also used as the print pages' query string
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrintOptions {
+    pub layout: PrintLayout,
+    /// the recipe's own image
+    pub image: bool,
+    /// images from the recipe's gallery
+    pub gallery: bool,
+    pub notes: bool,
+    pub source: bool,
+    pub labels: bool,
+    /// a QR code linking back to the recipe's page
+    pub qr_code: bool,
+    /// what ingredient amounts & yields are multiplied by
+    pub scale: f32,
+    pub units: UnitSystem,
+    /// percentage of the layout's text size
+    pub font_size: u16,
+}
+
+impl Default for PrintOptions {
+    fn default() -> Self {
+        Self {
+            layout: PrintLayout::default(),
+            image: true,
+            gallery: false,
+            notes: true,
+            source: true,
+            labels: false,
+            qr_code: false,
+            scale: 1.0,
+            units: UnitSystem::default(),
+            font_size: 100,
+        }
+    }
+}
+
+impl PrintOptions {
+    pub const SCALES: [f32; 6] = [0.5, 1.0, 1.5, 2.0, 3.0, 4.0];
+    pub const MIN_FONT_SIZE: u16 = 50;
+    pub const MAX_FONT_SIZE: u16 = 200;
+    pub const FONT_SIZE_STEP: u16 = 10;
+
+    /// An ingredient scaled & in the unit system, section headers are left as they are
+    pub fn ingredient(&self, ingredient: &Ingredient) -> Ingredient {
+        if ingredient.section_name().is_some() {
+            return ingredient.clone();
+        }
+        let (amount, unit_type) = convert_amount(
+            ingredient.amount * self.scale,
+            &ingredient.unit_type,
+            self.units,
+        );
+        Ingredient {
+            amount,
+            unit_type,
+            ..ingredient.clone()
+        }
+    }
+
+    /// A recipe's ingredients scaled & in the unit system. When scaled, sub-recipes
+    /// are expanded into the ingredients of the `linked` recipes (losing the sections),
+    /// so the amounts to measure out are shown rather than a number of batches.
+    pub fn ingredients(
+        &self,
+        recipe: &Recipe,
+        linked: &HashMap<String, Recipe>,
+    ) -> Vec<Ingredient> {
+        let has_links = recipe
+            .ingredients
+            .iter()
+            .any(|v| v.linked_recipe().is_some());
+        if self.scale == 1.0 || !has_links {
+            return recipe
+                .ingredients
+                .iter()
+                .map(|v| self.ingredient(v))
+                .collect();
+        }
+        let expanded = expand_ingredients(recipe, linked, self.scale);
+        // already scaled while expanding
+        let unscaled = Self {
+            scale: 1.0,
+            ..self.clone()
+        };
+        combine_ingredients(expanded.ingredients)
+            .iter()
+            .map(|v| unscaled.ingredient(v))
+            .collect()
+    }
+
+    /// A recipe's yield scaled, rounded to a whole number (at least 1)
+    pub fn yields(&self, value: usize) -> usize {
+        ((value as f32 * self.scale).round() as usize).max(1)
+    }
+
+    /// Text size after a step bigger (or smaller)
+    pub fn step_font_size(&self, bigger: bool) -> u16 {
+        match bigger {
+            true => self.font_size.saturating_add(Self::FONT_SIZE_STEP),
+            false => self.font_size.saturating_sub(Self::FONT_SIZE_STEP),
+        }
+        .clamp(Self::MIN_FONT_SIZE, Self::MAX_FONT_SIZE)
+    }
+}
+
+/// Ingredients used by the recipes, sorted by name, each with the positions in `recipes`
+/// of the recipes using it. Names are matched ignoring case, linked recipes use their title.
+pub fn ingredient_index(recipes: &[Recipe]) -> Vec<(String, Vec<usize>)> {
+    let mut index: HashMap<String, (String, Vec<usize>)> = HashMap::new();
+    for (position, recipe) in recipes.iter().enumerate() {
+        for ingredient in &recipe.ingredients {
+            if ingredient.section_name().is_some() {
+                continue;
+            }
+            let name = ingredient.display_name().trim().to_owned();
+            if name.is_empty() {
+                continue;
+            }
+            let (_, positions) = index
+                .entry(name.to_lowercase())
+                .or_insert_with(|| (name, Vec::new()));
+            if positions.last() != Some(&position) {
+                positions.push(position);
+            }
+        }
+    }
+    let mut index = index.into_values().collect::<Vec<_>>();
+    index.sort_by_cached_key(|(name, _)| name.to_lowercase());
+    index
+}
+
+#[cfg(test)]
+mod tests_cookbook {
+    use crate::{recipe::Info, sub_recipe::RecipeLink};
+
+    use super::*;
+
+    fn recipe(ingredients: &[&str]) -> Recipe {
+        Recipe {
+            id: "1".to_owned(),
+            owner_id: "owner".to_owned(),
+            title: "Recipe".to_owned(),
+            info: Info::default(),
+            short_description: None,
+            long_description: None,
+            tags: vec![],
+            ingredients: ingredients
+                .iter()
+                .map(|name| Ingredient {
+                    name: name.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            steps: vec![],
+            labels: vec![],
+            image_id: None,
+        }
+    }
+
+    #[test]
+    fn test_ingredient_index() {
+        let recipes = [
+            recipe(&["Flour", "# Topping", "sugar", "flour"]),
+            recipe(&["Eggs"]),
+            recipe(&["flour ", "Sugar"]),
+        ];
+        assert_eq!(
+            ingredient_index(&recipes),
+            vec![
+                ("Eggs".to_owned(), vec![1]),
+                ("Flour".to_owned(), vec![0, 2]),
+                ("sugar".to_owned(), vec![0, 2]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_print_ingredients_expanded() {
+        let dough = Recipe {
+            id: "dough".to_owned(),
+            ingredients: vec![Ingredient {
+                name: "Flour".to_owned(),
+                amount: 500.0,
+                unit_type: "g".to_owned(),
+                description: None,
+            }],
+            ..recipe(&[])
+        };
+        let pizza = Recipe {
+            ingredients: vec![
+                Ingredient::section("Base"),
+                Ingredient::sub_recipe(
+                    &RecipeLink {
+                        id: "dough".to_owned(),
+                        title: "Dough".to_owned(),
+                    },
+                    1.0,
+                ),
+                Ingredient {
+                    name: "flour".to_owned(),
+                    amount: 50.0,
+                    unit_type: "g".to_owned(),
+                    description: None,
+                },
+            ],
+            ..recipe(&[])
+        };
+        let linked = HashMap::from([(dough.id.clone(), dough)]);
+
+        let unscaled = PrintOptions::default().ingredients(&pizza, &linked);
+        assert_eq!(unscaled, pizza.ingredients);
+
+        let doubled = PrintOptions {
+            scale: 2.0,
+            units: UnitSystem::Metric,
+            ..Default::default()
+        }
+        .ingredients(&pizza, &linked);
+        assert_eq!(doubled.len(), 1);
+        assert_eq!(doubled[0].name, "Flour");
+        assert_eq!(doubled[0].amount, 1100.0);
+        assert_eq!(doubled[0].unit_type, "g");
+    }
+
+    #[test]
+    fn test_print_options() {
+        let options = PrintOptions {
+            scale: 2.0,
+            units: UnitSystem::Metric,
+            ..Default::default()
+        };
+        let ingredient = Ingredient {
+            name: "Milk".to_owned(),
+            amount: 0.5,
+            unit_type: "cup".to_owned(),
+            description: None,
+        };
+        assert_eq!(options.ingredient(&ingredient).amount, 237.0);
+        assert_eq!(options.ingredient(&ingredient).unit_type, "ml");
+        let section = Ingredient::section("Sauce");
+        assert_eq!(options.ingredient(&section), section);
+        assert_eq!(options.yields(3), 6);
+        assert_eq!(
+            PrintOptions {
+                scale: 0.5,
+                ..options.clone()
+            }
+            .yields(1),
+            1
+        );
+        assert_eq!(options.step_font_size(true), 110);
+        let smallest = PrintOptions {
+            font_size: PrintOptions::MIN_FONT_SIZE,
+            ..options
+        };
+        assert_eq!(smallest.step_font_size(false), PrintOptions::MIN_FONT_SIZE);
+    }
+}
@@ -0,0 +1,348 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::recipe::{Info, Ingredient, Recipe, Step, UpdateRecipe};
+
+/// Longest title the server accepts
+pub const MAX_TITLE_LEN: usize = 60;
+
+/// A part of a recipe the editor tracks changes for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum DraftField {
+    Title,
+    Info,
+    Description,
+    Notes,
+    Tags,
+    Labels,
+    Ingredients,
+    Steps,
+}
+
+impl DraftField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Info => "Info",
+            Self::Description => "Description",
+            Self::Notes => "Notes",
+            Self::Tags => "Tags",
+            Self::Labels => "Labels",
+            Self::Ingredients => "Ingredients",
+            Self::Steps => "Steps",
+        }
+    }
+}
+
+/// Why a draft can't be saved yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct DraftError {
+    pub field: DraftField,
+    pub message: String,
+}
+
+/// The editable parts of a recipe, as changed in the recipe editor
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeDraft {
+    pub title: String,
+    pub info: Info,
+    pub short_description: String,
+    pub long_description: String,
+    pub tags: Vec<String>,
+    pub labels: Vec<String>,
+    pub ingredients: Vec<Ingredient>,
+    pub steps: Vec<Step>,
+}
+
+impl From<&Recipe> for RecipeDraft {
+    fn from(recipe: &Recipe) -> Self {
+        let recipe = recipe.clone();
+        Self {
+            title: recipe.title,
+            info: recipe.info,
+            short_description: recipe.short_description.unwrap_or_default(),
+            long_description: recipe.long_description.unwrap_or_default(),
+            tags: recipe.tags,
+            labels: recipe.labels,
+            ingredients: recipe.ingredients,
+            steps: recipe.steps,
+        }
+    }
+}
+
+fn sorted(values: &[String]) -> Vec<&String> {
+    let mut values = values.iter().collect::<Vec<_>>();
+    values.sort();
+    values
+}
+
+impl RecipeDraft {
+    /// Fields that differ from the original, tags & labels are compared ignoring order
+    pub fn changed_fields(&self, original: &RecipeDraft) -> Vec<DraftField> {
+        [
+            (DraftField::Title, self.title != original.title),
+            (DraftField::Info, self.info != original.info),
+            (
+                DraftField::Description,
+                self.short_description != original.short_description,
+            ),
+            (
+                DraftField::Notes,
+                self.long_description != original.long_description,
+            ),
+            (
+                DraftField::Tags,
+                sorted(&self.tags) != sorted(&original.tags),
+            ),
+            (
+                DraftField::Labels,
+                sorted(&self.labels) != sorted(&original.labels),
+            ),
+            (
+                DraftField::Ingredients,
+                self.ingredients != original.ingredients,
+            ),
+            (DraftField::Steps, self.steps != original.steps),
+        ]
+        .into_iter()
+        .filter_map(|(field, changed)| changed.then_some(field))
+        .collect()
+    }
+
+    pub fn validate(&self) -> Vec<DraftError> {
+        let mut errors = Vec::new();
+        let mut error = |field, message: String| errors.push(DraftError { field, message });
+
+        if self.title.trim().is_empty() {
+            error(DraftField::Title, "Title is required".to_owned());
+        } else if self.title.chars().count() > MAX_TITLE_LEN {
+            error(
+                DraftField::Title,
+                format!("Title must be at most {MAX_TITLE_LEN} characters"),
+            );
+        }
+        if let Some(yields) = &self.info.yields {
+            if yields.value == 0 {
+                error(
+                    DraftField::Info,
+                    "Serving amount must be at least 1".to_owned(),
+                );
+            }
+            if yields.unit_type.trim().is_empty() {
+                error(DraftField::Info, "Serving type is required".to_owned());
+            }
+        }
+        let mut number = 0;
+        for ingredient in &self.ingredients {
+            if let Some(name) = ingredient.section_name() {
+                if name.trim().is_empty() {
+                    error(
+                        DraftField::Ingredients,
+                        "Ingredient sections need a name".to_owned(),
+                    );
+                }
+                continue;
+            }
+            number += 1;
+            if ingredient.name.trim().is_empty() {
+                error(
+                    DraftField::Ingredients,
+                    format!("Ingredient {number} needs a name"),
+                );
+            }
+            if ingredient.unit_type.trim().is_empty() {
+                error(
+                    DraftField::Ingredients,
+                    format!("Ingredient {number} needs a unit"),
+                );
+            }
+            if !ingredient.amount.is_finite() || ingredient.amount < 0.0 {
+                error(
+                    DraftField::Ingredients,
+                    format!("Ingredient {number} has an invalid amount"),
+                );
+            }
+        }
+        let mut number = 0;
+        for step in &self.steps {
+            if let Some(name) = step.section_name() {
+                if name.trim().is_empty() {
+                    error(DraftField::Steps, "Step groups need a name".to_owned());
+                }
+                continue;
+            }
+            number += 1;
+            if step.description.trim().is_empty() {
+                error(
+                    DraftField::Steps,
+                    format!("Step {number} needs a description"),
+                );
+            }
+        }
+        errors
+    }
+
+    /// An update holding only the fields changed from the original, if there are any.
+    /// Info can't be left out, so like the single field edits it is sent as the default when unchanged.
+    pub fn to_update(&self, original: &RecipeDraft) -> Option<UpdateRecipe> {
+        let changed = self.changed_fields(original);
+        if changed.is_empty() {
+            return None;
+        }
+        let draft = self.clone();
+        let has = |field| changed.contains(&field);
+        Some(UpdateRecipe {
+            title: has(DraftField::Title).then_some(draft.title),
+            info: if has(DraftField::Info) {
+                draft.info
+            } else {
+                Info::default()
+            },
+            short_description: has(DraftField::Description).then_some(draft.short_description),
+            long_description: has(DraftField::Notes).then_some(draft.long_description),
+            tags: has(DraftField::Tags).then_some(draft.tags),
+            labels: has(DraftField::Labels).then_some(draft.labels),
+            ingredients: has(DraftField::Ingredients)
+                .then(|| draft.ingredients.into_iter().map(Into::into).collect()),
+            steps: has(DraftField::Steps)
+                .then(|| draft.steps.into_iter().map(Into::into).collect()),
+        })
+    }
+
+    /// The recipe as it will be once the draft is saved
+    pub fn apply_to(&self, recipe: &Recipe) -> Recipe {
+        let draft = self.clone();
+        let non_empty = |v: String| (!v.is_empty()).then_some(v);
+        Recipe {
+            title: draft.title,
+            info: draft.info,
+            short_description: non_empty(draft.short_description),
+            long_description: non_empty(draft.long_description),
+            tags: draft.tags,
+            labels: draft.labels,
+            ingredients: draft.ingredients,
+            steps: draft.steps,
+            ..recipe.clone()
+        }
+    }
+}
+
+/// An unsaved draft, along with the version of the recipe it was started from
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredDraft {
+    pub saved_at: DateTime<Utc>,
+    pub base: RecipeDraft,
+    pub draft: RecipeDraft,
+}
+
+/// Unsaved recipe drafts by recipe id, kept by the client
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct RecipeDrafts(pub HashMap<String, StoredDraft>);
+
+#[cfg(test)]
+mod tests_draft {
+    use super::*;
+    use crate::recipe::InfoYields;
+
+    fn draft() -> RecipeDraft {
+        RecipeDraft {
+            title: "Pancakes".to_owned(),
+            tags: vec!["breakfast".to_owned(), "sweet".to_owned()],
+            ingredients: vec![Ingredient {
+                name: "Flour".to_owned(),
+                amount: 100.0,
+                unit_type: "g".to_owned(),
+                description: None,
+            }],
+            steps: vec![Step {
+                title: None,
+                description: "Mix".to_owned(),
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_changed_fields() {
+        let original = draft();
+        assert!(original.changed_fields(&original).is_empty());
+
+        let mut changed = original.clone();
+        changed.title = "Crepes".to_owned();
+        changed.tags.reverse();
+        changed.steps[0].description = "Whisk".to_owned();
+        assert_eq!(
+            changed.changed_fields(&original),
+            vec![DraftField::Title, DraftField::Steps]
+        );
+    }
+
+    #[test]
+    fn test_to_update() {
+        let original = draft();
+        assert!(original.to_update(&original).is_none());
+
+        let mut changed = original.clone();
+        changed.long_description = "Serve warm".to_owned();
+        changed.info.cook_time = 600;
+        let update = changed.to_update(&original).unwrap();
+        assert_eq!(update.long_description.as_deref(), Some("Serve warm"));
+        assert_eq!(update.info.cook_time, 600);
+        assert!(update.title.is_none());
+        assert!(update.ingredients.is_none());
+        assert!(update.steps.is_none());
+
+        let mut changed = original.clone();
+        changed.title = "Crepes".to_owned();
+        let update = changed.to_update(&original).unwrap();
+        assert_eq!(update.info, Info::default());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(draft().validate().is_empty());
+
+        let mut invalid = draft();
+        invalid.title = "  ".to_owned();
+        invalid.info.yields = Some(InfoYields {
+            value: 0,
+            unit_type: "servings".to_owned(),
+        });
+        invalid.ingredients[0].unit_type = String::new();
+        invalid.ingredients[0].amount = f32::NAN;
+        invalid.steps.push(Step::default());
+        let fields = invalid
+            .validate()
+            .into_iter()
+            .map(|v| v.field)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            fields,
+            vec![
+                DraftField::Title,
+                DraftField::Info,
+                DraftField::Ingredients,
+                DraftField::Ingredients,
+                DraftField::Steps,
+            ]
+        );
+
+        let mut sectioned = draft();
+        sectioned
+            .ingredients
+            .insert(0, Ingredient::section("Batter"));
+        sectioned.steps.insert(0, Step::section("Batter"));
+        assert!(sectioned.validate().is_empty());
+        sectioned.steps.insert(0, Step::section(""));
+        assert_eq!(sectioned.validate().len(), 1);
+
+        let mut long = draft();
+        long.title = "a".repeat(MAX_TITLE_LEN + 1);
+        assert_eq!(long.validate().len(), 1);
+    }
+}
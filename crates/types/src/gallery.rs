@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// An extra image of a recipe, kept by the client as the server only holds one (the cover)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GalleryImage {
+    /// key of the image in the client's image store
+    pub key: String,
+    /// number of the step the image belongs to (as shown, not counting groups),
+    /// none when it is of the whole recipe. Kept in step with the recipe by
+    /// [`RecipeGallery::renumber_steps`] when it is saved
+    pub step: Option<usize>,
+}
+
+/// Extra images of a recipe, in the order they are shown
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct RecipeGallery(pub Vec<GalleryImage>);
+
+impl RecipeGallery {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Images of the given step, or of the whole recipe for none
+    pub fn images_of(&self, step: Option<usize>) -> Vec<GalleryImage> {
+        self.0.iter().filter(|v| v.step == step).cloned().collect()
+    }
+
+    pub fn add(&mut self, key: String, step: Option<usize>) {
+        self.0.push(GalleryImage { key, step });
+    }
+
+    /// Remove an image, returning whether it existed
+    pub fn remove(&mut self, key: &str) -> bool {
+        let len = self.0.len();
+        self.0.retain(|v| v.key != key);
+        self.0.len() != len
+    }
+
+    pub fn set_step(&mut self, key: &str, step: Option<usize>) {
+        if let Some(image) = self.0.iter_mut().find(|v| v.key == key) {
+            image.step = step;
+        }
+    }
+
+    /// Move images to their step's new number once steps are reordered, added or removed,
+    /// `numbers` maps the old number of each kept step to its new one.
+    /// Images of steps that were removed are taken out & returned.
+    pub fn renumber_steps(&mut self, numbers: &HashMap<usize, usize>) -> Vec<GalleryImage> {
+        let mut removed = Vec::new();
+        self.0.retain_mut(|image| match image.step {
+            None => true,
+            Some(step) => match numbers.get(&step) {
+                Some(number) => {
+                    image.step = Some(*number);
+                    true
+                }
+                None => {
+                    removed.push(image.clone());
+                    false
+                }
+            },
+        });
+        removed
+    }
+
+    /// Swap an image with the one before (or after) it showing in the same place
+    pub fn move_image(&mut self, key: &str, earlier: bool) {
+        let Some(index) = self.0.iter().position(|v| v.key == key) else {
+            return;
+        };
+        let step = self.0[index].step;
+        let other = if earlier {
+            self.0[..index].iter().rposition(|v| v.step == step)
+        } else {
+            self.0[index + 1..]
+                .iter()
+                .position(|v| v.step == step)
+                .map(|v| v + index + 1)
+        };
+        if let Some(other) = other {
+            self.0.swap(index, other);
+        }
+    }
+
+    /// Replace an image with another, keeping its place (e.g. when swapped with the cover)
+    pub fn replace(&mut self, key: &str, new_key: String) {
+        if let Some(image) = self.0.iter_mut().find(|v| v.key == key) {
+            image.key = new_key;
+        }
+    }
+}
+
+/// Extra images by recipe id
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct RecipeGalleries(pub HashMap<String, RecipeGallery>);
+
+impl RecipeGalleries {
+    pub fn gallery(&self, id: &str) -> RecipeGallery {
+        self.0.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Replace a recipe's gallery, forgetting it when it becomes empty
+    pub fn set_gallery(&mut self, id: &str, gallery: RecipeGallery) {
+        if gallery.is_empty() {
+            self.0.remove(id);
+        } else {
+            self.0.insert(id.to_owned(), gallery);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_gallery {
+    use super::*;
+
+    fn gallery() -> RecipeGallery {
+        let mut gallery = RecipeGallery::default();
+        gallery.add("a".to_owned(), None);
+        gallery.add("b".to_owned(), Some(1));
+        gallery.add("c".to_owned(), None);
+        gallery.add("d".to_owned(), None);
+        gallery
+    }
+
+    fn keys(images: &[GalleryImage]) -> Vec<&str> {
+        images.iter().map(|v| v.key.as_str()).collect()
+    }
+
+    #[test]
+    fn test_images_of() {
+        let gallery = gallery();
+        assert_eq!(keys(&gallery.images_of(None)), vec!["a", "c", "d"]);
+        assert_eq!(keys(&gallery.images_of(Some(1))), vec!["b"]);
+        assert!(gallery.images_of(Some(2)).is_empty());
+    }
+
+    #[test]
+    fn test_move_image() {
+        let mut gallery = gallery();
+        gallery.move_image("c", true);
+        assert_eq!(keys(&gallery.images_of(None)), vec!["c", "a", "d"]);
+        gallery.move_image("c", true);
+        assert_eq!(keys(&gallery.images_of(None)), vec!["c", "a", "d"]);
+        gallery.move_image("a", false);
+        assert_eq!(keys(&gallery.images_of(None)), vec!["c", "d", "a"]);
+        gallery.move_image("b", false);
+        assert_eq!(keys(&gallery.images_of(Some(1))), vec!["b"]);
+    }
+
+    #[test]
+    fn test_renumber_steps() {
+        let mut gallery = gallery();
+        gallery.add("e".to_owned(), Some(2));
+        let removed = gallery.renumber_steps(&HashMap::from([(1, 3)]));
+        assert_eq!(keys(&removed), vec!["e"]);
+        assert_eq!(keys(&gallery.images_of(Some(3))), vec!["b"]);
+        assert_eq!(keys(&gallery.images_of(None)), vec!["a", "c", "d"]);
+    }
+
+    #[test]
+    fn test_edit() {
+        let mut gallery = gallery();
+        gallery.set_step("a", Some(1));
+        assert_eq!(keys(&gallery.images_of(Some(1))), vec!["a", "b"]);
+        gallery.replace("b", "e".to_owned());
+        assert_eq!(keys(&gallery.images_of(Some(1))), vec!["a", "e"]);
+        assert!(gallery.remove("e"));
+        assert!(!gallery.remove("e"));
+
+        let mut galleries = RecipeGalleries::default();
+        galleries.set_gallery("1", gallery);
+        assert_eq!(galleries.gallery("1").0.len(), 3);
+        galleries.set_gallery("1", RecipeGallery::default());
+        assert!(galleries.0.is_empty());
+    }
+}
@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::recipe::{Ingredient, Recipe, Step};
+
+/// How many revisions are kept for each recipe, oldest are dropped first
+pub const MAX_REVISIONS: usize = 20;
+/// How many revisions are kept across every recipe, the oldest (of any recipe) are dropped first
+pub const MAX_TOTAL_REVISIONS: usize = 200;
+
+/// A recipe as it was before being saved
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct RecipeRevision {
+    pub saved_at: DateTime<Utc>,
+    pub recipe: Recipe,
+}
+
+/// Previous versions of recipes, kept by the client, newest last
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct RecipeHistory(pub HashMap<String, Vec<RecipeRevision>>);
+
+impl RecipeHistory {
+    /// Remember a version of a recipe, unless it is the same as the last one remembered
+    pub fn push(&mut self, recipe: Recipe, saved_at: DateTime<Utc>) {
+        let revisions = self.0.entry(recipe.id.clone()).or_default();
+        if revisions.last().map(|v| &v.recipe) == Some(&recipe) {
+            return;
+        }
+        revisions.push(RecipeRevision { saved_at, recipe });
+        if revisions.len() > MAX_REVISIONS {
+            revisions.drain(..revisions.len() - MAX_REVISIONS);
+        }
+        while self.0.values().map(Vec::len).sum::<usize>() > MAX_TOTAL_REVISIONS {
+            self.remove_oldest();
+        }
+    }
+
+    /// Forget the oldest revision of any recipe
+    fn remove_oldest(&mut self) {
+        let oldest = self
+            .0
+            .iter()
+            .filter_map(|(id, revisions)| Some((revisions.first()?.saved_at, id.clone())))
+            .min();
+        if let Some((_, id)) = oldest {
+            let revisions = self.0.get_mut(&id).expect("id expected to exist");
+            revisions.remove(0);
+            if revisions.is_empty() {
+                self.0.remove(&id);
+            }
+        }
+    }
+
+    pub fn revisions(&self, id: &str) -> &[RecipeRevision] {
+        self.0.get(id).map(|v| v.as_slice()).unwrap_or_default()
+    }
+
+    pub fn remove(&mut self, id: &str) {
+        self.0.remove(id);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum DiffOp {
+    Same(usize, usize),
+    Removed(usize),
+    Added(usize),
+}
+
+/// Longest common subsequence diff, in order of the new list
+fn diff_ops<T: PartialEq>(old: &[T], new: &[T]) -> Vec<DiffOp> {
+    let mut lengths = vec![vec![0_usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            ops.push(DiffOp::Same(i, j));
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            ops.push(DiffOp::Removed(i));
+            i += 1;
+        } else {
+            ops.push(DiffOp::Added(j));
+            j += 1;
+        }
+    }
+    ops.extend((i..old.len()).map(DiffOp::Removed));
+    ops.extend((j..new.len()).map(DiffOp::Added));
+    ops
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TextPart {
+    Same(String),
+    Added(String),
+    Removed(String),
+}
+
+/// Split text into words, keeping the whitespace after each word
+fn words(text: &str) -> Vec<&str> {
+    let mut words = Vec::new();
+    let mut start = 0;
+    let mut in_space = false;
+    for (i, c) in text.char_indices() {
+        if in_space && !c.is_whitespace() {
+            words.push(&text[start..i]);
+            start = i;
+        }
+        in_space = c.is_whitespace();
+    }
+    if start < text.len() {
+        words.push(&text[start..]);
+    }
+    words
+}
+
+/// Word by word difference between two texts
+pub fn diff_text(old: &str, new: &str) -> Vec<TextPart> {
+    let (old, new) = (words(old), words(new));
+    let mut parts: Vec<TextPart> = Vec::new();
+    for op in diff_ops(&old, &new) {
+        let part = match op {
+            DiffOp::Same(i, _) => TextPart::Same(old[i].to_owned()),
+            DiffOp::Removed(i) => TextPart::Removed(old[i].to_owned()),
+            DiffOp::Added(j) => TextPart::Added(new[j].to_owned()),
+        };
+        // join runs of the same kind, so they read as one change
+        match (parts.last_mut(), part) {
+            (Some(TextPart::Same(a)), TextPart::Same(b))
+            | (Some(TextPart::Added(a)), TextPart::Added(b))
+            | (Some(TextPart::Removed(a)), TextPart::Removed(b)) => a.push_str(&b),
+            (_, part) => parts.push(part),
+        }
+    }
+    parts
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum RecipeChange {
+    /// a text field, such as the title or notes
+    Text {
+        field: &'static str,
+        diff: Vec<TextPart>,
+    },
+    /// a value from the info, shown as text
+    Info {
+        field: &'static str,
+        from: String,
+        to: String,
+    },
+    /// tags or labels
+    Names {
+        field: &'static str,
+        added: Vec<String>,
+        removed: Vec<String>,
+    },
+    IngredientAdded(Ingredient),
+    IngredientRemoved(Ingredient),
+    IngredientChanged {
+        from: Ingredient,
+        to: Ingredient,
+    },
+    StepAdded {
+        number: usize,
+        step: Step,
+    },
+    StepRemoved {
+        number: usize,
+        step: Step,
+    },
+    StepChanged {
+        number: usize,
+        diff: Vec<TextPart>,
+    },
+}
+
+fn diff_optional_text(
+    field: &'static str,
+    old: &Option<String>,
+    new: &Option<String>,
+) -> Option<RecipeChange> {
+    let (old, new) = (
+        old.as_deref().unwrap_or_default(),
+        new.as_deref().unwrap_or_default(),
+    );
+    (old != new).then(|| RecipeChange::Text {
+        field,
+        diff: diff_text(old, new),
+    })
+}
+
+fn diff_names(field: &'static str, old: &[String], new: &[String]) -> Option<RecipeChange> {
+    let added = new
+        .iter()
+        .filter(|v| !old.contains(v))
+        .cloned()
+        .collect::<Vec<_>>();
+    let removed = old
+        .iter()
+        .filter(|v| !new.contains(v))
+        .cloned()
+        .collect::<Vec<_>>();
+    (!added.is_empty() || !removed.is_empty()).then_some(RecipeChange::Names {
+        field,
+        added,
+        removed,
+    })
+}
+
+fn diff_ingredients(old: &[Ingredient], new: &[Ingredient]) -> Vec<RecipeChange> {
+    let mut changes = Vec::new();
+    for ingredient in new {
+        match old.iter().find(|v| v.name == ingredient.name) {
+            None => changes.push(RecipeChange::IngredientAdded(ingredient.clone())),
+            Some(from) if from != ingredient => changes.push(RecipeChange::IngredientChanged {
+                from: from.clone(),
+                to: ingredient.clone(),
+            }),
+            Some(_) => {}
+        }
+    }
+    for ingredient in old {
+        if !new.iter().any(|v| v.name == ingredient.name) {
+            changes.push(RecipeChange::IngredientRemoved(ingredient.clone()));
+        }
+    }
+    changes
+}
+
+fn step_text(step: &Step) -> String {
+    match &step.title {
+        Some(title) => format!("{title}: {}", step.description),
+        None => step.description.clone(),
+    }
+}
+
+fn diff_steps(old: &[Step], new: &[Step]) -> Vec<RecipeChange> {
+    let mut changes = Vec::new();
+    // removed & added steps next to each other are treated as the step being edited
+    let mut removed = Vec::new();
+    let flush = |removed: &mut Vec<usize>, changes: &mut Vec<RecipeChange>| {
+        changes.extend(removed.drain(..).map(|i| RecipeChange::StepRemoved {
+            number: i + 1,
+            step: old[i].clone(),
+        }));
+    };
+    for op in diff_ops(old, new) {
+        match op {
+            DiffOp::Same(..) => flush(&mut removed, &mut changes),
+            DiffOp::Removed(i) => removed.push(i),
+            DiffOp::Added(j) => {
+                if removed.is_empty() {
+                    changes.push(RecipeChange::StepAdded {
+                        number: j + 1,
+                        step: new[j].clone(),
+                    });
+                } else {
+                    let i = removed.remove(0);
+                    changes.push(RecipeChange::StepChanged {
+                        number: j + 1,
+                        diff: diff_text(&step_text(&old[i]), &step_text(&new[j])),
+                    });
+                }
+            }
+        }
+    }
+    flush(&mut removed, &mut changes);
+    changes
+}
+
+/// Field by field changes needed to turn one version of a recipe into another
+pub fn diff_recipes(old: &Recipe, new: &Recipe) -> Vec<RecipeChange> {
+    let mut changes = Vec::new();
+    if old.title != new.title {
+        changes.push(RecipeChange::Text {
+            field: "Title",
+            diff: diff_text(&old.title, &new.title),
+        });
+    }
+    let info = |recipe: &Recipe| {
+        let info = &recipe.info;
+        [
+            (
+                "Servings",
+                info.yields
+                    .as_ref()
+                    .map(|v| format!("{} {}", v.value, v.unit_type))
+                    .unwrap_or_default(),
+            ),
+            ("Prep Time (secs)", info.prep_time.to_string()),
+            ("Cook Time (secs)", info.cook_time.to_string()),
+            ("Freezable", info.freezable.to_string()),
+            ("Microwave Only", info.microwave_only.to_string()),
+        ]
+    };
+    for ((field, from), (_, to)) in info(old).into_iter().zip(info(new)) {
+        if from != to {
+            changes.push(RecipeChange::Info { field, from, to });
+        }
+    }
+    changes.extend(diff_optional_text(
+        "Source",
+        &old.info.source,
+        &new.info.source,
+    ));
+    changes.extend(diff_optional_text(
+        "Description",
+        &old.short_description,
+        &new.short_description,
+    ));
+    changes.extend(diff_optional_text(
+        "Notes",
+        &old.long_description,
+        &new.long_description,
+    ));
+    changes.extend(diff_names("Tags", &old.tags, &new.tags));
+    changes.extend(diff_names("Labels", &old.labels, &new.labels));
+    changes.extend(diff_ingredients(&old.ingredients, &new.ingredients));
+    changes.extend(diff_steps(&old.steps, &new.steps));
+    changes
+}
+
+#[cfg(test)]
+mod tests_history {
+    use super::*;
+    use crate::recipe::Info;
+
+    fn recipe(title: &str, ingredients: &[(&str, f32)], steps: &[&str]) -> Recipe {
+        Recipe {
+            id: "1".to_owned(),
+            owner_id: "owner".to_owned(),
+            title: title.to_owned(),
+            info: Info::default(),
+            short_description: None,
+            long_description: None,
+            tags: vec![],
+            ingredients: ingredients
+                .iter()
+                .map(|(name, amount)| Ingredient {
+                    name: name.to_string(),
+                    amount: *amount,
+                    ..Default::default()
+                })
+                .collect(),
+            steps: steps
+                .iter()
+                .map(|v| Step {
+                    title: None,
+                    description: v.to_string(),
+                })
+                .collect(),
+            labels: vec![],
+            image_id: None,
+        }
+    }
+
+    #[test]
+    fn test_diff_text() {
+        assert_eq!(
+            diff_text("Bake for 20 minutes", "Bake for 25 minutes"),
+            vec![
+                TextPart::Same("Bake for ".to_owned()),
+                TextPart::Removed("20 ".to_owned()),
+                TextPart::Added("25 ".to_owned()),
+                TextPart::Same("minutes".to_owned()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_ingredients() {
+        let old = recipe("Hummus", &[("Chickpeas", 400.0), ("Garlic", 1.0)], &[]);
+        let new = recipe("Hummus", &[("Chickpeas", 800.0), ("Tahini", 2.0)], &[]);
+        let changes = diff_recipes(&old, &new);
+        assert_eq!(changes.len(), 3);
+        assert!(
+            matches!(&changes[0], RecipeChange::IngredientChanged { to, .. } if to.amount == 800.0)
+        );
+        assert!(matches!(&changes[1], RecipeChange::IngredientAdded(v) if v.name == "Tahini"));
+        assert!(matches!(&changes[2], RecipeChange::IngredientRemoved(v) if v.name == "Garlic"));
+    }
+
+    #[test]
+    fn test_diff_steps() {
+        let old = recipe("Hummus", &[], &["Drain", "Blend", "Serve"]);
+        let new = recipe("Hummus", &[], &["Drain", "Blend well", "Serve", "Enjoy"]);
+        let changes = diff_recipes(&old, &new);
+        assert_eq!(changes.len(), 2);
+        assert!(matches!(
+            &changes[0],
+            RecipeChange::StepChanged { number: 2, .. }
+        ));
+        assert!(matches!(
+            &changes[1],
+            RecipeChange::StepAdded { number: 4, .. }
+        ));
+
+        let changes = diff_recipes(&new, &old);
+        assert!(matches!(
+            &changes[1],
+            RecipeChange::StepRemoved { number: 4, .. }
+        ));
+        assert!(diff_recipes(&old, &old).is_empty());
+    }
+
+    #[test]
+    fn test_history_push() {
+        let mut history = RecipeHistory::default();
+        let now = Utc::now();
+        history.push(recipe("A", &[], &[]), now);
+        // saving the same version twice is only remembered once
+        history.push(recipe("A", &[], &[]), now);
+        assert_eq!(history.revisions("1").len(), 1);
+        for i in 0..MAX_REVISIONS + 5 {
+            history.push(recipe(&i.to_string(), &[], &[]), now);
+        }
+        let revisions = history.revisions("1");
+        assert_eq!(revisions.len(), MAX_REVISIONS);
+        assert_eq!(
+            revisions.last().unwrap().recipe.title,
+            (MAX_REVISIONS + 4).to_string()
+        );
+    }
+
+    #[test]
+    fn test_history_total() {
+        let mut history = RecipeHistory::default();
+        let start = Utc::now();
+        let recipes = MAX_TOTAL_REVISIONS / MAX_REVISIONS + 1;
+        for i in 0..recipes * MAX_REVISIONS {
+            let recipe = Recipe {
+                id: (i % recipes).to_string(),
+                ..recipe(&i.to_string(), &[], &[])
+            };
+            history.push(recipe, start + chrono::Duration::seconds(i as i64));
+        }
+        let total = history.0.values().map(Vec::len).sum::<usize>();
+        assert_eq!(total, MAX_TOTAL_REVISIONS);
+        // the oldest revision left is the first one after those dropped
+        let oldest = history
+            .0
+            .values()
+            .filter_map(|v| v.first())
+            .min_by_key(|v| v.saved_at);
+        assert_eq!(
+            oldest.unwrap().recipe.title,
+            (recipes * MAX_REVISIONS - MAX_TOTAL_REVISIONS).to_string()
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+/// Most placeholders to keep, the oldest are forgotten first.
+/// Each thumbnail is around a kilobyte, so this stays well within browser storage.
+pub const MAX_IMAGE_PLACEHOLDERS: usize = 300;
+
+pub fn recipe_image_url(media_url: &str, image_id: &str) -> String {
+    format!("{media_url}/recipe-image/{image_id}")
+}
+
+/// Shown while an image loads, made from the image the first time it is seen
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagePlaceholder {
+    /// css colour, the average of the image
+    pub color: String,
+    /// data url of a tiny copy of the image, shown blurred
+    pub thumbnail: String,
+}
+
+/// Placeholders by image id, kept by the client
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ImagePlaceholders {
+    placeholders: HashMap<String, ImagePlaceholder>,
+    /// image ids, oldest first
+    order: Vec<String>,
+}
+
+impl ImagePlaceholders {
+    pub fn get(&self, image_id: &str) -> Option<&ImagePlaceholder> {
+        self.placeholders.get(image_id)
+    }
+
+    pub fn insert(&mut self, image_id: &str, placeholder: ImagePlaceholder) {
+        if self
+            .placeholders
+            .insert(image_id.to_owned(), placeholder)
+            .is_none()
+        {
+            self.order.push(image_id.to_owned());
+        }
+        while self.order.len() > MAX_IMAGE_PLACEHOLDERS {
+            let oldest = self.order.remove(0);
+            self.placeholders.remove(&oldest);
+        }
+    }
+}
+
+/// Average colour of RGBA pixels as a css hex colour, ignoring transparent pixels
+pub fn average_color(rgba: &[u8]) -> String {
+    let (mut totals, mut count) = ([0u64; 3], 0u64);
+    for pixel in rgba.as_chunks::<4>().0.iter().filter(|v| v[3] > 0) {
+        for (total, value) in totals.iter_mut().zip(pixel) {
+            *total += *value as u64;
+        }
+        count += 1;
+    }
+    let [r, g, b] = totals.map(|v| v.checked_div(count).unwrap_or_default());
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+#[cfg(test)]
+mod tests_image {
+    use super::*;
+
+    #[test]
+    fn test_average_color() {
+        assert_eq!(average_color(&[]), "#000000");
+        assert_eq!(
+            average_color(&[255, 0, 0, 255, 0, 0, 255, 255, 9, 9, 9, 0]),
+            "#7f007f"
+        );
+    }
+
+    #[test]
+    fn test_placeholders() {
+        let placeholder = |color: &str| ImagePlaceholder {
+            color: color.to_owned(),
+            thumbnail: String::new(),
+        };
+        let mut placeholders = ImagePlaceholders::default();
+        for i in 0..MAX_IMAGE_PLACEHOLDERS {
+            placeholders.insert(&i.to_string(), placeholder("#000000"));
+        }
+        placeholders.insert("0", placeholder("#ffffff"));
+        assert_eq!(placeholders.get("0").unwrap().color, "#ffffff");
+        placeholders.insert("new", placeholder("#000000"));
+        assert!(placeholders.get("0").is_none());
+        assert!(placeholders.get("1").is_some());
+        assert!(placeholders.get("new").is_some());
+    }
+}
@@ -0,0 +1,430 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{pantry::Item, recipe::Recipe};
+
+// guards against parent loops, labels are not expected to be nested this deep
+const MAX_LABEL_DEPTH: usize = 16;
+
+/// How many recipes and pantry items use a label
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct LabelUsage {
+    pub recipes: usize,
+    pub pantry_items: usize,
+}
+
+/// Count label usage across recipes and pantry items, sorted by label.
+pub fn count_label_usage(recipes: &[Recipe], items: &[Item]) -> BTreeMap<String, LabelUsage> {
+    let mut usage = BTreeMap::<String, LabelUsage>::new();
+    for recipe in recipes {
+        for label in HashSet::<&String>::from_iter(&recipe.labels) {
+            usage.entry(label.clone()).or_default().recipes += 1;
+        }
+    }
+    for item in items {
+        for label in HashSet::<&String>::from_iter(&item.labels) {
+            usage.entry(label.clone()).or_default().pantry_items += 1;
+        }
+    }
+    usage
+}
+
+/// Replace labels everywhere they are used.
+///
+/// Covers renaming (one label), merging (many labels) and deleting (no replacement).
+#[derive(Debug, Clone, PartialEq)]
+pub struct LabelChange {
+    pub from: HashSet<String>,
+    pub to: Option<String>,
+}
+
+impl LabelChange {
+    /// Apply to a list of labels, returning the new labels if anything changed.
+    ///
+    /// The replacement takes the position of the first replaced label and is never duplicated.
+    pub fn apply(&self, labels: &[String]) -> Option<Vec<String>> {
+        if !labels.iter().any(|label| self.from.contains(label)) {
+            return None;
+        }
+        let mut new_labels: Vec<String> = Vec::with_capacity(labels.len());
+        for label in labels {
+            let label = match self.from.contains(label) {
+                true => match &self.to {
+                    Some(to) => to,
+                    None => continue,
+                },
+                false => label,
+            };
+            if !new_labels.contains(label) {
+                new_labels.push(label.clone());
+            }
+        }
+        (new_labels != labels).then_some(new_labels)
+    }
+
+    /// Recipes that would change, with their new labels
+    pub fn plan_recipes(&self, recipes: &[Recipe]) -> Vec<(Recipe, Vec<String>)> {
+        recipes
+            .iter()
+            .filter_map(|recipe| Some((recipe.clone(), self.apply(&recipe.labels)?)))
+            .collect()
+    }
+
+    /// Pantry items that would change, with their new labels
+    pub fn plan_items(&self, items: &[Item]) -> Vec<(Item, Vec<String>)> {
+        items
+            .iter()
+            .filter_map(|item| Some((item.clone(), self.apply(&item.labels)?)))
+            .collect()
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LabelColor {
+    Primary,
+    Secondary,
+    Accent,
+    Neutral,
+    Info,
+    Success,
+    Warning,
+    Error,
+}
+
+impl LabelColor {
+    pub const ALL: [Self; 8] = [
+        Self::Primary,
+        Self::Secondary,
+        Self::Accent,
+        Self::Neutral,
+        Self::Info,
+        Self::Success,
+        Self::Warning,
+        Self::Error,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Primary => "Primary",
+            Self::Secondary => "Secondary",
+            Self::Accent => "Accent",
+            Self::Neutral => "Neutral",
+            Self::Info => "Info",
+            Self::Success => "Success",
+            Self::Warning => "Warning",
+            Self::Error => "Error",
+        }
+    }
+}
+
+/// Display settings for a label
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct LabelMeta {
+    pub color: Option<LabelColor>,
+    /// emoji or short text shown before the label
+    pub icon: Option<String>,
+    /// when not set, the parent is taken from the name (e.g. "diet/vegan")
+    pub parent: Option<String>,
+}
+
+/// Display settings for every label of an account, keyed by label.
+/// The server has nowhere to keep these, so they are kept per account in each browser
+/// and are not shared between devices.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(transparent)]
+pub struct LabelMetadata(pub HashMap<String, LabelMeta>);
+
+impl LabelMetadata {
+    pub fn get(&self, label: &str) -> LabelMeta {
+        self.0.get(label).cloned().unwrap_or_default()
+    }
+
+    /// Set a label's metadata, removing it when everything is default
+    pub fn set(&mut self, label: &str, meta: LabelMeta) {
+        if meta == LabelMeta::default() {
+            self.0.remove(label);
+        } else {
+            self.0.insert(label.to_owned(), meta);
+        }
+    }
+
+    pub fn parent(&self, label: &str) -> Option<String> {
+        match self.0.get(label).and_then(|v| v.parent.clone()) {
+            Some(parent) => Some(parent),
+            None => label
+                .rsplit_once('/')
+                .map(|(parent, _)| parent.to_owned())
+                .filter(|v| !v.is_empty()),
+        }
+    }
+
+    /// Parents of a label, nearest first
+    pub fn ancestors(&self, label: &str) -> Vec<String> {
+        let mut ancestors: Vec<String> = Vec::new();
+        let mut current = label.to_owned();
+        while let Some(parent) = self.parent(&current) {
+            if parent == label || ancestors.contains(&parent) || ancestors.len() == MAX_LABEL_DEPTH
+            {
+                break;
+            }
+            ancestors.push(parent.clone());
+            current = parent;
+        }
+        ancestors
+    }
+
+    /// The label's colour, or the nearest parent's colour
+    pub fn color(&self, label: &str) -> Option<LabelColor> {
+        std::iter::once(label.to_owned())
+            .chain(self.ancestors(label))
+            .find_map(|v| self.0.get(&v).and_then(|meta| meta.color))
+    }
+
+    pub fn icon(&self, label: &str) -> Option<String> {
+        self.0
+            .get(label)
+            .and_then(|v| v.icon.clone())
+            .filter(|v| !v.is_empty())
+    }
+
+    /// Known labels plus any parents they imply
+    pub fn with_parents(&self, labels: &HashSet<String>) -> HashSet<String> {
+        labels
+            .iter()
+            .flat_map(|label| std::iter::once(label.clone()).chain(self.ancestors(label)))
+            .collect()
+    }
+
+    pub fn has_children(&self, label: &str, known: &[String]) -> bool {
+        known
+            .iter()
+            .any(|v| self.ancestors(v).iter().any(|parent| parent == label))
+    }
+
+    /// Whether labels match every selected label, either directly or through a child label
+    pub fn matches(&self, selected: &HashSet<String>, labels: &[String]) -> bool {
+        selected.iter().all(|wanted| {
+            labels
+                .iter()
+                .any(|label| label == wanted || self.ancestors(label).contains(wanted))
+        })
+    }
+
+    /// Selected labels that the server can filter on,
+    /// labels with children are left out as they have to be matched with [`Self::matches`].
+    pub fn server_labels(&self, selected: &HashSet<String>, known: &[String]) -> HashSet<String> {
+        selected
+            .iter()
+            .filter(|label| !self.has_children(label, known))
+            .cloned()
+            .collect()
+    }
+
+    /// Keep metadata following a rename/merge, dropping it for deleted labels
+    pub fn apply_change(&mut self, change: &LabelChange) {
+        let mut moved = None;
+        for label in &change.from {
+            if Some(label) == change.to.as_ref() {
+                continue;
+            }
+            if let Some(meta) = self.0.remove(label) {
+                moved.get_or_insert(meta);
+            }
+        }
+        if let (Some(to), Some(meta)) = (&change.to, moved) {
+            self.0.entry(to.clone()).or_insert(meta);
+        }
+        for meta in self.0.values_mut() {
+            if meta
+                .parent
+                .as_ref()
+                .is_some_and(|v| change.from.contains(v))
+            {
+                meta.parent = change.to.clone();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests_label_change {
+    use super::*;
+
+    fn labels(v: &[&str]) -> Vec<String> {
+        v.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn change(from: &[&str], to: Option<&str>) -> LabelChange {
+        LabelChange {
+            from: HashSet::from_iter(labels(from)),
+            to: to.map(|v| v.to_owned()),
+        }
+    }
+
+    #[test]
+    fn test_rename() {
+        let change = change(&["vegatarian"], Some("vegetarian"));
+        assert_eq!(
+            change.apply(&labels(&["quick", "vegatarian", "cheap"])),
+            Some(labels(&["quick", "vegetarian", "cheap"]))
+        );
+        assert_eq!(change.apply(&labels(&["quick"])), None);
+    }
+
+    #[test]
+    fn test_merge_does_not_duplicate() {
+        let change = change(&["veg", "veggie"], Some("vegetarian"));
+        assert_eq!(
+            change.apply(&labels(&["veggie", "quick", "veg", "vegetarian"])),
+            Some(labels(&["vegetarian", "quick"]))
+        );
+    }
+
+    #[test]
+    fn test_delete() {
+        let change = change(&["old"], None);
+        assert_eq!(
+            change.apply(&labels(&["old", "new"])),
+            Some(labels(&["new"]))
+        );
+    }
+
+    #[test]
+    fn test_rename_to_itself_is_unchanged() {
+        let change = change(&["same"], Some("same"));
+        assert_eq!(change.apply(&labels(&["same"])), None);
+    }
+
+    #[test]
+    fn test_count_label_usage() {
+        let item = Item {
+            id: "1".to_owned(),
+            name: "beans".to_owned(),
+            location_id: "a".to_owned(),
+            quantity: Default::default(),
+            notes: None,
+            expiry: None,
+            labels: labels(&["tins", "vegan"]),
+        };
+        let usage = count_label_usage(&[], &[item.clone(), item]);
+        assert_eq!(
+            usage.get("vegan"),
+            Some(&LabelUsage {
+                recipes: 0,
+                pantry_items: 2
+            })
+        );
+        assert_eq!(usage.len(), 2);
+    }
+}
+
+#[cfg(test)]
+mod tests_label_metadata {
+    use super::*;
+
+    fn labels(v: &[&str]) -> Vec<String> {
+        v.iter().map(|v| v.to_string()).collect()
+    }
+
+    fn metadata() -> LabelMetadata {
+        let mut metadata = LabelMetadata::default();
+        metadata.set(
+            "diet",
+            LabelMeta {
+                color: Some(LabelColor::Success),
+                ..Default::default()
+            },
+        );
+        metadata.set(
+            "vegan",
+            LabelMeta {
+                parent: Some("vegetarian".to_owned()),
+                icon: Some("🌱".to_owned()),
+                ..Default::default()
+            },
+        );
+        metadata.set(
+            "vegetarian",
+            LabelMeta {
+                parent: Some("diet".to_owned()),
+                ..Default::default()
+            },
+        );
+        metadata
+    }
+
+    #[test]
+    fn test_parent_from_name() {
+        let metadata = LabelMetadata::default();
+        assert_eq!(metadata.parent("diet/vegan").as_deref(), Some("diet"));
+        assert_eq!(metadata.ancestors("a/b/c"), labels(&["a/b", "a"]));
+        assert_eq!(metadata.parent("diet"), None);
+    }
+
+    #[test]
+    fn test_ancestors_and_colour_inherit() {
+        let metadata = metadata();
+        assert_eq!(metadata.ancestors("vegan"), labels(&["vegetarian", "diet"]));
+        assert_eq!(metadata.color("vegan"), Some(LabelColor::Success));
+        assert_eq!(metadata.color("other"), None);
+        assert_eq!(metadata.icon("vegan").as_deref(), Some("🌱"));
+    }
+
+    #[test]
+    fn test_parent_loop() {
+        let mut metadata = LabelMetadata::default();
+        for (label, parent) in [("a", "b"), ("b", "a")] {
+            metadata.set(
+                label,
+                LabelMeta {
+                    parent: Some(parent.to_owned()),
+                    ..Default::default()
+                },
+            );
+        }
+        assert_eq!(metadata.ancestors("a"), labels(&["b"]));
+    }
+
+    #[test]
+    fn test_matches_children() {
+        let metadata = metadata();
+        let selected = HashSet::from(["diet".to_owned()]);
+        assert!(metadata.matches(&selected, &labels(&["vegan"])));
+        assert!(metadata.matches(&selected, &labels(&["diet"])));
+        assert!(!metadata.matches(&selected, &labels(&["quick"])));
+        let selected = HashSet::from(["vegetarian".to_owned(), "quick".to_owned()]);
+        assert!(metadata.matches(&selected, &labels(&["vegan", "quick"])));
+        assert!(!metadata.matches(&selected, &labels(&["vegan"])));
+    }
+
+    #[test]
+    fn test_server_labels() {
+        let metadata = metadata();
+        let known = labels(&["vegan", "quick", "tins/beans"]);
+        let selected = HashSet::from_iter(labels(&["diet", "quick", "tins"]));
+        assert_eq!(
+            metadata.server_labels(&selected, &known),
+            HashSet::from(["quick".to_owned()])
+        );
+    }
+
+    #[test]
+    fn test_apply_change() {
+        let mut metadata = metadata();
+        metadata.apply_change(&LabelChange {
+            from: HashSet::from(["vegetarian".to_owned()]),
+            to: Some("veggie".to_owned()),
+        });
+        assert_eq!(metadata.get("veggie").parent.as_deref(), Some("diet"));
+        assert_eq!(metadata.get("vegan").parent.as_deref(), Some("veggie"));
+        metadata.apply_change(&LabelChange {
+            from: HashSet::from(["diet".to_owned()]),
+            to: None,
+        });
+        assert_eq!(metadata.get("diet"), LabelMeta::default());
+        assert_eq!(metadata.get("veggie").parent, None);
+    }
+}
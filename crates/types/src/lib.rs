@@ -1,10 +1,22 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
+pub mod collection;
+pub mod cookbook;
+pub mod draft;
+pub mod gallery;
+pub mod history;
+pub mod image;
+pub mod label;
+pub mod markdown;
+pub mod page;
 pub mod pantry;
 pub mod query;
 pub mod recipe;
+pub mod search;
 pub mod stats;
+pub mod sub_recipe;
+pub mod units;
 pub mod user;
 
 use std::str::FromStr;
@@ -34,6 +46,9 @@ pub struct LoginToken {
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct StoredLogin {
+    /// empty for logins stored before it was recorded, which have to log in again
+    #[serde(default)]
+    pub username: String,
     pub api_url: String,
     pub media_url: String,
     pub token: LoginToken,
@@ -46,7 +61,7 @@ pub struct SelectedUpdate<T> {
     pub model: T,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct ApiInfo {
     pub api_version_major: usize,
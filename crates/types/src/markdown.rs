@@ -0,0 +1,477 @@
+/// Inline Markdown content, text is kept as-is and escaped when rendered
+#[derive(Debug, Clone, PartialEq)]
+pub enum Inline {
+    Text(String),
+    Strong(Vec<Inline>),
+    Emphasis(Vec<Inline>),
+    Code(String),
+    /// only links with a safe url, see [`safe_url`]
+    Link {
+        content: Vec<Inline>,
+        url: String,
+    },
+    LineBreak,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Block {
+    Paragraph(Vec<Inline>),
+    Heading {
+        level: usize,
+        content: Vec<Inline>,
+    },
+    List {
+        /// the first number of an ordered list
+        start: Option<usize>,
+        items: Vec<Vec<Inline>>,
+    },
+    Quote(Vec<Block>),
+    Code(String),
+    Rule,
+}
+
+const SAFE_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+
+/// The url when it is relative or uses a safe scheme, e.g. never `javascript:`.
+/// Browsers ignore whitespace & control characters within urls, so those are removed first.
+pub fn safe_url(url: &str) -> Option<String> {
+    let url = url
+        .chars()
+        .filter(|c| !c.is_whitespace() && !c.is_control())
+        .collect::<String>();
+    if url.is_empty() {
+        return None;
+    }
+    // anything before a colon that could be a scheme is one, otherwise the url is relative
+    let scheme = url.find(':').map(|end| &url[..end]).filter(|scheme| {
+        scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+            && scheme
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+    });
+    match scheme {
+        Some(scheme) => SAFE_SCHEMES
+            .contains(&scheme.to_ascii_lowercase().as_str())
+            .then_some(url),
+        None => Some(url),
+    }
+}
+
+fn push_text(inlines: &mut Vec<Inline>, text: &str) {
+    if let Some(Inline::Text(last)) = inlines.last_mut() {
+        last.push_str(text);
+    } else if !text.is_empty() {
+        inlines.push(Inline::Text(text.to_owned()));
+    }
+}
+
+/// Position of a closing delimiter, which can't directly follow whitespace
+fn find_closing(chars: &[char], from: usize, delimiter: &[char]) -> Option<usize> {
+    (from..chars.len()).find(|&i| {
+        chars[i..].starts_with(delimiter)
+            && i > from
+            && !chars[i - 1].is_whitespace()
+            // `_` only works on word boundaries, so snake_case stays as written
+            && (delimiter[0] != '_'
+                || chars
+                    .get(i + delimiter.len())
+                    .is_none_or(|c| !c.is_alphanumeric()))
+    })
+}
+
+fn parse_inline_chars(chars: &[char]) -> Vec<Inline> {
+    let mut inlines = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        let rest = &chars[i..];
+        match c {
+            '\\' if chars.get(i + 1).is_some_and(|c| c.is_ascii_punctuation()) => {
+                push_text(&mut inlines, &chars[i + 1].to_string());
+                i += 2;
+                continue;
+            }
+            '\n' => {
+                inlines.push(Inline::LineBreak);
+                i += 1;
+                continue;
+            }
+            '`' => {
+                if let Some(end) = (i + 1..chars.len()).find(|&j| chars[j] == '`') {
+                    inlines.push(Inline::Code(chars[i + 1..end].iter().collect()));
+                    i = end + 1;
+                    continue;
+                }
+            }
+            '*' | '_' => {
+                let is_start = c == '*' || i == 0 || !chars[i - 1].is_alphanumeric();
+                let strong = rest.len() > 1 && rest[1] == c;
+                let delimiter = if strong { &rest[..2] } else { &rest[..1] };
+                let inner_start = i + delimiter.len();
+                let opens = is_start && chars.get(inner_start).is_some_and(|c| !c.is_whitespace());
+                if let Some(end) = opens
+                    .then(|| find_closing(chars, inner_start, delimiter))
+                    .flatten()
+                {
+                    let content = parse_inline_chars(&chars[inner_start..end]);
+                    inlines.push(if strong {
+                        Inline::Strong(content)
+                    } else {
+                        Inline::Emphasis(content)
+                    });
+                    i = end + delimiter.len();
+                    continue;
+                }
+            }
+            '[' => {
+                if let Some((content, url, end)) = parse_link(chars, i) {
+                    let content = parse_inline_chars(&content);
+                    match safe_url(&url) {
+                        Some(url) => inlines.push(Inline::Link { content, url }),
+                        // unsafe links keep their text, without linking anywhere
+                        None => content.into_iter().for_each(|v| match v {
+                            Inline::Text(text) => push_text(&mut inlines, &text),
+                            v => inlines.push(v),
+                        }),
+                    }
+                    i = end;
+                    continue;
+                }
+            }
+            _ => {}
+        }
+        push_text(&mut inlines, &c.to_string());
+        i += 1;
+    }
+    inlines
+}
+
+/// `[content](url)` starting at `start`, giving the content, url & where the link ends
+fn parse_link(chars: &[char], start: usize) -> Option<(Vec<char>, String, usize)> {
+    let mut depth = 0;
+    let close = (start..chars.len()).find(|&i| {
+        match chars[i] {
+            '[' => depth += 1,
+            ']' => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+    })?;
+    if chars.get(close + 1) != Some(&'(') {
+        return None;
+    }
+    // urls can hold balanced brackets, e.g. wikipedia links
+    let mut depth = 1;
+    let url_end = (close + 2..chars.len()).find(|&i| {
+        match chars[i] {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => {}
+        }
+        depth == 0
+    })?;
+    Some((
+        chars[start + 1..close].to_vec(),
+        chars[close + 2..url_end].iter().collect(),
+        url_end + 1,
+    ))
+}
+
+pub fn parse_inline(text: &str) -> Vec<Inline> {
+    parse_inline_chars(&text.chars().collect::<Vec<_>>())
+}
+
+fn heading(line: &str) -> Option<(usize, &str)> {
+    let level = line.chars().take_while(|c| *c == '#').count();
+    let rest = &line[level..];
+    ((1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')))
+        .then(|| (level, rest.trim()))
+}
+
+fn is_rule(line: &str) -> bool {
+    let line = line.replace(' ', "");
+    line.len() >= 3
+        && ["-", "*", "_"]
+            .iter()
+            .any(|c| line.chars().all(|v| v.to_string() == *c))
+}
+
+/// A list item's number (for ordered lists) & content
+fn list_item(line: &str) -> Option<(Option<usize>, &str)> {
+    let line = line.trim_start();
+    for marker in ["- ", "* ", "+ "] {
+        if let Some(rest) = line.strip_prefix(marker) {
+            return Some((None, rest));
+        }
+    }
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if !(1..10).contains(&digits) {
+        return None;
+    }
+    let rest = &line[digits..];
+    let rest = rest
+        .strip_prefix(". ")
+        .or_else(|| rest.strip_prefix(") "))?;
+    Some((line[..digits].parse().ok(), rest))
+}
+
+fn starts_block(line: &str) -> bool {
+    let trimmed = line.trim_start();
+    trimmed.starts_with("```")
+        || trimmed.starts_with('>')
+        || heading(trimmed).is_some()
+        || is_rule(trimmed)
+        || list_item(line).is_some()
+}
+
+/// Parse a CommonMark subset: paragraphs, headings, lists, quotes, code, rules, emphasis & links.
+/// Raw HTML is kept as text, and single newlines are line breaks as notes were written that way.
+pub fn parse_markdown(text: &str) -> Vec<Block> {
+    let lines = text.lines().collect::<Vec<_>>();
+    let mut blocks = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let line = lines[i];
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() {
+            i += 1;
+        } else if trimmed.starts_with("```") {
+            let end = (i + 1..lines.len())
+                .find(|&j| lines[j].trim_start().starts_with("```"))
+                .unwrap_or(lines.len());
+            blocks.push(Block::Code(lines[i + 1..end].join("\n")));
+            i = end + 1;
+        } else if let Some((level, content)) = heading(trimmed) {
+            blocks.push(Block::Heading {
+                level,
+                content: parse_inline(content),
+            });
+            i += 1;
+        } else if is_rule(trimmed) {
+            blocks.push(Block::Rule);
+            i += 1;
+        } else if trimmed.starts_with('>') {
+            let mut quoted = Vec::new();
+            while let Some(rest) = lines.get(i).and_then(|v| v.trim_start().strip_prefix('>')) {
+                quoted.push(rest.strip_prefix(' ').unwrap_or(rest));
+                i += 1;
+            }
+            blocks.push(Block::Quote(parse_markdown(&quoted.join("\n"))));
+        } else if let Some((number, _)) = list_item(line) {
+            let ordered = number.is_some();
+            let mut items: Vec<String> = Vec::new();
+            while i < lines.len() {
+                let line = lines[i];
+                match list_item(line) {
+                    Some((number, content)) if number.is_some() == ordered => {
+                        items.push(content.to_owned())
+                    }
+                    // indented lines continue the last item
+                    None if line.starts_with("  ") && !line.trim().is_empty() => {
+                        let last = items.last_mut().expect("lists start with an item");
+                        last.push('\n');
+                        last.push_str(line.trim());
+                    }
+                    _ => break,
+                }
+                i += 1;
+            }
+            blocks.push(Block::List {
+                start: number,
+                items: items.iter().map(|v| parse_inline(v)).collect(),
+            });
+        } else {
+            let mut paragraph = vec![line.trim()];
+            i += 1;
+            while i < lines.len() && !lines[i].trim().is_empty() && !starts_block(lines[i]) {
+                paragraph.push(lines[i].trim());
+                i += 1;
+            }
+            blocks.push(Block::Paragraph(parse_inline(&paragraph.join("\n"))));
+        }
+    }
+    blocks
+}
+
+#[cfg(test)]
+mod tests_markdown {
+    use super::*;
+
+    fn text(v: &str) -> Inline {
+        Inline::Text(v.to_owned())
+    }
+
+    /// Every url & every piece of text in the parsed blocks
+    fn collect(blocks: &[Block]) -> (Vec<String>, Vec<String>) {
+        fn inlines(values: &[Inline], urls: &mut Vec<String>, texts: &mut Vec<String>) {
+            for v in values {
+                match v {
+                    Inline::Text(v) | Inline::Code(v) => texts.push(v.clone()),
+                    Inline::Strong(v) | Inline::Emphasis(v) => inlines(v, urls, texts),
+                    Inline::Link { content, url } => {
+                        urls.push(url.clone());
+                        inlines(content, urls, texts);
+                    }
+                    Inline::LineBreak => {}
+                }
+            }
+        }
+        let (mut urls, mut texts) = (Vec::new(), Vec::new());
+        for block in blocks {
+            match block {
+                Block::Paragraph(v) | Block::Heading { content: v, .. } => {
+                    inlines(v, &mut urls, &mut texts)
+                }
+                Block::List { items, .. } => {
+                    items.iter().for_each(|v| inlines(v, &mut urls, &mut texts))
+                }
+                Block::Quote(v) => {
+                    let (u, t) = collect(v);
+                    urls.extend(u);
+                    texts.extend(t);
+                }
+                Block::Code(v) => texts.push(v.clone()),
+                Block::Rule => {}
+            }
+        }
+        (urls, texts)
+    }
+
+    #[test]
+    fn test_inline() {
+        assert_eq!(
+            parse_inline("a **bold** and *em* `x*y*`"),
+            vec![
+                text("a "),
+                Inline::Strong(vec![text("bold")]),
+                text(" and "),
+                Inline::Emphasis(vec![text("em")]),
+                text(" "),
+                Inline::Code("x*y*".to_owned()),
+            ]
+        );
+        assert_eq!(
+            parse_inline("snake_case_name"),
+            vec![text("snake_case_name")]
+        );
+        assert_eq!(parse_inline("2 * 3 * 4"), vec![text("2 * 3 * 4")]);
+        assert_eq!(parse_inline(r"\*not em\*"), vec![text("*not em*")]);
+        assert_eq!(
+            parse_inline("see [the **site**](https://example.com)"),
+            vec![
+                text("see "),
+                Inline::Link {
+                    content: vec![text("the "), Inline::Strong(vec![text("site")])],
+                    url: "https://example.com".to_owned(),
+                },
+            ]
+        );
+        assert_eq!(
+            parse_inline("[roux](https://en.wikipedia.org/wiki/Roux_(cooking))"),
+            vec![Inline::Link {
+                content: vec![text("roux")],
+                url: "https://en.wikipedia.org/wiki/Roux_(cooking)".to_owned(),
+            }]
+        );
+        assert_eq!(
+            parse_inline("one\ntwo"),
+            vec![text("one"), Inline::LineBreak, text("two")]
+        );
+    }
+
+    #[test]
+    fn test_blocks() {
+        let blocks = parse_markdown(
+            "# Title\n\nSome text\nmore text\n\n- one\n- two\n  continued\n\n3. three\n4. four\n\n> quoted\n\n```\n<b>code</b>\n```\n---",
+        );
+        assert_eq!(
+            blocks,
+            vec![
+                Block::Heading {
+                    level: 1,
+                    content: vec![text("Title")],
+                },
+                Block::Paragraph(vec![
+                    text("Some text"),
+                    Inline::LineBreak,
+                    text("more text")
+                ]),
+                Block::List {
+                    start: None,
+                    items: vec![
+                        vec![text("one")],
+                        vec![text("two"), Inline::LineBreak, text("continued")],
+                    ],
+                },
+                Block::List {
+                    start: Some(3),
+                    items: vec![vec![text("three")], vec![text("four")]],
+                },
+                Block::Quote(vec![Block::Paragraph(vec![text("quoted")])]),
+                Block::Code("<b>code</b>".to_owned()),
+                Block::Rule,
+            ]
+        );
+        assert_eq!(
+            parse_markdown("*emphasis* is not a list"),
+            vec![Block::Paragraph(vec![
+                Inline::Emphasis(vec![text("emphasis")]),
+                text(" is not a list"),
+            ])]
+        );
+    }
+
+    #[test]
+    fn test_safe_url() {
+        assert_eq!(
+            safe_url("https://example.com/a?b#c").as_deref(),
+            Some("https://example.com/a?b#c")
+        );
+        assert_eq!(safe_url("/recipes/1").as_deref(), Some("/recipes/1"));
+        assert_eq!(safe_url("mailto:a@b.c").as_deref(), Some("mailto:a@b.c"));
+        assert_eq!(safe_url("page?x=a:b").as_deref(), Some("page?x=a:b"));
+        assert_eq!(safe_url("javascript:alert(1)"), None);
+        assert_eq!(safe_url("JaVaScRiPt:alert(1)"), None);
+        assert_eq!(safe_url("java\tscript:alert(1)"), None);
+        assert_eq!(safe_url(" \u{1}javascript:alert(1)"), None);
+        assert_eq!(safe_url("data:text/html,<script>alert(1)</script>"), None);
+        assert_eq!(safe_url("vbscript:msgbox(1)"), None);
+        assert_eq!(safe_url(""), None);
+    }
+
+    #[test]
+    fn test_xss_payloads() {
+        let payloads = [
+            "<script>alert(1)</script>",
+            "<img src=x onerror=alert(1)>",
+            "[click](javascript:alert(1))",
+            "[click](JAVASCRIPT:alert(1))",
+            "[click](java&#x09;script:alert(1))",
+            "[click]( javascript:alert(1) )",
+            "[x](data:text/html;base64,PHNjcmlwdD5hbGVydCgxKTwvc2NyaXB0Pg==)",
+            "**<svg onload=alert(1)>**",
+            "> <iframe src=javascript:alert(1)>",
+            "- [a](vbscript:msgbox(1))",
+            "```\n<script>alert(1)</script>\n```",
+        ];
+        for payload in payloads {
+            let blocks = parse_markdown(payload);
+            let (urls, texts) = collect(&blocks);
+            for url in &urls {
+                assert_eq!(safe_url(url).as_ref(), Some(url), "{payload}");
+                // entities aren't decoded in attributes set from the DOM, so this is a relative url
+                assert!(url.starts_with("java&"), "{payload} gave {url}");
+            }
+            // markup is only ever kept as text
+            let all = texts.concat();
+            if payload.contains('<') {
+                assert!(all.contains('<'), "{payload} lost its text");
+            }
+        }
+        // the text of unsafe links remains, without a link
+        assert_eq!(
+            parse_markdown("[click](javascript:alert(1))"),
+            vec![Block::Paragraph(vec![text("click")])]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+use serde::{Deserialize, Serialize};
+
+/// How long lists are paged through
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum PagingMode {
+    /// pages are added to the list when scrolled to the bottom
+    #[default]
+    Scroll,
+    /// one page is shown at a time, moved through with previous & next buttons,
+    /// as the server does not say how many pages there are
+    Numbered,
+}
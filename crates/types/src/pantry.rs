@@ -1,7 +1,94 @@
 use chrono::{DateTime, Days, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::HumanDateFormats;
+use crate::{Fraction, HumanDateFormats, SelectedUpdate};
+
+// the server only stores whole number quantities, so the exact amount & unit are kept
+// on the last line of the notes as `[quantity: <amount>]` or `[quantity: <amount> <unit>]`,
+// e.g. `[quantity: 0.5 bag]`. Other clients show this line as part of the notes,
+// and it is ignored when the server's quantity no longer matches its amount rounded up.
+const QUANTITY_NOTE_PREFIX: &str = "[quantity: ";
+const QUANTITY_NOTE_SUFFIX: &str = "]";
+
+/// Quantity of a pantry item, e.g. `1/2 bag` or `750 g`.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Quantity {
+    pub amount: f32,
+    pub unit: Option<String>,
+}
+
+impl Quantity {
+    pub fn new(amount: f32, unit: Option<String>) -> Self {
+        Self { amount, unit }
+    }
+
+    /// Whether the quantity can be stored as just the server's whole number quantity
+    fn is_whole_without_unit(&self) -> bool {
+        self.amount.fract() == 0.0 && self.unit.is_none()
+    }
+
+    /// Encode into the server schema, as a whole quantity and notes.
+    fn encode(&self, notes: Option<String>) -> (isize, Option<String>) {
+        let whole = self.amount.ceil() as isize;
+        if self.is_whole_without_unit() {
+            return (whole, notes);
+        }
+        let marker = match &self.unit {
+            Some(unit) => format!(
+                "{QUANTITY_NOTE_PREFIX}{} {unit}{QUANTITY_NOTE_SUFFIX}",
+                self.amount
+            ),
+            None => format!(
+                "{QUANTITY_NOTE_PREFIX}{}{QUANTITY_NOTE_SUFFIX}",
+                self.amount
+            ),
+        };
+        let notes = match notes {
+            Some(notes) if !notes.is_empty() => format!("{notes}\n{marker}"),
+            _ => marker,
+        };
+        (whole, Some(notes))
+    }
+
+    /// Decode from the server schema, returning the quantity and notes without the quantity marker.
+    ///
+    /// The exact amount is only used when it still matches the whole quantity,
+    /// otherwise the quantity was changed by something unaware of the marker.
+    fn decode(whole: isize, notes: Option<String>) -> (Self, Option<String>) {
+        let Some(notes) = notes else {
+            return (Self::new(whole as f32, None), None);
+        };
+        let (rest, last_line) = match notes.rsplit_once('\n') {
+            Some((rest, last_line)) => (Some(rest.to_owned()), last_line),
+            None => (None, notes.as_str()),
+        };
+        let parsed = last_line
+            .strip_prefix(QUANTITY_NOTE_PREFIX)
+            .and_then(|v| v.strip_suffix(QUANTITY_NOTE_SUFFIX))
+            .and_then(|v| {
+                let (amount, unit) = match v.split_once(' ') {
+                    Some((amount, unit)) => (amount, Some(unit.to_owned())),
+                    None => (v, None),
+                };
+                Some(Self::new(amount.parse().ok()?, unit))
+            });
+        match parsed {
+            Some(quantity) if quantity.amount.ceil() as isize == whole => (quantity, rest),
+            Some(quantity) => (Self::new(whole as f32, quantity.unit), rest),
+            None => (Self::new(whole as f32, None), Some(notes)),
+        }
+    }
+}
+
+impl std::fmt::Display for Quantity {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match &self.unit {
+            Some(unit) => write!(f, "{} {}", Fraction::from(self.amount), unit),
+            None => write!(f, "{}", Fraction::from(self.amount)),
+        }
+    }
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
@@ -12,20 +99,63 @@ pub struct Location {
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
-#[serde(rename_all = "camelCase")]
+#[serde(from = "ItemSchema", into = "ItemSchema")]
 pub struct Item {
     pub id: String,
     pub name: String,
     pub location_id: String,
-    pub quantity: isize,
-    #[serde(default)]
+    pub quantity: Quantity,
     pub notes: Option<String>,
-    #[serde(default)]
     pub expiry: Option<DateTime<Utc>>,
-    #[serde(default)]
     pub labels: Vec<String>,
 }
 
+/// [`Item`] as the server stores it
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ItemSchema {
+    id: String,
+    name: String,
+    location_id: String,
+    quantity: isize,
+    #[serde(default)]
+    notes: Option<String>,
+    #[serde(default)]
+    expiry: Option<DateTime<Utc>>,
+    #[serde(default)]
+    labels: Vec<String>,
+}
+
+impl From<ItemSchema> for Item {
+    fn from(item: ItemSchema) -> Self {
+        let (quantity, notes) = Quantity::decode(item.quantity, item.notes);
+        Self {
+            id: item.id,
+            name: item.name,
+            location_id: item.location_id,
+            quantity,
+            notes,
+            expiry: item.expiry,
+            labels: item.labels,
+        }
+    }
+}
+
+impl From<Item> for ItemSchema {
+    fn from(item: Item) -> Self {
+        let (quantity, notes) = item.quantity.encode(item.notes);
+        Self {
+            id: item.id,
+            name: item.name,
+            location_id: item.location_id,
+            quantity,
+            notes,
+            expiry: item.expiry,
+            labels: item.labels,
+        }
+    }
+}
+
 impl Item {
     pub fn is_expired(&self) -> bool {
         match self.expiry {
@@ -62,25 +192,265 @@ pub struct CreateLocation {
     pub name: String,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(into = "CreateItemSchema")]
 pub struct CreateItem {
     pub name: String,
-    pub quantity: isize,
+    pub quantity: Quantity,
     pub notes: Option<String>,
     pub expiry: Option<DateTime<Utc>>,
     pub labels: Vec<String>,
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CreateItemSchema {
+    name: String,
+    quantity: isize,
+    notes: Option<String>,
+    expiry: Option<DateTime<Utc>>,
+    labels: Vec<String>,
+}
+
+impl From<CreateItem> for CreateItemSchema {
+    fn from(item: CreateItem) -> Self {
+        let (quantity, notes) = item.quantity.encode(item.notes);
+        Self {
+            name: item.name,
+            quantity,
+            notes,
+            expiry: item.expiry,
+            labels: item.labels,
+        }
+    }
+}
+
 pub type UpdateLocation = CreateLocation;
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
-#[serde(rename_all = "camelCase")]
+/// Item update, when updating `quantity` the `notes` field must also be selected
+#[derive(Serialize, Debug, Default, Clone)]
+#[serde(into = "UpdateItemSchema")]
 pub struct UpdateItem {
     pub name: String,
     pub location_id: String,
-    pub quantity: isize,
+    pub quantity: Quantity,
     pub notes: Option<String>,
     pub expiry: Option<DateTime<Utc>>,
     pub labels: Vec<String>,
 }
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UpdateItemSchema {
+    name: String,
+    location_id: String,
+    quantity: isize,
+    notes: Option<String>,
+    expiry: Option<DateTime<Utc>>,
+    labels: Vec<String>,
+}
+
+impl From<UpdateItem> for UpdateItemSchema {
+    fn from(item: UpdateItem) -> Self {
+        let (quantity, notes) = item.quantity.encode(item.notes);
+        Self {
+            name: item.name,
+            location_id: item.location_id,
+            quantity,
+            notes,
+            expiry: item.expiry,
+            labels: item.labels,
+        }
+    }
+}
+
+impl From<Item> for UpdateItem {
+    fn from(item: Item) -> Self {
+        Self {
+            name: item.name,
+            location_id: item.location_id,
+            quantity: item.quantity,
+            notes: item.notes,
+            expiry: item.expiry,
+            labels: item.labels,
+        }
+    }
+}
+
+/// An action that can be applied to many items at once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BulkItemAction {
+    /// Move to the location with the given id
+    Move(String),
+    AddLabels(Vec<String>),
+    RemoveLabels(Vec<String>),
+    SetExpiry(Option<DateTime<Utc>>),
+    /// Add to the quantity amount (or remove if negative), never going below 1,
+    /// or below the current amount when that is already less than 1
+    AdjustQuantity(f32),
+    Delete,
+}
+
+impl BulkItemAction {
+    /// Fields modified by the action, as used in a [`SelectedUpdate`].
+    pub fn fields(&self) -> Vec<String> {
+        let field = match self {
+            Self::Move(_) => "location_id",
+            Self::AddLabels(_) | Self::RemoveLabels(_) => "labels",
+            Self::SetExpiry(_) => "expiry",
+            // exact quantities are partly stored in the notes
+            Self::AdjustQuantity(_) => return vec!["quantity".to_owned(), "notes".to_owned()],
+            Self::Delete => return vec![],
+        };
+        vec![field.to_owned()]
+    }
+
+    /// Apply the action to an item, returns `None` if the item is deleted.
+    pub fn apply(&self, item: &Item) -> Option<Item> {
+        let mut item = item.clone();
+        match self {
+            Self::Move(location_id) => item.location_id = location_id.clone(),
+            Self::AddLabels(labels) => {
+                for label in labels {
+                    if !item.labels.contains(label) {
+                        item.labels.push(label.clone());
+                    }
+                }
+            }
+            Self::RemoveLabels(labels) => item.labels.retain(|v| !labels.contains(v)),
+            Self::SetExpiry(expiry) => item.expiry = *expiry,
+            Self::AdjustQuantity(by) => {
+                let floor = item.quantity.amount.min(1.0);
+                item.quantity.amount = (item.quantity.amount + by).max(floor);
+            }
+            Self::Delete => return None,
+        };
+        Some(item)
+    }
+
+    /// Create the update to send for an item, which must already have the action applied.
+    pub fn to_update(&self, item: &Item) -> SelectedUpdate<UpdateItem> {
+        SelectedUpdate {
+            fields: self.fields(),
+            model: item.clone().into(),
+        }
+    }
+}
+
+/// Details remembered against a scanned barcode,
+/// used to pre-fill new items when the same product is scanned again.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ScannedProduct {
+    pub name: String,
+    #[serde(default)]
+    pub labels: Vec<String>,
+    #[serde(default)]
+    pub location_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests_bulk_item_action {
+    use super::*;
+
+    fn item() -> Item {
+        Item {
+            id: "1".to_owned(),
+            name: "Peas".to_owned(),
+            location_id: "freezer".to_owned(),
+            quantity: Quantity::new(2.0, None),
+            notes: None,
+            expiry: None,
+            labels: vec!["veg".to_owned()],
+        }
+    }
+
+    #[test]
+    fn test_apply_labels() {
+        let action = BulkItemAction::AddLabels(vec!["veg".to_owned(), "frozen".to_owned()]);
+        let updated = action.apply(&item()).unwrap();
+        assert_eq!(updated.labels, vec!["veg", "frozen"]);
+
+        let action = BulkItemAction::RemoveLabels(vec!["veg".to_owned()]);
+        let updated = action.apply(&updated).unwrap();
+        assert_eq!(updated.labels, vec!["frozen"]);
+    }
+
+    #[test]
+    fn test_apply_quantity() {
+        let updated = BulkItemAction::AdjustQuantity(0.5).apply(&item()).unwrap();
+        assert_eq!(updated.quantity.amount, 2.5);
+        let updated = BulkItemAction::AdjustQuantity(-10.0)
+            .apply(&item())
+            .unwrap();
+        assert_eq!(updated.quantity.amount, 1.0);
+        let mut half = item();
+        half.quantity = Quantity::new(0.5, Some("bag".to_owned()));
+        let updated = BulkItemAction::AdjustQuantity(-1.0).apply(&half).unwrap();
+        assert_eq!(updated.quantity.amount, 0.5);
+    }
+
+    #[test]
+    fn test_apply_move_and_delete() {
+        let action = BulkItemAction::Move("fridge".to_owned());
+        let updated = action.apply(&item()).unwrap();
+        assert_eq!(updated.location_id, "fridge");
+        assert_eq!(action.fields(), vec!["location_id"]);
+        assert_eq!(action.to_update(&updated).model.location_id, "fridge");
+
+        assert_eq!(BulkItemAction::Delete.apply(&item()), None);
+        assert!(BulkItemAction::Delete.fields().is_empty());
+    }
+}
+
+#[cfg(test)]
+mod tests_quantity {
+    use super::*;
+
+    #[test]
+    fn test_encode() {
+        let (whole, notes) = Quantity::new(3.0, None).encode(Some("Opened".to_owned()));
+        assert_eq!(whole, 3);
+        assert_eq!(notes.as_deref(), Some("Opened"));
+
+        let (whole, notes) = Quantity::new(0.5, Some("bag".to_owned())).encode(None);
+        assert_eq!(whole, 1);
+        assert_eq!(notes.as_deref(), Some("[quantity: 0.5 bag]"));
+
+        let (whole, notes) =
+            Quantity::new(750.0, Some("g".to_owned())).encode(Some("Opened".to_owned()));
+        assert_eq!(whole, 750);
+        assert_eq!(notes.as_deref(), Some("Opened\n[quantity: 750 g]"));
+    }
+
+    #[test]
+    fn test_decode() {
+        // integer only items from before units existed
+        let (quantity, notes) = Quantity::decode(2, Some("Opened".to_owned()));
+        assert_eq!(quantity, Quantity::new(2.0, None));
+        assert_eq!(notes.as_deref(), Some("Opened"));
+
+        let (quantity, notes) = Quantity::decode(1, Some("Opened\n[quantity: 0.5 bag]".to_owned()));
+        assert_eq!(quantity, Quantity::new(0.5, Some("bag".to_owned())));
+        assert_eq!(notes.as_deref(), Some("Opened"));
+
+        let (quantity, notes) = Quantity::decode(1, Some("[quantity: 0.25]".to_owned()));
+        assert_eq!(quantity, Quantity::new(0.25, None));
+        assert_eq!(notes, None);
+
+        // whole quantity was changed elsewhere, so the exact amount is stale
+        let (quantity, _) = Quantity::decode(4, Some("[quantity: 0.5 bag]".to_owned()));
+        assert_eq!(quantity, Quantity::new(4.0, Some("bag".to_owned())));
+    }
+
+    #[test]
+    fn test_item_round_trip() {
+        let json = r#"{"id":"1","name":"Rice","locationId":"2","quantity":1,"notes":"[quantity: 0.5 bag]"}"#;
+        let item: Item = serde_json::from_str(json).unwrap();
+        assert_eq!(item.quantity.to_string(), "1/2 bag");
+        assert_eq!(item.notes, None);
+        let update = serde_json::to_value(UpdateItem::from(item)).unwrap();
+        assert_eq!(update["quantity"], 1);
+        assert_eq!(update["notes"], "[quantity: 0.5 bag]");
+    }
+}
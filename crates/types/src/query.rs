@@ -1,15 +1,23 @@
-use std::collections::HashSet;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+};
 
 use serde::{Deserialize, Serialize};
 
+use crate::{pantry::Item, recipe::Recipe};
+
+/// Also used as the page's query string, so missing fields use the default
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct RecipesFilter {
     pub page: usize,
     pub per_page: usize,
     pub title: Option<String>,
     #[serde(rename = "label")]
     pub labels: Option<HashSet<String>>,
+    #[serde(rename = "tag")]
+    pub tags: Option<HashSet<String>>,
     pub freezable: Option<bool>,
     pub microwave_only: Option<bool>,
 }
@@ -21,14 +29,44 @@ impl Default for RecipesFilter {
             per_page: 20,
             title: None,
             labels: None,
+            tags: None,
             freezable: None,
             microwave_only: None,
         }
     }
 }
 
+impl RecipesFilter {
+    /// Whether a recipe has every tag being filtered on,
+    /// checked by the client as the server does not filter by tag.
+    pub fn matches_tags(&self, recipe: &Recipe) -> bool {
+        self.tags
+            .as_ref()
+            .is_none_or(|tags| tags.iter().all(|tag| recipe.tags.contains(tag)))
+    }
+}
+
+/// Query string of the recipe search page
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchQuery {
+    pub q: String,
+}
+
+/// Query string of the cookbook print page, it prints the recipes with these ids,
+/// or without any those matching a [`RecipesFilter`] in the same query string
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct PrintQuery {
+    #[serde(rename = "id")]
+    pub ids: Vec<String>,
+    /// title of the cover page
+    pub cookbook: Option<String>,
+}
+
+/// Also used as the page's query string, so missing fields use the default
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
+#[serde(default, rename_all = "camelCase")]
 pub struct PantryFilter {
     pub page: usize,
     pub per_page: usize,
@@ -51,3 +89,157 @@ impl Default for PantryFilter {
         }
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PantrySort {
+    /// soonest expiry first, items without an expiry last
+    Expiry,
+    Name,
+    /// lowest quantity first
+    Quantity,
+    /// by location name, then item name
+    Location,
+}
+
+impl PantrySort {
+    pub const ALL: [Self; 4] = [Self::Expiry, Self::Name, Self::Quantity, Self::Location];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Expiry => "Expiry (Soonest)",
+            Self::Name => "Name",
+            Self::Quantity => "Quantity",
+            Self::Location => "Location",
+        }
+    }
+
+    /// Sort items in place, `locations` maps location ids to names.
+    pub fn sort(&self, items: &mut [Item], locations: &HashMap<String, String>) {
+        let by_name = |a: &Item, b: &Item| a.name.to_lowercase().cmp(&b.name.to_lowercase());
+        match self {
+            Self::Expiry => items.sort_by(|a, b| match (a.expiry, b.expiry) {
+                (Some(a_expiry), Some(b_expiry)) => {
+                    a_expiry.cmp(&b_expiry).then_with(|| by_name(a, b))
+                }
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => by_name(a, b),
+            }),
+            Self::Name => items.sort_by(by_name),
+            Self::Quantity => items.sort_by(|a, b| {
+                a.quantity
+                    .amount
+                    .total_cmp(&b.quantity.amount)
+                    .then_with(|| by_name(a, b))
+            }),
+            Self::Location => {
+                let name_of = |item: &Item| {
+                    locations
+                        .get(&item.location_id)
+                        .map(|v| v.to_lowercase())
+                        .unwrap_or_default()
+                };
+                items.sort_by(|a, b| name_of(a).cmp(&name_of(b)).then_with(|| by_name(a, b)))
+            }
+        }
+    }
+}
+
+/// Optional columns of the pantry table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PantryColumn {
+    Notes,
+    Labels,
+    Location,
+}
+
+impl PantryColumn {
+    pub const ALL: [Self; 3] = [Self::Notes, Self::Labels, Self::Location];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Notes => "Notes",
+            Self::Labels => "Labels",
+            Self::Location => "Location",
+        }
+    }
+}
+
+/// How the pantry table is displayed, kept separate from [`PantryFilter`]
+/// as it is applied client side.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PantryView {
+    pub sort: Option<PantrySort>,
+    #[serde(default, rename = "column")]
+    pub columns: HashSet<PantryColumn>,
+}
+
+#[cfg(test)]
+mod tests_pantry_sort {
+    use chrono::{TimeZone, Utc};
+
+    use super::*;
+    use crate::pantry::Quantity;
+
+    fn item(name: &str, location_id: &str, amount: f32, expiry_day: Option<u32>) -> Item {
+        Item {
+            id: name.to_owned(),
+            name: name.to_owned(),
+            location_id: location_id.to_owned(),
+            quantity: Quantity::new(amount, None),
+            notes: None,
+            expiry: expiry_day.map(|day| Utc.with_ymd_and_hms(2024, 1, day, 0, 0, 0).unwrap()),
+            labels: vec![],
+        }
+    }
+
+    fn sorted_names(sort: PantrySort) -> Vec<String> {
+        let mut items = vec![
+            item("beans", "b", 3.0, None),
+            item("Apples", "b", 0.5, Some(20)),
+            item("carrots", "a", 2.0, Some(5)),
+            item("dates", "a", 1.0, None),
+        ];
+        let locations = HashMap::from([
+            ("a".to_owned(), "Fridge".to_owned()),
+            ("b".to_owned(), "cupboard".to_owned()),
+        ]);
+        sort.sort(&mut items, &locations);
+        items.into_iter().map(|v| v.name).collect()
+    }
+
+    #[test]
+    fn test_sort_expiry() {
+        assert_eq!(
+            sorted_names(PantrySort::Expiry),
+            vec!["carrots", "Apples", "beans", "dates"]
+        );
+    }
+
+    #[test]
+    fn test_sort_name() {
+        assert_eq!(
+            sorted_names(PantrySort::Name),
+            vec!["Apples", "beans", "carrots", "dates"]
+        );
+    }
+
+    #[test]
+    fn test_sort_quantity() {
+        assert_eq!(
+            sorted_names(PantrySort::Quantity),
+            vec!["Apples", "dates", "carrots", "beans"]
+        );
+    }
+
+    #[test]
+    fn test_sort_location() {
+        assert_eq!(
+            sorted_names(PantrySort::Location),
+            vec!["Apples", "beans", "carrots", "dates"]
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -16,6 +18,26 @@ pub struct CreateStep {
     pub description: String,
 }
 
+impl From<Ingredient> for CreateIngredient {
+    fn from(ingredient: Ingredient) -> Self {
+        Self {
+            name: ingredient.name,
+            amount: ingredient.amount,
+            unit_type: ingredient.unit_type,
+            description: ingredient.description,
+        }
+    }
+}
+
+impl From<Step> for CreateStep {
+    fn from(step: Step) -> Self {
+        Self {
+            title: step.title,
+            description: step.description,
+        }
+    }
+}
+
 pub type CreateInfo = Info;
 
 #[derive(Serialize, Deserialize, Debug, Default)]
@@ -98,6 +120,121 @@ pub struct Step {
     pub description: String,
 }
 
+/// Marks an ingredient or step as a section header, so sections fit the flat lists the server stores.
+/// Headers have nothing else set, e.g. `{"name": "# For the sauce", "amount": 0, "unitType": ""}`
+pub const SECTION_PREFIX: &str = "# ";
+
+impl Ingredient {
+    pub fn section(name: &str) -> Self {
+        Self {
+            name: format!("{SECTION_PREFIX}{name}"),
+            ..Default::default()
+        }
+    }
+
+    /// The section name, when this is a section header
+    pub fn section_name(&self) -> Option<&str> {
+        if self.amount != 0.0
+            || !self.unit_type.is_empty()
+            || self.description.as_ref().is_some_and(|v| !v.is_empty())
+        {
+            return None;
+        }
+        self.name.strip_prefix(SECTION_PREFIX)
+    }
+}
+
+impl Step {
+    pub fn section(name: &str) -> Self {
+        Self {
+            title: Some(format!("{SECTION_PREFIX}{name}")),
+            description: String::new(),
+        }
+    }
+
+    /// The group name, when this is a group header
+    pub fn section_name(&self) -> Option<&str> {
+        if !self.description.is_empty() {
+            return None;
+        }
+        self.title.as_deref()?.strip_prefix(SECTION_PREFIX)
+    }
+}
+
+/// Items following a section header, the first section has no name
+/// when the list doesn't start with a header
+#[derive(Debug, Clone, PartialEq)]
+pub struct Section<T> {
+    pub name: Option<String>,
+    pub items: Vec<T>,
+}
+
+fn sections<'a, T: Clone>(
+    items: &'a [T],
+    section_name: impl Fn(&'a T) -> Option<&'a str>,
+) -> Vec<Section<T>> {
+    let mut sections = vec![Section {
+        name: None,
+        items: Vec::new(),
+    }];
+    for item in items {
+        match section_name(item) {
+            Some(name) => sections.push(Section {
+                name: Some(name.to_owned()),
+                items: Vec::new(),
+            }),
+            None => sections
+                .last_mut()
+                .expect("sections are never empty")
+                .items
+                .push(item.clone()),
+        }
+    }
+    if sections[0].items.is_empty() {
+        sections.remove(0);
+    }
+    sections
+}
+
+pub fn ingredient_sections(ingredients: &[Ingredient]) -> Vec<Section<Ingredient>> {
+    sections(ingredients, Ingredient::section_name)
+}
+
+/// Steps grouped by their headers, along with each step's number counting only steps
+pub fn step_sections(steps: &[Step]) -> Vec<Section<(usize, Step)>> {
+    let mut number = 0;
+    let numbered = steps
+        .iter()
+        .map(|step| {
+            if step.section_name().is_none() {
+                number += 1;
+            }
+            (number, step.clone())
+        })
+        .collect::<Vec<_>>();
+    sections(&numbered, |(_, step)| step.section_name())
+}
+
+/// Number of the same step in `saved` for each of `steps`, matched in order by content,
+/// none for headers & steps that were not saved. Used to follow steps (and the images
+/// attached to them by number) from a saved recipe into a draft of it.
+pub fn saved_step_numbers(saved: &[Step], steps: &[Step]) -> Vec<Option<usize>> {
+    let mut unmatched = step_sections(saved)
+        .into_iter()
+        .flat_map(|section| section.items)
+        .collect::<Vec<_>>();
+    steps
+        .iter()
+        .map(|step| {
+            if step.section_name().is_some() {
+                return None;
+            }
+            let index = unmatched.iter().position(|(_, v)| v == step)?;
+            Some(unmatched.remove(index).0)
+        })
+        .collect()
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct InfoYields {
@@ -143,3 +280,317 @@ pub struct Recipe {
     #[serde(default)]
     pub image_id: Option<String>,
 }
+
+impl UpdateRecipe {
+    /// Every field of a recipe (apart from its image), e.g. to restore an earlier version
+    pub fn all_of(recipe: Recipe) -> Self {
+        Self {
+            title: Some(recipe.title),
+            info: recipe.info,
+            short_description: Some(recipe.short_description.unwrap_or_default()),
+            long_description: Some(recipe.long_description.unwrap_or_default()),
+            tags: Some(recipe.tags),
+            ingredients: Some(recipe.ingredients.into_iter().map(Into::into).collect()),
+            steps: Some(recipe.steps.into_iter().map(Into::into).collect()),
+            labels: Some(recipe.labels),
+        }
+    }
+}
+
+impl CreateRecipe {
+    /// A full copy of an existing recipe (apart from its image), under a new title
+    pub fn copy_of(recipe: &Recipe, title: String) -> Self {
+        let recipe = recipe.clone();
+        Self {
+            title,
+            info: recipe.info,
+            short_description: recipe.short_description,
+            long_description: recipe.long_description,
+            tags: recipe.tags,
+            ingredients: recipe.ingredients.into_iter().map(Into::into).collect(),
+            steps: recipe.steps.into_iter().map(Into::into).collect(),
+            labels: recipe.labels,
+        }
+    }
+}
+
+/// Links from variant recipes to the recipe they were copied from,
+/// kept by the client as the server has nowhere to store them.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[serde(transparent)]
+pub struct RecipeVariants(pub HashMap<String, String>);
+
+impl RecipeVariants {
+    pub fn parent(&self, id: &str) -> Option<&String> {
+        self.0.get(id)
+    }
+
+    /// Direct variants of a recipe, sorted for a stable order
+    pub fn variants(&self, id: &str) -> Vec<String> {
+        let mut variants = self
+            .0
+            .iter()
+            .filter(|(_, parent)| *parent == id)
+            .map(|(variant, _)| variant.clone())
+            .collect::<Vec<_>>();
+        variants.sort();
+        variants
+    }
+
+    /// Link a variant to its parent, ignored if it would make a loop
+    pub fn link(&mut self, variant: &str, parent: &str) {
+        let mut current = Some(parent);
+        while let Some(id) = current {
+            if id == variant {
+                return;
+            }
+            current = self.parent(id).map(|v| v.as_str());
+        }
+        self.0.insert(variant.to_owned(), parent.to_owned());
+    }
+
+    pub fn unlink(&mut self, variant: &str) {
+        self.0.remove(variant);
+    }
+
+    /// Forget a removed recipe, its variants become standalone recipes
+    pub fn remove(&mut self, id: &str) {
+        self.0
+            .retain(|variant, parent| variant != id && parent != id);
+    }
+}
+
+/// Count how many recipes use each tag, from the tags of each recipe, sorted by tag.
+pub fn count_tags<'a>(recipe_tags: impl IntoIterator<Item = &'a [String]>) -> Vec<(String, usize)> {
+    let mut counts = BTreeMap::<&str, usize>::new();
+    for recipe_tags in recipe_tags {
+        let mut tags = recipe_tags.iter().map(|v| v.as_str()).collect::<Vec<_>>();
+        tags.sort_unstable();
+        tags.dedup();
+        for tag in tags {
+            *counts.entry(tag).or_default() += 1;
+        }
+    }
+    counts
+        .into_iter()
+        .map(|(tag, count)| (tag.to_owned(), count))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests_recipe_tags {
+    use super::*;
+
+    fn recipe(tags: &[&str]) -> Recipe {
+        Recipe {
+            id: String::default(),
+            owner_id: String::default(),
+            title: String::default(),
+            info: Info::default(),
+            short_description: None,
+            long_description: None,
+            tags: tags.iter().map(|v| v.to_string()).collect(),
+            ingredients: vec![],
+            steps: vec![],
+            labels: vec![],
+            image_id: None,
+        }
+    }
+
+    #[test]
+    fn test_count_tags() {
+        let recipes = [
+            recipe(&["pasta", "quick"]),
+            recipe(&["quick", "quick"]),
+            recipe(&[]),
+        ];
+        assert_eq!(
+            count_tags(recipes.iter().map(|recipe| recipe.tags.as_slice())),
+            vec![("pasta".to_owned(), 1), ("quick".to_owned(), 2)]
+        );
+    }
+
+    #[test]
+    fn test_filter_matches_tags() {
+        use crate::query::RecipesFilter;
+        use std::collections::HashSet;
+
+        let recipe = recipe(&["pasta", "quick"]);
+        let mut filters = RecipesFilter::default();
+        assert!(filters.matches_tags(&recipe));
+        filters.tags = Some(HashSet::from(["quick".to_owned()]));
+        assert!(filters.matches_tags(&recipe));
+        filters.tags = Some(HashSet::from(["quick".to_owned(), "soup".to_owned()]));
+        assert!(!filters.matches_tags(&recipe));
+    }
+}
+
+#[cfg(test)]
+mod tests_recipe_variants {
+    use super::*;
+
+    #[test]
+    fn test_copy_of() {
+        let recipe = Recipe {
+            id: "1".to_owned(),
+            owner_id: "owner".to_owned(),
+            title: "Lasagne".to_owned(),
+            info: Info {
+                cook_time: 60,
+                ..Default::default()
+            },
+            short_description: Some("Layers".to_owned()),
+            long_description: None,
+            tags: vec!["pasta".to_owned()],
+            ingredients: vec![Ingredient {
+                name: "Mince".to_owned(),
+                amount: 500.0,
+                unit_type: "g".to_owned(),
+                description: None,
+            }],
+            steps: vec![Step {
+                title: None,
+                description: "Bake".to_owned(),
+            }],
+            labels: vec!["dinner".to_owned()],
+            image_id: Some("image".to_owned()),
+        };
+        let copy = CreateRecipe::copy_of(&recipe, "Veggie Lasagne".to_owned());
+        assert_eq!(copy.title, "Veggie Lasagne");
+        assert_eq!(copy.info, recipe.info);
+        assert_eq!(copy.ingredients[0].name, "Mince");
+        assert_eq!(copy.steps[0].description, "Bake");
+        assert_eq!(copy.labels, recipe.labels);
+        assert_eq!(copy.tags, recipe.tags);
+    }
+
+    #[test]
+    fn test_variants() {
+        let mut variants = RecipeVariants::default();
+        variants.link("veggie", "lasagne");
+        variants.link("vegan", "lasagne");
+        variants.link("spicy-vegan", "vegan");
+        assert_eq!(variants.variants("lasagne"), vec!["vegan", "veggie"]);
+        assert_eq!(
+            variants.parent("spicy-vegan").map(|v| v.as_str()),
+            Some("vegan")
+        );
+        // loops are ignored
+        variants.link("lasagne", "spicy-vegan");
+        assert_eq!(variants.parent("lasagne"), None);
+
+        variants.remove("vegan");
+        assert_eq!(variants.variants("lasagne"), vec!["veggie"]);
+        assert_eq!(variants.parent("spicy-vegan"), None);
+    }
+}
+
+#[cfg(test)]
+mod tests_recipe_sections {
+    use super::*;
+
+    fn ingredient(name: &str) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            amount: 1.0,
+            unit_type: "g".to_owned(),
+            description: None,
+        }
+    }
+
+    fn step(description: &str) -> Step {
+        Step {
+            title: None,
+            description: description.to_owned(),
+        }
+    }
+
+    #[test]
+    fn test_saved_step_numbers() {
+        let saved = [step("a"), Step::section("Sauce"), step("b"), step("a")];
+        let steps = [
+            step("b"),
+            step("a"),
+            step("new"),
+            Step::section("Sauce"),
+            step("a"),
+        ];
+        assert_eq!(
+            saved_step_numbers(&saved, &steps),
+            vec![Some(2), Some(1), None, None, Some(3)]
+        );
+    }
+
+    #[test]
+    fn test_section_name() {
+        assert_eq!(
+            Ingredient::section("For the sauce").section_name(),
+            Some("For the sauce")
+        );
+        assert_eq!(ingredient("# Salt").section_name(), None);
+        assert_eq!(ingredient("Salt").section_name(), None);
+        assert_eq!(Step::section("Dough").section_name(), Some("Dough"));
+        assert_eq!(
+            Step {
+                title: Some("# Dough".to_owned()),
+                description: "Knead".to_owned(),
+            }
+            .section_name(),
+            None
+        );
+        let serialized = serde_json::to_string(&Ingredient::section("Sauce")).unwrap();
+        let deserialized: Ingredient = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized.section_name(), Some("Sauce"));
+    }
+
+    #[test]
+    fn test_ingredient_sections() {
+        assert_eq!(
+            ingredient_sections(&[ingredient("Salt")]),
+            vec![Section {
+                name: None,
+                items: vec![ingredient("Salt")],
+            }]
+        );
+        let sections = ingredient_sections(&[
+            Ingredient::section("Dough"),
+            ingredient("Flour"),
+            ingredient("Water"),
+            Ingredient::section("Sauce"),
+            ingredient("Tomato"),
+        ]);
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].name.as_deref(), Some("Dough"));
+        assert_eq!(sections[0].items.len(), 2);
+        assert_eq!(sections[1].name.as_deref(), Some("Sauce"));
+        assert_eq!(sections[1].items, vec![ingredient("Tomato")]);
+    }
+
+    #[test]
+    fn test_step_sections() {
+        let sections = step_sections(&[
+            step("Preheat"),
+            Step::section("Dough"),
+            step("Mix"),
+            step("Knead"),
+            Step::section("Topping"),
+        ]);
+        let numbers = sections
+            .iter()
+            .map(|v| {
+                (
+                    v.name.clone(),
+                    v.items.iter().map(|(n, _)| *n).collect::<Vec<_>>(),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            numbers,
+            vec![
+                (None, vec![1]),
+                (Some("Dough".to_owned()), vec![2, 3]),
+                (Some("Topping".to_owned()), vec![]),
+            ]
+        );
+    }
+}
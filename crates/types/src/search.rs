@@ -0,0 +1,616 @@
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use crate::recipe::Recipe;
+
+/// Words too common to be worth indexing
+const STOP_WORDS: [&str; 12] = [
+    "a", "an", "and", "as", "at", "for", "in", "of", "on", "or", "the", "with",
+];
+/// Roughly how long (in bytes) a matched text can be before it is shortened
+const SNIPPET_LEN: usize = 120;
+/// How many matched fields are given per result
+const MAX_MATCHES: usize = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchField {
+    Title,
+    Tags,
+    Labels,
+    Ingredient,
+    Description,
+    Step,
+    Source,
+}
+
+impl SearchField {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Title => "Title",
+            Self::Tags => "Tags",
+            Self::Labels => "Labels",
+            Self::Ingredient => "Ingredient",
+            Self::Description => "Description",
+            Self::Step => "Step",
+            Self::Source => "Source",
+        }
+    }
+
+    /// How much a match in this field counts towards a result's score
+    fn weight(&self) -> u32 {
+        match self {
+            Self::Title => 5,
+            Self::Tags | Self::Labels | Self::Ingredient => 3,
+            Self::Description => 2,
+            Self::Step | Self::Source => 1,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SearchText {
+    pub field: SearchField,
+    pub text: String,
+}
+
+/// The searchable parts of a recipe
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchDocument {
+    pub id: String,
+    pub title: String,
+    pub image_id: Option<String>,
+    pub texts: Vec<SearchText>,
+}
+
+impl From<&Recipe> for SearchDocument {
+    fn from(recipe: &Recipe) -> Self {
+        let mut texts = vec![SearchText {
+            field: SearchField::Title,
+            text: recipe.title.clone(),
+        }];
+        let mut push = |field, text: String| {
+            if !text.trim().is_empty() {
+                texts.push(SearchText { field, text });
+            }
+        };
+        push(SearchField::Tags, recipe.tags.join(", "));
+        push(SearchField::Labels, recipe.labels.join(", "));
+        for ingredient in &recipe.ingredients {
+            let text = match &ingredient.description {
+                Some(description) => format!("{} ({description})", ingredient.display_name()),
+                None => ingredient.display_name(),
+            };
+            push(SearchField::Ingredient, text);
+        }
+        for description in [&recipe.short_description, &recipe.long_description]
+            .into_iter()
+            .flatten()
+        {
+            push(SearchField::Description, description.clone());
+        }
+        for step in &recipe.steps {
+            if let Some(title) = &step.title {
+                push(SearchField::Step, title.clone());
+            }
+            push(SearchField::Step, step.description.clone());
+        }
+        if let Some(source) = &recipe.info.source {
+            push(SearchField::Source, source.clone());
+        }
+        Self {
+            id: recipe.id.clone(),
+            title: recipe.title.clone(),
+            image_id: recipe.image_id.clone(),
+            texts,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Token {
+    /// byte offset into the original text
+    pub start: usize,
+    /// byte offset into the original text
+    pub end: usize,
+    pub stem: String,
+}
+
+/// Split text into stemmed words, leaving out stop words
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(word_start)) => {
+                let word = text[word_start..i].to_lowercase();
+                if !STOP_WORDS.contains(&word.as_str()) {
+                    tokens.push(Token {
+                        start: word_start,
+                        end: i,
+                        stem: stem(&word),
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    tokens
+}
+
+/// A light English stemmer, so "chopped", "chopping" & "chop" are all found by each other.
+/// Expects a lowercase word.
+pub fn stem(word: &str) -> String {
+    let mut stem = word.to_owned();
+    let len = |v: &str| v.chars().count();
+    if let Some(v) = stem
+        .strip_suffix("ies")
+        .or_else(|| stem.strip_suffix("ied"))
+    {
+        if len(v) >= 2 {
+            return format!("{v}y");
+        }
+    }
+    if !stem.ends_with("ss") && !stem.ends_with("us") && len(&stem) > 3 {
+        if let Some(v) = stem.strip_suffix('s') {
+            stem = v.to_owned();
+        }
+    }
+    for suffix in ["ing", "ed"] {
+        if let Some(v) = stem.strip_suffix(suffix).filter(|v| len(v) >= 3) {
+            stem = v.to_owned();
+            // "chopp" -> "chop"
+            let mut chars = stem.chars().rev();
+            if let (Some(a), Some(b)) = (chars.next(), chars.next()) {
+                if a == b && !"aeiouls".contains(a) {
+                    stem.pop();
+                }
+            }
+            break;
+        }
+    }
+    if len(&stem) > 3 {
+        if let Some(v) = stem.strip_suffix('e') {
+            stem = v.to_owned();
+        }
+    }
+    stem
+}
+
+/// Edit distance between two words, giving up once it is over `max`
+fn edit_distance(a: &str, b: &str, max: usize) -> Option<usize> {
+    let a = a.chars().collect::<Vec<_>>();
+    let b = b.chars().collect::<Vec<_>>();
+    if a.len().abs_diff(b.len()) > max {
+        return None;
+    }
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.iter().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = usize::from(ca != cb);
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        if current.iter().min().is_some_and(|v| *v > max) {
+            return None;
+        }
+        previous = current;
+    }
+    previous.last().copied().filter(|v| *v <= max)
+}
+
+/// How closely an indexed stem matches a searched for stem,
+/// 0 being exact, `None` when it does not match at all.
+fn term_penalty(query: &str, term: &str) -> Option<u32> {
+    let query_len = query.chars().count();
+    if query == term {
+        Some(0)
+    } else if query_len >= 2 && term.starts_with(query) {
+        Some(1)
+    } else if query_len >= 4 {
+        let max = if query_len >= 8 { 2 } else { 1 };
+        edit_distance(query, term, max).map(|_| 2)
+    } else {
+        None
+    }
+}
+
+/// Score how well a short query matches a name, for quick pickers.
+/// Every query character has to appear in order, consecutive & word start matches score higher.
+pub fn fuzzy_score(query: &str, text: &str) -> Option<u32> {
+    let query = query
+        .to_lowercase()
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<Vec<_>>();
+    let mut score = 0;
+    let mut matched = 0;
+    let mut previous_matched = false;
+    let mut previous = ' ';
+    for c in text.to_lowercase().chars() {
+        if query.get(matched) == Some(&c) {
+            score += 1;
+            if previous_matched {
+                score += 2;
+            }
+            if !previous.is_alphanumeric() {
+                score += 3;
+            }
+            matched += 1;
+            previous_matched = true;
+        } else {
+            previous_matched = false;
+        }
+        previous = c;
+    }
+    (matched == query.len()).then_some(score)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchMatch {
+    pub field: SearchField,
+    /// the matched text, shortened around the first match if long
+    pub text: String,
+    /// byte ranges of `text` to highlight
+    pub highlights: Vec<(usize, usize)>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchResult {
+    pub id: String,
+    pub title: String,
+    pub image_id: Option<String>,
+    pub score: u32,
+    /// best matching fields first
+    pub matches: Vec<SearchMatch>,
+}
+
+fn floor_char_boundary(text: &str, mut index: usize) -> usize {
+    while !text.is_char_boundary(index) {
+        index -= 1;
+    }
+    index
+}
+
+/// Shorten a long text around its first highlight
+fn snippet(text: &str, highlights: &[(usize, usize)]) -> (String, Vec<(usize, usize)>) {
+    if text.len() <= SNIPPET_LEN {
+        return (text.to_owned(), highlights.to_vec());
+    }
+    let first = highlights.first().map(|v| v.0).unwrap_or_default();
+    let start = floor_char_boundary(text, first.saturating_sub(SNIPPET_LEN / 4));
+    let end = floor_char_boundary(text, (start + SNIPPET_LEN).min(text.len()));
+    let prefix = if start > 0 { "…" } else { "" };
+    let suffix = if end < text.len() { "…" } else { "" };
+    let highlights = highlights
+        .iter()
+        .filter(|(s, e)| *s >= start && *e <= end)
+        .map(|(s, e)| (s - start + prefix.len(), e - start + prefix.len()))
+        .collect();
+    (format!("{prefix}{}{suffix}", &text[start..end]), highlights)
+}
+
+/// A full-text index of recipes, only the documents are stored as the terms are rebuilt on load
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(from = "Vec<SearchDocument>", into = "Vec<SearchDocument>")]
+pub struct SearchIndex {
+    documents: BTreeMap<String, SearchDocument>,
+    /// stem -> document ids
+    terms: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl From<Vec<SearchDocument>> for SearchIndex {
+    fn from(documents: Vec<SearchDocument>) -> Self {
+        let mut index = Self::default();
+        for document in documents {
+            index.upsert(document);
+        }
+        index
+    }
+}
+
+impl From<SearchIndex> for Vec<SearchDocument> {
+    fn from(index: SearchIndex) -> Self {
+        index.documents.into_values().collect()
+    }
+}
+
+impl SearchIndex {
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Add or replace a document, returning whether anything changed
+    pub fn upsert(&mut self, document: SearchDocument) -> bool {
+        if self.documents.get(&document.id) == Some(&document) {
+            return false;
+        }
+        self.remove(&document.id);
+        for text in &document.texts {
+            for token in tokenize(&text.text) {
+                self.terms
+                    .entry(token.stem)
+                    .or_default()
+                    .insert(document.id.clone());
+            }
+        }
+        self.documents.insert(document.id.clone(), document);
+        true
+    }
+
+    /// Remove a document, returning whether it existed
+    pub fn remove(&mut self, id: &str) -> bool {
+        let Some(document) = self.documents.remove(id) else {
+            return false;
+        };
+        for text in &document.texts {
+            for token in tokenize(&text.text) {
+                if let Some(ids) = self.terms.get_mut(&token.stem) {
+                    ids.remove(id);
+                    if ids.is_empty() {
+                        self.terms.remove(&token.stem);
+                    }
+                }
+            }
+        }
+        true
+    }
+
+    /// Bring the index up to date with every recipe,
+    /// only re-indexing what changed. Returns whether anything changed.
+    pub fn refresh(&mut self, documents: Vec<SearchDocument>) -> bool {
+        let ids = documents
+            .iter()
+            .map(|v| v.id.clone())
+            .collect::<HashSet<_>>();
+        let removed = self
+            .documents
+            .keys()
+            .filter(|id| !ids.contains(*id))
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut changed = !removed.is_empty();
+        for id in removed {
+            self.remove(&id);
+        }
+        for document in documents {
+            changed |= self.upsert(document);
+        }
+        changed
+    }
+
+    /// Documents containing a term matching the searched stem
+    fn matching_ids(&self, query: &str) -> BTreeSet<&String> {
+        self.terms
+            .iter()
+            .filter(|(term, _)| term_penalty(query, term).is_some())
+            .flat_map(|(_, ids)| ids)
+            .collect()
+    }
+
+    /// Find documents matching every word of the query, best first
+    pub fn search(&self, query: &str) -> Vec<SearchResult> {
+        let query = tokenize(query)
+            .into_iter()
+            .map(|v| v.stem)
+            .collect::<BTreeSet<_>>();
+        let Some(ids) = query
+            .iter()
+            .map(|v| self.matching_ids(v))
+            .reduce(|a, b| a.intersection(&b).copied().collect())
+        else {
+            return Vec::new();
+        };
+
+        let mut results = ids
+            .into_iter()
+            .filter_map(|id| self.documents.get(id))
+            .map(|document| {
+                // best score for each query term
+                let mut best = vec![0; query.len()];
+                let mut matches = Vec::new();
+                for text in &document.texts {
+                    let mut highlights = Vec::new();
+                    let mut field_score = 0;
+                    for token in tokenize(&text.text) {
+                        let penalties = query.iter().map(|v| term_penalty(v, &token.stem));
+                        for (best, penalty) in best.iter_mut().zip(penalties) {
+                            if let Some(penalty) = penalty {
+                                let score = text.field.weight() * (3 - penalty);
+                                *best = score.max(*best);
+                                field_score = score.max(field_score);
+                                if highlights.last() != Some(&(token.start, token.end)) {
+                                    highlights.push((token.start, token.end));
+                                }
+                            }
+                        }
+                    }
+                    if !highlights.is_empty() {
+                        let (text_snippet, highlights) = snippet(&text.text, &highlights);
+                        matches.push((
+                            field_score,
+                            SearchMatch {
+                                field: text.field,
+                                text: text_snippet,
+                                highlights,
+                            },
+                        ));
+                    }
+                }
+                matches.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+                SearchResult {
+                    id: document.id.clone(),
+                    title: document.title.clone(),
+                    image_id: document.image_id.clone(),
+                    score: best.into_iter().sum(),
+                    matches: matches
+                        .into_iter()
+                        .take(MAX_MATCHES)
+                        .map(|(_, v)| v)
+                        .collect(),
+                }
+            })
+            .collect::<Vec<_>>();
+        results.sort_by(|a, b| b.score.cmp(&a.score).then_with(|| a.title.cmp(&b.title)));
+        results
+    }
+}
+
+#[cfg(test)]
+mod tests_search {
+    use super::*;
+    use crate::recipe::{Ingredient, Step};
+
+    fn recipe(id: &str, title: &str, ingredients: &[&str], steps: &[&str]) -> Recipe {
+        Recipe {
+            id: id.to_owned(),
+            owner_id: "owner".to_owned(),
+            title: title.to_owned(),
+            info: Default::default(),
+            short_description: None,
+            long_description: None,
+            tags: Vec::new(),
+            ingredients: ingredients
+                .iter()
+                .map(|v| Ingredient {
+                    name: v.to_string(),
+                    ..Default::default()
+                })
+                .collect(),
+            steps: steps
+                .iter()
+                .map(|v| Step {
+                    title: None,
+                    description: v.to_string(),
+                })
+                .collect(),
+            labels: Vec::new(),
+            image_id: None,
+        }
+    }
+
+    fn index() -> SearchIndex {
+        SearchIndex::from(vec![
+            SearchDocument::from(&recipe(
+                "1",
+                "Hummus",
+                &["Chickpeas", "Tahini", "Lemon"],
+                &["Blend everything until smooth."],
+            )),
+            SearchDocument::from(&recipe(
+                "2",
+                "Roast Potatoes",
+                &["Potatoes", "Oil"],
+                &["Chop the potatoes.", "Roast until crispy."],
+            )),
+        ])
+    }
+
+    fn ids(results: &[SearchResult]) -> Vec<&str> {
+        results.iter().map(|v| v.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_stem() {
+        assert_eq!(stem("chopped"), stem("chop"));
+        assert_eq!(stem("chopping"), stem("chop"));
+        assert_eq!(stem("potatoes"), stem("potato"));
+        assert_eq!(stem("berries"), stem("berry"));
+        assert_eq!(stem("baked"), stem("bake"));
+        assert_eq!(stem("glass"), "glass");
+    }
+
+    #[test]
+    fn test_tokenize_offsets() {
+        let tokens = tokenize("Chop the crème fraîche!");
+        let words = tokens
+            .iter()
+            .map(|v| &"Chop the crème fraîche!"[v.start..v.end])
+            .collect::<Vec<_>>();
+        assert_eq!(words, vec!["Chop", "crème", "fraîche"]);
+    }
+
+    #[test]
+    fn test_finds_ingredient_not_in_title() {
+        let results = index().search("tahini");
+        assert_eq!(ids(&results), vec!["1"]);
+        let found = &results[0].matches[0];
+        assert_eq!(found.field, SearchField::Ingredient);
+        assert_eq!(found.highlights, vec![(0, 6)]);
+    }
+
+    #[test]
+    fn test_stemmed_fuzzy_and_prefix() {
+        let index = index();
+        assert_eq!(ids(&index.search("chopping")), vec!["2"]);
+        assert_eq!(ids(&index.search("tahnini")), vec!["1"]);
+        assert_eq!(ids(&index.search("pota")), vec!["2"]);
+        // every word has to match
+        assert!(index.search("tahini potato").is_empty());
+        assert!(index.search("").is_empty());
+    }
+
+    #[test]
+    fn test_title_scores_higher() {
+        let mut index = index();
+        index.upsert(SearchDocument::from(&recipe(
+            "3",
+            "Lemon Tart",
+            &["Butter"],
+            &[],
+        )));
+        assert_eq!(ids(&index.search("lemon")), vec!["3", "1"]);
+    }
+
+    #[test]
+    fn test_refresh() {
+        let mut index = index();
+        let unchanged = SearchDocument::from(&recipe(
+            "1",
+            "Hummus",
+            &["Chickpeas", "Tahini", "Lemon"],
+            &["Blend everything until smooth."],
+        ));
+        // only the removed recipe changes the index
+        assert!(index.refresh(vec![unchanged.clone()]));
+        assert_eq!(index.len(), 1);
+        assert!(index.search("potato").is_empty());
+        assert!(!index.refresh(vec![unchanged]));
+    }
+
+    #[test]
+    fn test_snippet() {
+        let text = format!("{} tahini {}", "a".repeat(200), "b".repeat(200));
+        let (snippet, highlights) = snippet(&text, &[(201, 207)]);
+        assert!(snippet.len() < text.len());
+        let (start, end) = highlights[0];
+        assert_eq!(&snippet[start..end], "tahini");
+    }
+
+    #[test]
+    fn test_fuzzy_score() {
+        assert!(fuzzy_score("np", "New Pantry Item").is_some());
+        assert!(fuzzy_score("ip", "New Pantry Item").is_none());
+        assert_eq!(fuzzy_score("", "Anything"), Some(0));
+        // word starts & runs beat scattered letters
+        assert!(fuzzy_score("pan", "Pantry") > fuzzy_score("pan", "Spinach & Bean"));
+    }
+
+    #[test]
+    fn test_serde_rebuilds_terms() {
+        let index = index();
+        let json = serde_json::to_string(&index).unwrap();
+        let index: SearchIndex = serde_json::from_str(&json).unwrap();
+        assert_eq!(ids(&index.search("tahini")), vec!["1"]);
+    }
+}
@@ -0,0 +1,355 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::recipe::{Ingredient, Recipe};
+
+/// Unit for using whole batches of another recipe
+pub const BATCH_UNIT: &str = "batch";
+
+/// Another recipe used as an ingredient, written in the ingredient's name
+/// as a link to it, e.g. `[Pizza Dough](/recipes/<id>)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecipeLink {
+    pub id: String,
+    pub title: String,
+}
+
+impl RecipeLink {
+    pub fn href(&self) -> String {
+        format!("/recipes/{}", self.id)
+    }
+
+    pub fn parse(name: &str) -> Option<Self> {
+        let (title, rest) = name.strip_prefix('[')?.split_once("](/recipes/")?;
+        let id = rest.strip_suffix(')')?;
+        if id.is_empty() || id.contains(['/', ')']) {
+            return None;
+        }
+        Some(Self {
+            id: id.to_owned(),
+            title: title.to_owned(),
+        })
+    }
+
+    /// The ingredient name linking to the recipe
+    pub fn to_name(&self) -> String {
+        // brackets would end the title early
+        let title = self.title.replace(['[', ']'], "");
+        format!("[{title}]({})", self.href())
+    }
+}
+
+impl Ingredient {
+    /// An ingredient using batches of another recipe
+    pub fn sub_recipe(link: &RecipeLink, batches: f32) -> Self {
+        Self {
+            name: link.to_name(),
+            amount: batches,
+            unit_type: BATCH_UNIT.to_owned(),
+            description: None,
+        }
+    }
+
+    pub fn linked_recipe(&self) -> Option<RecipeLink> {
+        RecipeLink::parse(&self.name)
+    }
+
+    /// The name to show, giving the title of linked recipes
+    pub fn display_name(&self) -> String {
+        match self.linked_recipe() {
+            Some(link) => link.title,
+            None => self.name.clone(),
+        }
+    }
+
+    /// How many batches of the linked recipe are used, amounts in the recipe's
+    /// own yield unit are divided by its yield, otherwise the amount is the batches
+    fn batches_of(&self, recipe: &Recipe) -> f32 {
+        match &recipe.info.yields {
+            Some(yields)
+                if yields.value > 0 && yields.unit_type.eq_ignore_ascii_case(&self.unit_type) =>
+            {
+                self.amount / yields.value as f32
+            }
+            _ => self.amount,
+        }
+    }
+}
+
+/// Ingredients with sub-recipes replaced by their own ingredients
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ExpandedIngredients {
+    pub ingredients: Vec<Ingredient>,
+    /// ids of linked recipes that were not loaded, kept as ingredients
+    pub missing: Vec<String>,
+    /// ids of recipes that link back to themselves, kept as ingredients
+    pub cycles: Vec<String>,
+}
+
+/// Expand sub-recipes of a recipe recursively, scaling each by the batches used.
+/// Section headers are left out, as the sections of different recipes can't be told apart.
+pub fn expand_ingredients(
+    recipe: &Recipe,
+    recipes: &HashMap<String, Recipe>,
+    scale: f32,
+) -> ExpandedIngredients {
+    fn expand(
+        recipe: &Recipe,
+        recipes: &HashMap<String, Recipe>,
+        scale: f32,
+        ancestors: &mut Vec<String>,
+        expanded: &mut ExpandedIngredients,
+    ) {
+        ancestors.push(recipe.id.clone());
+        for ingredient in &recipe.ingredients {
+            if ingredient.section_name().is_some() {
+                continue;
+            }
+            let scaled = Ingredient {
+                amount: ingredient.amount * scale,
+                ..ingredient.clone()
+            };
+            let Some(link) = ingredient.linked_recipe() else {
+                expanded.ingredients.push(scaled);
+                continue;
+            };
+            if ancestors.contains(&link.id) {
+                if !expanded.cycles.contains(&link.id) {
+                    expanded.cycles.push(link.id);
+                }
+                expanded.ingredients.push(scaled);
+                continue;
+            }
+            match recipes.get(&link.id) {
+                Some(sub_recipe) => expand(
+                    sub_recipe,
+                    recipes,
+                    scale * ingredient.batches_of(sub_recipe),
+                    ancestors,
+                    expanded,
+                ),
+                None => {
+                    if !expanded.missing.contains(&link.id) {
+                        expanded.missing.push(link.id);
+                    }
+                    expanded.ingredients.push(scaled);
+                }
+            }
+        }
+        ancestors.pop();
+    }
+
+    let mut expanded = ExpandedIngredients::default();
+    expand(recipe, recipes, scale, &mut Vec::new(), &mut expanded);
+    expanded
+}
+
+/// Ids of every recipe linked from these ingredients
+pub fn linked_recipe_ids(ingredients: &[Ingredient]) -> HashSet<String> {
+    ingredients
+        .iter()
+        .filter_map(|v| v.linked_recipe())
+        .map(|v| v.id)
+        .collect()
+}
+
+/// Add up ingredients with the same name & unit, keeping the order they first appear
+pub fn combine_ingredients(ingredients: Vec<Ingredient>) -> Vec<Ingredient> {
+    let mut combined: Vec<Ingredient> = Vec::new();
+    for ingredient in ingredients {
+        let same = combined.iter_mut().find(|v| {
+            v.name.trim().eq_ignore_ascii_case(ingredient.name.trim())
+                && v.unit_type
+                    .trim()
+                    .eq_ignore_ascii_case(ingredient.unit_type.trim())
+        });
+        match same {
+            Some(same) => {
+                same.amount += ingredient.amount;
+                same.description = match (same.description.take(), ingredient.description) {
+                    (Some(a), Some(b)) if !b.is_empty() && a != b => Some(format!("{a}, {b}")),
+                    (a, b) => a.filter(|v| !v.is_empty()).or(b),
+                };
+            }
+            None => combined.push(ingredient),
+        }
+    }
+    combined
+}
+
+#[cfg(test)]
+mod tests_sub_recipe {
+    use super::*;
+    use crate::recipe::{Info, InfoYields};
+
+    fn ingredient(name: &str, amount: f32, unit_type: &str) -> Ingredient {
+        Ingredient {
+            name: name.to_owned(),
+            amount,
+            unit_type: unit_type.to_owned(),
+            description: None,
+        }
+    }
+
+    fn recipe(id: &str, yields: Option<(usize, &str)>, ingredients: Vec<Ingredient>) -> Recipe {
+        Recipe {
+            id: id.to_owned(),
+            owner_id: "owner".to_owned(),
+            title: id.to_owned(),
+            info: Info {
+                yields: yields.map(|(value, unit_type)| InfoYields {
+                    value,
+                    unit_type: unit_type.to_owned(),
+                }),
+                ..Default::default()
+            },
+            short_description: None,
+            long_description: None,
+            tags: vec![],
+            ingredients,
+            steps: vec![],
+            labels: vec![],
+            image_id: None,
+        }
+    }
+
+    fn link(id: &str) -> RecipeLink {
+        RecipeLink {
+            id: id.to_owned(),
+            title: format!("The {id}"),
+        }
+    }
+
+    fn names(expanded: &ExpandedIngredients) -> Vec<(String, f32)> {
+        expanded
+            .ingredients
+            .iter()
+            .map(|v| (v.display_name(), v.amount))
+            .collect()
+    }
+
+    #[test]
+    fn test_recipe_link() {
+        let link = link("abc");
+        assert_eq!(link.to_name(), "[The abc](/recipes/abc)");
+        assert_eq!(RecipeLink::parse(&link.to_name()), Some(link));
+        assert_eq!(RecipeLink::parse("Flour"), None);
+        assert_eq!(RecipeLink::parse("[Dough](https://example.com)"), None);
+        assert_eq!(RecipeLink::parse("[Dough](/recipes/)"), None);
+        assert_eq!(
+            RecipeLink::parse(
+                &RecipeLink {
+                    id: "1".to_owned(),
+                    title: "[odd] title".to_owned(),
+                }
+                .to_name()
+            )
+            .map(|v| v.title),
+            Some("odd title".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_expand() {
+        let dough = recipe(
+            "dough",
+            Some((2, "bases")),
+            vec![
+                ingredient("Flour", 500.0, "g"),
+                ingredient("Water", 300.0, "ml"),
+            ],
+        );
+        let sauce = recipe("sauce", None, vec![ingredient("Tomato", 4.0, "")]);
+        let pizza = recipe(
+            "pizza",
+            Some((4, "servings")),
+            vec![
+                Ingredient::section("Base"),
+                ingredient("Pizza Dough", 1.0, "bases").name_from(&link("dough")),
+                Ingredient::sub_recipe(&link("sauce"), 0.5),
+                ingredient("Cheese", 200.0, "g"),
+            ],
+        );
+        let recipes = HashMap::from([("dough".to_owned(), dough), ("sauce".to_owned(), sauce)]);
+        let expanded = expand_ingredients(&pizza, &recipes, 2.0);
+        assert_eq!(
+            names(&expanded),
+            vec![
+                ("Flour".to_owned(), 500.0),
+                ("Water".to_owned(), 300.0),
+                ("Tomato".to_owned(), 4.0),
+                ("Cheese".to_owned(), 400.0),
+            ]
+        );
+        assert!(expanded.missing.is_empty());
+        assert!(expanded.cycles.is_empty());
+
+        let expanded = expand_ingredients(&pizza, &HashMap::new(), 1.0);
+        assert_eq!(
+            expanded.missing,
+            vec!["dough".to_owned(), "sauce".to_owned()]
+        );
+        assert_eq!(expanded.ingredients.len(), 3);
+    }
+
+    #[test]
+    fn test_expand_cycles() {
+        let a = recipe(
+            "a",
+            None,
+            vec![
+                ingredient("Salt", 1.0, "g"),
+                Ingredient::sub_recipe(&link("b"), 1.0),
+            ],
+        );
+        let b = recipe(
+            "b",
+            None,
+            vec![
+                ingredient("Pepper", 1.0, "g"),
+                Ingredient::sub_recipe(&link("a"), 1.0),
+            ],
+        );
+        let recipes = HashMap::from([("a".to_owned(), a.clone()), ("b".to_owned(), b)]);
+        let expanded = expand_ingredients(&a, &recipes, 1.0);
+        assert_eq!(expanded.cycles, vec!["a".to_owned()]);
+        assert_eq!(
+            names(&expanded),
+            vec![
+                ("Salt".to_owned(), 1.0),
+                ("Pepper".to_owned(), 1.0),
+                ("The a".to_owned(), 1.0),
+            ]
+        );
+
+        let own = recipe("own", None, vec![Ingredient::sub_recipe(&link("own"), 1.0)]);
+        let expanded = expand_ingredients(&own, &HashMap::new(), 1.0);
+        assert_eq!(expanded.cycles, vec!["own".to_owned()]);
+    }
+
+    #[test]
+    fn test_combine() {
+        let combined = combine_ingredients(vec![
+            ingredient("Flour", 100.0, "g"),
+            ingredient("Salt", 1.0, "tsp"),
+            ingredient("flour ", 50.0, "G"),
+            ingredient("Flour", 1.0, "cup"),
+        ]);
+        assert_eq!(
+            combined
+                .iter()
+                .map(|v| (v.name.as_str(), v.amount))
+                .collect::<Vec<_>>(),
+            vec![("Flour", 150.0), ("Salt", 1.0), ("Flour", 1.0)]
+        );
+        assert_eq!(linked_recipe_ids(&combined), HashSet::new());
+    }
+
+    impl Ingredient {
+        fn name_from(self, link: &RecipeLink) -> Self {
+            Self {
+                name: link.to_name(),
+                ..self
+            }
+        }
+    }
+}
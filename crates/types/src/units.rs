@@ -0,0 +1,185 @@
+use serde::{Deserialize, Serialize};
+
+/// Which units ingredient amounts are shown in
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum UnitSystem {
+    /// as the recipe was written
+    #[default]
+    Original,
+    Metric,
+    /// US customary units (cups, ounces, etc)
+    Imperial,
+}
+
+impl UnitSystem {
+    pub const ALL: [Self; 3] = [Self::Original, Self::Metric, Self::Imperial];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Original => "Original",
+            Self::Metric => "Metric",
+            Self::Imperial => "Imperial",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Measure {
+    /// in grams
+    Mass,
+    /// in millilitres
+    Volume,
+}
+
+/// Names a unit may be written as, what it measures & how many grams/millilitres it is
+const UNITS: [(&[&str], Measure, f32, UnitSystem); 10] = [
+    (
+        &["g", "gram", "grams"],
+        Measure::Mass,
+        1.0,
+        UnitSystem::Metric,
+    ),
+    (
+        &["kg", "kilogram", "kilograms"],
+        Measure::Mass,
+        1000.0,
+        UnitSystem::Metric,
+    ),
+    (
+        &[
+            "ml",
+            "millilitre",
+            "millilitres",
+            "milliliter",
+            "milliliters",
+        ],
+        Measure::Volume,
+        1.0,
+        UnitSystem::Metric,
+    ),
+    (
+        &["l", "litre", "litres", "liter", "liters"],
+        Measure::Volume,
+        1000.0,
+        UnitSystem::Metric,
+    ),
+    (
+        &["oz", "ounce", "ounces"],
+        Measure::Mass,
+        28.3495,
+        UnitSystem::Imperial,
+    ),
+    (
+        &["lb", "lbs", "pound", "pounds"],
+        Measure::Mass,
+        453.592,
+        UnitSystem::Imperial,
+    ),
+    (
+        &["tsp", "teaspoon", "teaspoons"],
+        Measure::Volume,
+        4.92892,
+        UnitSystem::Imperial,
+    ),
+    (
+        &["tbsp", "tablespoon", "tablespoons"],
+        Measure::Volume,
+        14.7868,
+        UnitSystem::Imperial,
+    ),
+    (
+        &["fl oz", "fluid ounce", "fluid ounces"],
+        Measure::Volume,
+        29.5735,
+        UnitSystem::Imperial,
+    ),
+    (
+        &["cup", "cups"],
+        Measure::Volume,
+        236.588,
+        UnitSystem::Imperial,
+    ),
+];
+
+/// Unit to show an amount (in grams/millilitres) in, with its size & what to round to
+fn best_unit(base: f32, measure: Measure, system: UnitSystem) -> (&'static str, f32, f32) {
+    match (system, measure) {
+        (UnitSystem::Imperial, Measure::Mass) if base >= 453.592 => ("lb", 453.592, 0.25),
+        (UnitSystem::Imperial, Measure::Mass) => ("oz", 28.3495, 0.25),
+        (UnitSystem::Imperial, Measure::Volume) if base >= 59.147 => ("cup", 236.588, 0.25),
+        (UnitSystem::Imperial, Measure::Volume) if base >= 14.7868 => ("tbsp", 14.7868, 0.5),
+        (UnitSystem::Imperial, Measure::Volume) => ("tsp", 4.92892, 0.125),
+        (_, Measure::Mass) if base >= 1000.0 => ("kg", 1000.0, 0.1),
+        (_, Measure::Mass) => ("g", 1.0, if base >= 10.0 { 1.0 } else { 0.5 }),
+        (_, Measure::Volume) if base >= 1000.0 => ("l", 1000.0, 0.1),
+        (_, Measure::Volume) => ("ml", 1.0, if base >= 10.0 { 1.0 } else { 0.5 }),
+    }
+}
+
+/// An amount in the unit system, rounded to what can be measured out.
+/// Units that are unknown or already in the system are kept,
+/// as are amounts too small to show in it.
+pub fn convert_amount(amount: f32, unit: &str, system: UnitSystem) -> (f32, String) {
+    let unchanged = (amount, unit.to_owned());
+    if system == UnitSystem::Original {
+        return unchanged;
+    }
+    let name = unit.trim().to_lowercase();
+    let Some((_, measure, size, unit_system)) = UNITS
+        .iter()
+        .find(|(names, ..)| names.contains(&name.as_str()))
+    else {
+        return unchanged;
+    };
+    if *unit_system == system {
+        return unchanged;
+    }
+    let base = amount * size;
+    let (new_unit, new_size, step) = best_unit(base, *measure, system);
+    let converted = (base / new_size / step).round() * step;
+    match converted > 0.0 {
+        true => (converted, new_unit.to_owned()),
+        false => unchanged,
+    }
+}
+
+#[cfg(test)]
+mod tests_units {
+    use super::*;
+
+    #[test]
+    fn test_convert_amount() {
+        assert_eq!(
+            convert_amount(2.0, "cups", UnitSystem::Metric),
+            (473.0, "ml".to_owned())
+        );
+        assert_eq!(
+            convert_amount(1.5, "Kg", UnitSystem::Imperial),
+            (3.25, "lb".to_owned())
+        );
+        assert_eq!(
+            convert_amount(5.0, "ml", UnitSystem::Imperial),
+            (1.0, "tsp".to_owned())
+        );
+        assert_eq!(
+            convert_amount(1.2, "lb", UnitSystem::Metric),
+            (544.0, "g".to_owned())
+        );
+    }
+
+    #[test]
+    fn test_convert_amount_kept() {
+        for (amount, unit, system) in [
+            (2.0, "cup", UnitSystem::Original),
+            (2.0, "cup", UnitSystem::Imperial),
+            (3.0, "pinch", UnitSystem::Metric),
+            (0.01, "g", UnitSystem::Imperial),
+        ] {
+            assert_eq!(
+                convert_amount(amount, unit, system),
+                (amount, unit.to_owned())
+            );
+        }
+    }
+}
@@ -1,7 +1,21 @@
 /// General Components
+pub mod barcode;
 pub mod collapse;
+pub mod collections;
+pub mod command_palette;
 pub mod drawer;
+pub mod gallery;
+pub mod image_cropper;
 pub mod image_links;
 pub mod input;
+pub mod labels;
 pub mod loading;
+pub mod markdown;
+pub mod qr_code;
+pub mod recipe_editor;
+pub mod recipe_history;
+pub mod recipe_image;
+pub mod search;
 pub mod stats;
+pub mod tags;
+pub mod virtual_list;
@@ -0,0 +1,234 @@
+use std::time::Duration;
+
+use leptos::{html::Video, *};
+use mcc_frontend_core::barcode::{decode_luma, rgba_to_luma};
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    CanvasRenderingContext2d, HtmlCanvasElement, HtmlVideoElement, MediaStream,
+    MediaStreamConstraints, MediaStreamTrack,
+};
+
+const SCAN_INTERVAL: Duration = Duration::from_millis(300);
+const NATIVE_FORMATS: [&str; 4] = ["ean_13", "ean_8", "upc_a", "upc_e"];
+
+/// Create a `BarcodeDetector`, if the browser supports it
+fn create_native_detector() -> Option<JsValue> {
+    let constructor = js_sys::Reflect::get(&window(), &"BarcodeDetector".into())
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    let options = js_sys::Object::new();
+    let formats = NATIVE_FORMATS
+        .iter()
+        .map(|v| JsValue::from_str(v))
+        .collect::<js_sys::Array>();
+    js_sys::Reflect::set(&options, &"formats".into(), &formats).ok()?;
+    js_sys::Reflect::construct(&constructor, &js_sys::Array::of1(&options)).ok()
+}
+
+async fn detect_native(detector: &JsValue, video: &HtmlVideoElement) -> Option<String> {
+    let detect = js_sys::Reflect::get(detector, &"detect".into())
+        .ok()?
+        .dyn_into::<js_sys::Function>()
+        .ok()?;
+    let promise = detect
+        .call1(detector, video)
+        .ok()?
+        .dyn_into::<js_sys::Promise>()
+        .ok()?;
+    let found = JsFuture::from(promise)
+        .await
+        .ok()?
+        .dyn_into::<js_sys::Array>()
+        .ok()?;
+    js_sys::Reflect::get(&found.get(0), &"rawValue".into())
+        .ok()?
+        .as_string()
+}
+
+/// Decode the current video frame in wasm, used when there is no native detector
+fn detect_fallback(video: &HtmlVideoElement, canvas: &HtmlCanvasElement) -> Option<String> {
+    let (width, height) = (video.video_width(), video.video_height());
+    if width == 0 || height == 0 {
+        return None;
+    }
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = canvas
+        .get_context("2d")
+        .ok()??
+        .dyn_into::<CanvasRenderingContext2d>()
+        .ok()?;
+    context
+        .draw_image_with_html_video_element(video, 0.0, 0.0)
+        .ok()?;
+    let frame = context
+        .get_image_data(0.0, 0.0, width as f64, height as f64)
+        .ok()?;
+    let luma = rgba_to_luma(&frame.data());
+    decode_luma(&luma, width as usize, height as usize)
+}
+
+async fn open_camera() -> Result<MediaStream, JsValue> {
+    let video = js_sys::Object::new();
+    js_sys::Reflect::set(&video, &"facingMode".into(), &"environment".into())?;
+    let mut constraints = MediaStreamConstraints::new();
+    constraints.video(&video);
+    let promise = window()
+        .navigator()
+        .media_devices()?
+        .get_user_media_with_constraints(&constraints)?;
+    JsFuture::from(promise).await?.dyn_into()
+}
+
+fn stop_camera(stream: &MediaStream) {
+    stream
+        .get_tracks()
+        .iter()
+        .filter_map(|track| track.dyn_into::<MediaStreamTrack>().ok())
+        .for_each(|track| track.stop());
+}
+
+/// Scan barcodes using the device camera,
+/// `on_scan` will be called once with the first barcode found.
+#[component]
+pub fn BarcodeScanner<F>(on_scan: F) -> impl IntoView
+where
+    F: Fn(String) + 'static + Copy,
+{
+    let video_ref = create_node_ref::<Video>();
+    let camera_error = create_rw_signal(false);
+    let stream = store_value::<Option<MediaStream>>(None);
+    let detector = store_value(create_native_detector());
+    let canvas = store_value(
+        document()
+            .create_element("canvas")
+            .expect("canvas to be created")
+            .unchecked_into::<HtmlCanvasElement>(),
+    );
+    let detecting = store_value(false);
+    let found = store_value(false);
+
+    video_ref.on_load(move |video| {
+        spawn_local(async move {
+            match open_camera().await {
+                Ok(new_stream) => {
+                    // scanner may have closed while waiting for camera permission
+                    if stream.try_set_value(Some(new_stream.clone())).is_some() {
+                        stop_camera(&new_stream);
+                        return;
+                    }
+                    video.set_src_object(Some(&new_stream));
+                    let _ = video.play();
+                }
+                Err(err) => {
+                    log::error!("unable to open camera: {err:?}");
+                    camera_error.set(true);
+                }
+            }
+        });
+    });
+
+    let interval = set_interval_with_handle(
+        move || {
+            if detecting.get_value() || found.get_value() {
+                return;
+            }
+            let Some(video) = video_ref.get_untracked() else {
+                return;
+            };
+            // HAVE_CURRENT_DATA
+            if video.ready_state() < 2 {
+                return;
+            }
+            detecting.set_value(true);
+            spawn_local(async move {
+                let barcode = match detector.get_value() {
+                    Some(detector) => detect_native(&detector, &video).await,
+                    None => detect_fallback(&video, &canvas.get_value()),
+                };
+                if detecting.try_set_value(false).is_some() {
+                    return;
+                }
+                if let Some(barcode) = barcode {
+                    found.set_value(true);
+                    on_scan(barcode);
+                }
+            });
+        },
+        SCAN_INTERVAL,
+    )
+    .ok();
+
+    on_cleanup(move || {
+        if let Some(interval) = interval {
+            interval.clear();
+        }
+        if let Some(Some(stream)) = stream.try_get_value() {
+            stop_camera(&stream);
+        }
+    });
+
+    view! {
+        <div class="rounded bg-base-300 overflow-hidden">
+            <Show
+                when=move || !camera_error.get()
+                fallback=|| view!{ <p class="p-4 text-center">"Unable to access camera"</p>}
+            >
+                <video
+                    node_ref=video_ref
+                    class="w-full max-h-64 object-cover"
+                    autoplay=true
+                    muted=true
+                    playsinline=true
+                />
+            </Show>
+        </div>
+    }
+}
+
+/// Barcode field with a toggleable camera scanner
+#[component]
+pub fn BarcodeInput<F>(#[prop(into)] value: Signal<Option<String>>, on_change: F) -> impl IntoView
+where
+    F: Fn(Option<String>) + 'static + Copy,
+{
+    let scanning = create_rw_signal(false);
+
+    let on_scan = move |barcode| {
+        scanning.set(false);
+        on_change(Some(barcode));
+    };
+
+    view! {
+        <div class="flex flex-col gap-2">
+            <div class="join w-full">
+                <input
+                    prop:value=move || value.get().unwrap_or_default()
+                    on:input=move |ev| {
+                        let v = event_target_value(&ev);
+                        on_change(if v.is_empty() { None } else { Some(v) });
+                    }
+                    type="text"
+                    inputmode="numeric"
+                    class="input input-bordered w-full join-item"
+                    placeholder="e.g. 5901234123457"
+                    maxlength=14
+                />
+                <button
+                    on:click=move |_| scanning.update(|v| *v = !*v)
+                    type="button"
+                    class="btn join-item"
+                    // class="btn-primary"
+                    class:btn-primary=move || scanning.get()
+                >
+                    {move || if scanning.get() { "Stop" } else { "Scan" }}
+                </button>
+            </div>
+            <Show when=move || scanning.get()>
+                <BarcodeScanner on_scan=on_scan />
+            </Show>
+        </div>
+    }
+}
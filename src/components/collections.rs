@@ -0,0 +1,116 @@
+use leptos::{ev::SubmitEvent, *};
+use mcc_frontend_core::storage;
+use mcc_frontend_types::{collection::Collections, StoredLogin};
+
+use crate::{
+    contexts::prelude::{use_login, use_toasts, CurrentLogin, Toasts},
+    helpers::storage_error_to_toast,
+};
+
+/// Every collection, saved whenever they are updated
+#[derive(Clone, Copy)]
+pub struct CollectionsState {
+    pub collections: RwSignal<Collections>,
+    login: StoredValue<StoredLogin>,
+    toasts: Toasts,
+}
+
+impl CollectionsState {
+    pub fn update(&self, f: impl FnOnce(&mut Collections)) {
+        self.collections.update(|collections| {
+            f(collections);
+            if let Err(err) = self
+                .login
+                .with_value(|login| storage::set_collections(login, collections))
+            {
+                self.toasts
+                    .push(storage_error_to_toast(&err, "collections"));
+            }
+        });
+    }
+}
+
+pub fn use_collections() -> CollectionsState {
+    let CurrentLogin { login, .. } = use_login();
+    let login = store_value(login.get_untracked().expect("expected login to exist"));
+    CollectionsState {
+        collections: create_rw_signal(login.with_value(storage::read_collections)),
+        login,
+        toasts: use_toasts(),
+    }
+}
+
+/// Dropdown to add a recipe to (or remove it from) collections, or start a new one with it
+#[component]
+pub fn AddToCollection(recipe_id: String) -> impl IntoView {
+    let state = use_collections();
+    let recipe_id = store_value(recipe_id);
+    let new_name = create_rw_signal(String::new());
+
+    let on_toggle = move |collection_id: &str, add: bool| {
+        state.update(|collections| {
+            if let Some(collection) = collections.get_mut(collection_id) {
+                recipe_id.with_value(|recipe_id| match add {
+                    true => _ = collection.add_recipe(recipe_id),
+                    false => collection.remove_recipe(recipe_id),
+                });
+            }
+        });
+    };
+
+    let on_new_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let name = new_name.get_untracked().trim().to_owned();
+        if name.is_empty() {
+            return;
+        }
+        state.update(|collections| {
+            let id = collections.create(name, None);
+            if let Some(collection) = collections.get_mut(&id) {
+                recipe_id.with_value(|recipe_id| collection.add_recipe(recipe_id));
+            }
+        });
+        new_name.set(String::new());
+    };
+
+    view! {
+        <div class="dropdown dropdown-bottom">
+            <label tabindex="0" class="btn shadow-lg">"Collections"</label>
+            <div tabindex="0" class="dropdown-content z-10 p-2 w-64 shadow-lg bg-base-200 rounded">
+                <ul class="max-h-64 overflow-y-auto">
+                    {move || state.collections.with(|collections| {
+                        collections.0.iter().map(|collection| {
+                            let id = collection.id.clone();
+                            let in_collection = recipe_id.with_value(|v| collection.contains(v));
+                            view! {
+                                <li>
+                                    <label class="label cursor-pointer justify-start gap-2">
+                                        <input
+                                            prop:checked=in_collection
+                                            on:change=move |ev| on_toggle(&id, event_target_checked(&ev))
+                                            type="checkbox"
+                                            class="checkbox checkbox-sm"
+                                        />
+                                        <span class="label-text">{collection.name.clone()}</span>
+                                    </label>
+                                </li>
+                            }
+                        }).collect_view()
+                    })}
+                </ul>
+                <form on:submit=on_new_submit class="join w-full mt-2">
+                    <input
+                        prop:value=move || new_name.get()
+                        on:input=move |ev| new_name.set(event_target_value(&ev))
+                        type="text"
+                        class="input input-bordered input-sm join-item w-full"
+                        placeholder="New collection..."
+                        aria-label="New collection name"
+                        required=true
+                    />
+                    <button type="submit" class="btn btn-sm join-item">"Add"</button>
+                </form>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,328 @@
+use std::rc::Rc;
+
+use leptos::{html::Input, *};
+use leptos_router::use_navigate;
+use mcc_frontend_core::query::to_query_string;
+use mcc_frontend_types::{query::PantryFilter, search::fuzzy_score};
+use wasm_bindgen::JsCast;
+
+use crate::contexts::prelude::{
+    use_api, use_modal_controller, use_pantry, use_search_index, use_shortcuts, CurrentApi,
+};
+
+// how many results each group can show
+const MAX_GROUP_RESULTS: usize = 5;
+
+const GO_TO: [(&str, &str); 6] = [
+    ("Home", "/"),
+    ("Recipes", "/recipes"),
+    ("Recipe Tags", "/recipes/tags"),
+    ("Search Recipes", "/recipes/search"),
+    ("Pantry", "/pantry"),
+    ("Labels", "/labels"),
+];
+
+#[derive(Clone)]
+struct Command {
+    group: &'static str,
+    title: String,
+    /// keyboard shortcut, if it has one
+    hint: Option<&'static str>,
+    run: Rc<dyn Fn()>,
+}
+
+fn pantry_href(filter: PantryFilter) -> String {
+    format!("/pantry?{}", to_query_string(&filter))
+}
+
+/// Keep the best matching commands for a query, best first
+fn best_matches(query: &str, commands: Vec<Command>) -> Vec<Command> {
+    let mut scored = commands
+        .into_iter()
+        .filter_map(|command| fuzzy_score(query, &command.title).map(|score| (score, command)))
+        .collect::<Vec<_>>();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored
+        .into_iter()
+        .take(MAX_GROUP_RESULTS)
+        .map(|(_, command)| command)
+        .collect()
+}
+
+#[component]
+fn PaletteBox() -> impl IntoView {
+    let shortcuts = use_shortcuts();
+    let search_index = use_search_index();
+    let CurrentApi { api, .. } = use_api();
+    let query = create_rw_signal(String::default());
+    let selected = create_rw_signal(0_usize);
+    let input_ref = create_node_ref::<Input>();
+    let navigate = Rc::new(use_navigate());
+
+    // pantry items & locations are kept between openings, failures just leave them out
+    let pantry = use_pantry();
+    spawn_local(async move {
+        if let Some(api) = api.get_untracked() {
+            pantry.load(&api).await;
+        }
+    });
+    if search_index.index.with_untracked(|v| v.is_empty()) {
+        spawn_local(async move {
+            if let Some(api) = api.get_untracked() {
+                _ = search_index.refresh(&api).await;
+            }
+        });
+    }
+
+    input_ref.on_load(|input| {
+        _ = input.focus();
+    });
+
+    let commands = move || {
+        let query = query.get();
+        let navigate = navigate.clone();
+        let go = move |href: String| -> Rc<dyn Fn()> {
+            let navigate = navigate.clone();
+            Rc::new(move || navigate(&href, Default::default()))
+        };
+
+        // the latest registered action wins when titles clash, e.g. a page's own "New Recipe"
+        let mut actions: Vec<Command> = Vec::new();
+        for action in shortcuts.actions.get().into_iter().rev() {
+            if !actions.iter().any(|v| v.title == action.title) {
+                actions.push(Command {
+                    group: "Actions",
+                    title: action.title,
+                    hint: action.key,
+                    run: action.action,
+                });
+            }
+        }
+        let mut commands = best_matches(&query, actions);
+        commands.extend(best_matches(
+            &query,
+            GO_TO
+                .into_iter()
+                .map(|(title, href)| Command {
+                    group: "Go To",
+                    title: title.to_owned(),
+                    hint: None,
+                    run: go(href.to_owned()),
+                })
+                .collect(),
+        ));
+        if query.trim().is_empty() {
+            return commands;
+        }
+
+        let recipes = search_index.index.with(|index| index.search(&query));
+        commands.extend(
+            recipes
+                .into_iter()
+                .take(MAX_GROUP_RESULTS)
+                .map(|v| Command {
+                    group: "Recipes",
+                    title: v.title,
+                    hint: None,
+                    run: go(format!("/recipes/{}", v.id)),
+                }),
+        );
+        let (items, locations) = pantry.contents.get().unwrap_or_default();
+        commands.extend(best_matches(
+            &query,
+            items
+                .into_iter()
+                .map(|item| Command {
+                    group: "Pantry",
+                    run: go(pantry_href(PantryFilter {
+                        name: Some(item.name.clone()),
+                        ..Default::default()
+                    })),
+                    title: item.name,
+                    hint: None,
+                })
+                .collect(),
+        ));
+        commands.extend(best_matches(
+            &query,
+            locations
+                .into_iter()
+                .map(|location| Command {
+                    group: "Locations",
+                    title: location.name,
+                    hint: None,
+                    run: go(pantry_href(PantryFilter {
+                        location_id: Some(location.id),
+                        ..Default::default()
+                    })),
+                })
+                .collect(),
+        ));
+        commands
+    };
+    let commands = Signal::derive(commands);
+
+    let run = move |i: usize| {
+        let command = commands.with_untracked(|v| v.get(i).map(|v| v.run.clone()));
+        shortcuts.palette_open.set(false);
+        if let Some(run) = command {
+            run();
+        }
+    };
+
+    let on_keydown = move |ev: ev::KeyboardEvent| {
+        let len = commands.with_untracked(|v| v.len());
+        match ev.key().as_str() {
+            "ArrowDown" => selected.update(|v| *v = (*v + 1).min(len.saturating_sub(1))),
+            "ArrowUp" => selected.update(|v| *v = v.saturating_sub(1)),
+            "Enter" => run(selected.get_untracked()),
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+
+    view! {
+        <div
+            on:click=move |_| shortcuts.palette_open.set(false)
+            class="fixed inset-0 z-[60] bg-black/50 flex items-start justify-center pt-24 px-2"
+        >
+            <div
+                on:click=|ev| ev.stop_propagation()
+                class="bg-base-200 rounded-lg shadow-xl w-full max-w-xl"
+                role="dialog"
+                aria-label="Command palette"
+            >
+                <input
+                    node_ref=input_ref
+                    prop:value=move || query.get()
+                    on:input=move |ev| {
+                        query.set(event_target_value(&ev));
+                        selected.set(0);
+                    }
+                    on:keydown=on_keydown
+                    type="text"
+                    class="input input-bordered w-full rounded-b-none"
+                    placeholder="Search or run a command..."
+                />
+                <ul class="menu max-h-96 overflow-y-auto flex-nowrap">
+                    {move || {
+                        let commands = commands.get();
+                        commands.iter().enumerate().map(|(i, command)| {
+                        let show_group = i == 0 || commands[i - 1].group != command.group;
+                        let hint = command.hint;
+                        view!{
+                            {show_group.then(|| view!{ <li class="menu-title">{command.group}</li> })}
+                            <li>
+                                <a
+                                    on:click=move |_| run(i)
+                                    on:mousemove=move |_| selected.set(i)
+                                    class:active=move || selected.get() == i
+                                >
+                                    <span class="mr-auto">{command.title.clone()}</span>
+                                    {hint.map(|key| view!{ <kbd class="kbd kbd-sm">{key}</kbd> })}
+                                </a>
+                            </li>
+                        }
+                    }).collect_view()
+                    }}
+                </ul>
+            </div>
+        </div>
+    }
+}
+
+/// Ctrl+K palette for searching & running page actions
+#[component]
+pub fn CommandPalette() -> impl IntoView {
+    let shortcuts = use_shortcuts();
+    view! {
+        <Show when=move || shortcuts.palette_open.get()>
+            <PaletteBox/>
+        </Show>
+    }
+}
+
+/// Overlay listing the shortcuts that can currently be used
+#[component]
+pub fn ShortcutsHelp() -> impl IntoView {
+    let shortcuts = use_shortcuts();
+    let page_shortcuts = move || {
+        shortcuts
+            .actions
+            .get()
+            .into_iter()
+            .filter_map(|v| v.key.map(|key| (key, v.title)))
+            .collect::<Vec<_>>()
+    };
+
+    view! {
+        <Show when=move || shortcuts.help_open.get()>
+            <div class="modal modal-open" on:click=move |_| shortcuts.help_open.set(false)>
+                <div class="modal-box" on:click=|ev| ev.stop_propagation()>
+                    <h3 class="font-bold text-lg mb-2">"Keyboard Shortcuts"</h3>
+                    <table class="table table-sm">
+                        <tbody>
+                            <tr><td><kbd class="kbd kbd-sm">"Ctrl"</kbd>" + "<kbd class="kbd kbd-sm">"K"</kbd></td><td>"Command Palette"</td></tr>
+                            <tr><td><kbd class="kbd kbd-sm">"?"</kbd></td><td>"Show Shortcuts"</td></tr>
+                            <tr><td><kbd class="kbd kbd-sm">"Esc"</kbd></td><td>"Close"</td></tr>
+                            {move || page_shortcuts().into_iter().map(|(key, title)| view!{
+                                <tr><td><kbd class="kbd kbd-sm">{key}</kbd></td><td>{title}</td></tr>
+                            }).collect_view()}
+                        </tbody>
+                    </table>
+                    <div class="modal-action">
+                        <button on:click=move |_| shortcuts.help_open.set(false) class="btn">"Close"</button>
+                    </div>
+                </div>
+            </div>
+        </Show>
+    }
+}
+
+/// Whether a key press was meant for a text field
+fn is_typing(ev: &ev::KeyboardEvent) -> bool {
+    ev.target()
+        .and_then(|v| v.dyn_into::<web_sys::HtmlElement>().ok())
+        .is_some_and(|element| {
+            matches!(element.tag_name().as_str(), "INPUT" | "TEXTAREA" | "SELECT")
+                || element.is_content_editable()
+        })
+}
+
+/// Listens for the global & page keyboard shortcuts
+#[component]
+pub fn KeyboardShortcuts() -> impl IntoView {
+    let shortcuts = use_shortcuts();
+    let modal_controller = use_modal_controller();
+
+    let handle = window_event_listener(ev::keydown, move |ev| {
+        let key = ev.key();
+        if (ev.ctrl_key() || ev.meta_key()) && key.eq_ignore_ascii_case("k") {
+            ev.prevent_default();
+            shortcuts.help_open.set(false);
+            shortcuts.palette_open.update(|v| *v = !*v);
+            return;
+        }
+        if key == "Escape" {
+            shortcuts.palette_open.set(false);
+            shortcuts.help_open.set(false);
+            return;
+        }
+        if is_typing(&ev)
+            || ev.ctrl_key()
+            || ev.meta_key()
+            || ev.alt_key()
+            || shortcuts.palette_open.get_untracked()
+            || modal_controller.current_modal.get_untracked().is_some()
+        {
+            return;
+        }
+        if key == "?" {
+            ev.prevent_default();
+            shortcuts.help_open.update(|v| *v = !*v);
+        } else if shortcuts.run_key(&key) {
+            ev.prevent_default();
+        }
+    });
+    on_cleanup(move || handle.remove());
+}
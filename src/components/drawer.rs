@@ -2,11 +2,13 @@ use leptos::*;
 use leptos_router::A;
 use mcc_frontend_core::APP_TITLE;
 
-use crate::contexts::prelude::{use_login, CurrentLogin};
+use super::search::SearchBox;
+use crate::contexts::prelude::{use_login, use_shortcuts, CurrentLogin};
 
 #[component]
 fn DrawerHeader() -> impl IntoView {
     let CurrentLogin { login, .. } = use_login();
+    let shortcuts = use_shortcuts();
 
     view! {
         <div class="navbar bg-base-300/[.5] backdrop-blur-md shadow-[0_10px_15px_-3px_rgba(0,0,0,0.4)] sticky top-0 z-40">
@@ -19,6 +21,15 @@ fn DrawerHeader() -> impl IntoView {
                 <span class="text-xl p-2">{APP_TITLE}</span>
             </div>
             <div class="flex-none">
+                <Show when=move || login.get().is_some()>
+                    <button
+                        on:click=move |_| shortcuts.palette_open.set(true)
+                        class="btn btn-ghost hidden sm:inline-flex"
+                        title="Command Palette"
+                    >
+                        <kbd class="kbd kbd-sm">"Ctrl"</kbd><kbd class="kbd kbd-sm">"K"</kbd>
+                    </button>
+                </Show>
                 {move || {
                     if login.get().is_none() {
                         view!( <A href="/login" class="btn btn-ghost">"Login"</A>)
@@ -46,7 +57,13 @@ impl DrawerLink {
 }
 
 #[component]
-pub fn Drawer(#[prop(into)] links: Vec<DrawerLink>, children: Children) -> impl IntoView {
+pub fn Drawer(
+    #[prop(into)] links: Vec<DrawerLink>,
+    /// show a recipe search box above the links
+    #[prop(optional)]
+    search: bool,
+    children: Children,
+) -> impl IntoView {
     view! {
         <div class="drawer lg:drawer-open">
             <input id="main-drawer" type="checkbox" class="drawer-toggle" />
@@ -59,6 +76,7 @@ pub fn Drawer(#[prop(into)] links: Vec<DrawerLink>, children: Children) -> impl
             <div class="drawer-side z-50">
                 <label for="main-drawer" class="drawer-overlay"></label>
                 <ul class="menu gap-2 p-4 w-80 bg-base-300 h-full">
+                    {search.then(|| view!{ <li class="mb-2"><SearchBox/></li> })}
                     {links.into_iter().map(|link|{
                         view!{ <li><A href={link.href} active_class="active" exact=true class="bg-base-200 dark:bg-base-100">{link.text}</A></li>}
                     }).collect::<Vec<_>>()}
@@ -0,0 +1,401 @@
+use leptos::{ev::Event, *};
+use mcc_frontend_core::{
+    api::{ApiError, ApiInternalError},
+    image_store,
+    storage::{self, StorageError},
+};
+use mcc_frontend_types::{
+    gallery::{GalleryImage, RecipeGallery},
+    StoredLogin,
+};
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, File, HtmlInputElement, Url};
+
+use crate::{
+    components::image_cropper::{prepare_image, DEFAULT_IMAGE_MAX_SIZE},
+    contexts::prelude::{use_api, use_login, use_toasts, CurrentApi, CurrentLogin, Toast, Toasts},
+    helpers::{api_error_to_toast, logout_on_401, storage_error_to_toast},
+};
+
+/// Where an image is kept
+#[derive(Debug, Clone, PartialEq)]
+pub enum ImageSource {
+    /// the recipe's image held by the server
+    Cover(String),
+    /// an image held by this browser
+    Local(String),
+}
+
+impl ImageSource {
+    /// The cover followed by the given gallery images
+    pub fn with_cover(cover: Option<String>, images: Vec<GalleryImage>) -> Vec<Self> {
+        cover
+            .map(Self::Cover)
+            .into_iter()
+            .chain(images.into_iter().map(|v| Self::Local(v.key)))
+            .collect()
+    }
+}
+
+/// The gallery of a recipe, saved whenever it is updated
+#[derive(Clone, Copy)]
+pub struct GalleryState {
+    pub gallery: RwSignal<RecipeGallery>,
+    login: StoredValue<StoredLogin>,
+    id: StoredValue<String>,
+    toasts: Toasts,
+}
+
+impl GalleryState {
+    pub fn update(&self, f: impl FnOnce(&mut RecipeGallery)) {
+        self.gallery.update(|gallery| {
+            f(gallery);
+            self.login.with_value(|login| {
+                let mut galleries = storage::read_recipe_galleries(login);
+                self.id
+                    .with_value(|id| galleries.set_gallery(id, gallery.clone()));
+                if let Err(err) = storage::set_recipe_galleries(login, &galleries) {
+                    self.toasts
+                        .push(storage_error_to_toast(&err, "the recipe's gallery"));
+                }
+            });
+        });
+    }
+}
+
+pub fn use_recipe_gallery(id: String) -> GalleryState {
+    let CurrentLogin { login, .. } = use_login();
+    let login = store_value(login.get_untracked().expect("expected login to exist"));
+    let gallery = create_rw_signal(
+        login.with_value(|login| storage::read_recipe_galleries(login).gallery(&id)),
+    );
+    GalleryState {
+        gallery,
+        login,
+        id: store_value(id),
+        toasts: use_toasts(),
+    }
+}
+
+/// Forget a recipe's gallery & remove its images, e.g. once the recipe is deleted
+pub async fn delete_recipe_gallery(login: &StoredLogin, id: &str) -> Result<(), StorageError> {
+    let mut galleries = storage::read_recipe_galleries(login);
+    if let Some(gallery) = galleries.0.remove(id) {
+        storage::set_recipe_galleries(login, &galleries)?;
+        for image in gallery.0 {
+            _ = image_store::delete_image(&image.key).await;
+        }
+    }
+    Ok(())
+}
+
+#[component]
+pub fn GalleryImg(
+    source: ImageSource,
+    #[prop(into, optional)] class: String,
+    #[prop(into, optional)] alt: String,
+) -> impl IntoView {
+    let CurrentLogin { login, .. } = use_login();
+    match source {
+        ImageSource::Cover(image_id) => {
+            let media_url = login
+                .get_untracked()
+                .expect("expected login to exist")
+                .media_url;
+            view! { <img class=class alt=alt src=format!("{media_url}/recipe-image/{image_id}") /> }
+                .into_view()
+        }
+        ImageSource::Local(key) => {
+            // object urls keep the image in memory until revoked
+            let object_url = store_value(None::<String>);
+            let url = create_resource(
+                || {},
+                move |_| {
+                    let key = key.clone();
+                    async move {
+                        let url = match image_store::get_image(&key).await {
+                            Ok(Some(blob)) => Url::create_object_url_with_blob(&blob).ok(),
+                            _ => None,
+                        };
+                        object_url.set_value(url.clone());
+                        url
+                    }
+                },
+            );
+            on_cleanup(move || {
+                if let Some(url) = object_url.get_value() {
+                    _ = Url::revoke_object_url(&url);
+                }
+            });
+            view! {
+                {move || match url.get() {
+                    Some(Some(url)) => view! { <img class=class.clone() alt=alt.clone() src=url /> }.into_view(),
+                    Some(None) => view! {
+                        <div class=format!("{class} flex items-center justify-center bg-base-300 text-sm p-2")>
+                            "Image not on this device"
+                        </div>
+                    }.into_view(),
+                    None => view! { <div class=format!("{class} bg-base-300")></div> }.into_view(),
+                }}
+            }
+            .into_view()
+        }
+    }
+}
+
+/// Full screen viewer, moving between images with the arrow keys
+#[component]
+pub fn Lightbox(images: Vec<ImageSource>, index: RwSignal<Option<usize>>) -> impl IntoView {
+    let count = images.len();
+    let images = store_value(images);
+    let step = move |by: isize| {
+        index.update(|index| {
+            if let Some(index) = index {
+                *index = (*index as isize + by).rem_euclid(count as isize) as usize;
+            }
+        })
+    };
+
+    let handle = window_event_listener(ev::keydown, move |ev| match ev.key().as_str() {
+        "Escape" => index.set(None),
+        "ArrowLeft" => step(-1),
+        "ArrowRight" => step(1),
+        _ => {}
+    });
+    on_cleanup(move || handle.remove());
+
+    view! {
+        <div
+            on:click=move |_| index.set(None)
+            class="fixed inset-0 z-50 flex flex-col items-center justify-center gap-2 p-4 bg-black/[.85]"
+            role="dialog"
+            aria-label="Image viewer"
+        >
+            {move || index.get().and_then(|index| images.with_value(|v| v.get(index).cloned())).map(|source| view! {
+                <GalleryImg source=source class="max-h-[85vh] max-w-full object-contain rounded" />
+            })}
+            <div on:click=|ev| ev.stop_propagation() class="flex items-center gap-2">
+                <button on:click=move |_| step(-1) class="btn btn-sm" disabled=count < 2 aria-label="Previous image">"<"</button>
+                <span class="text-white">{move || format!("{} / {count}", index.get().unwrap_or_default() + 1)}</span>
+                <button on:click=move |_| step(1) class="btn btn-sm" disabled=count < 2 aria-label="Next image">">"</button>
+                <button on:click=move |_| index.set(None) class="btn btn-sm">"Close"</button>
+            </div>
+        </div>
+    }
+}
+
+/// Row of thumbnails, opening the viewer when clicked
+#[component]
+pub fn ImageStrip(images: Vec<ImageSource>) -> impl IntoView {
+    let open = create_rw_signal(None::<usize>);
+    let lightbox_images = images.clone();
+    view! {
+        <div class="flex flex-wrap gap-2">
+            {images.into_iter().enumerate().map(|(index, source)| view! {
+                <button on:click=move |_| open.set(Some(index)) type="button" aria-label=format!("View image {}", index + 1)>
+                    <GalleryImg source=source class="h-20 w-20 object-cover rounded" />
+                </button>
+            }).collect_view()}
+        </div>
+        <Show when=move || open.get().is_some() fallback=|| ()>
+            <Lightbox images=lightbox_images.clone() index=open />
+        </Show>
+    }
+}
+
+fn blob_to_file(blob: &Blob) -> Result<File, ApiError> {
+    File::new_with_blob_sequence(&js_sys::Array::of1(blob), "image")
+        .map_err(|_| ApiError::Internal(ApiInternalError::Generic))
+}
+
+/// Add, order & remove the extra images of a recipe, or make one the cover
+#[component]
+pub fn GalleryEditor<F>(
+    id: String,
+    state: GalleryState,
+    /// the current cover
+    image_id: Signal<Option<String>>,
+    /// how many steps images can be attached to, as numbered when last saved
+    step_count: Signal<usize>,
+    on_cover_change: F,
+) -> impl IntoView
+where
+    F: Fn(Option<String>) + 'static + Copy,
+{
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, set_login } = use_login();
+    let gallery = state.gallery;
+    let id = store_value(id);
+
+    let add_action = create_action(move |files: &Vec<File>| {
+        let files = files.clone();
+        async move {
+            let max_size = storage::read_image_max_size().unwrap_or(DEFAULT_IMAGE_MAX_SIZE);
+            for file in files {
+                // upright, resized & without metadata, like the cover
+                let Ok(file) = prepare_image(&file, max_size).await else {
+                    toasts.push(Toast {
+                        message: "Could not process image, when adding to gallery".to_owned(),
+                    });
+                    continue;
+                };
+                let key = image_store::new_image_key();
+                match image_store::put_image(&key, &file).await {
+                    Ok(_) => state.update(|gallery| gallery.add(key.clone(), None)),
+                    Err(err) => toasts.push(api_error_to_toast(&err, "storing image")),
+                }
+            }
+        }
+    });
+
+    let delete_action = create_action(move |key: &String| {
+        let key = key.clone();
+        async move {
+            state.update(|gallery| {
+                gallery.remove(&key);
+            });
+            _ = image_store::delete_image(&key).await;
+        }
+    });
+
+    // the image is uploaded as the cover, with the old cover taking its place in the gallery
+    let cover_action = create_action(move |key: &String| {
+        let key = key.clone();
+        let api = api.get_untracked().expect("api expected to exist");
+        let media_url = login
+            .get_untracked()
+            .expect("expected login to exist")
+            .media_url;
+        let old_image_id = image_id.get_untracked();
+        async move {
+            let result: Result<String, ApiError> = async {
+                let Some(image) = image_store::get_image(&key).await? else {
+                    return Err(ApiError::Internal(ApiInternalError::Generic));
+                };
+                let file = blob_to_file(&image)?;
+                // the old cover is stored in this browser before it is replaced on the server,
+                // so it isn't lost when storing fails (e.g. the storage is full)
+                let old_key = match &old_image_id {
+                    Some(old_image_id) => {
+                        let old_image = api.get_recipe_image(&media_url, old_image_id).await?;
+                        let old_key = image_store::new_image_key();
+                        image_store::put_image(&old_key, &old_image).await?;
+                        Some(old_key)
+                    }
+                    None => None,
+                };
+                let new_image_id = match api.post_recipe_image(id.get_value(), file).await {
+                    Ok(new_image_id) => new_image_id,
+                    Err(err) => {
+                        // the old cover is still the cover, so its copy isn't needed
+                        if let Some(old_key) = &old_key {
+                            _ = image_store::delete_image(old_key).await;
+                        }
+                        return Err(err);
+                    }
+                };
+                match old_key {
+                    Some(old_key) => state.update(|gallery| gallery.replace(&key, old_key)),
+                    None => state.update(|gallery| {
+                        gallery.remove(&key);
+                    }),
+                }
+                _ = image_store::delete_image(&key).await;
+                Ok(new_image_id)
+            }
+            .await;
+            match result {
+                Ok(new_image_id) => on_cover_change(Some(new_image_id)),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "changing cover image"));
+                    logout_on_401(&set_login, &err);
+                }
+            }
+        }
+    });
+
+    let on_files_change = move |ev: Event| {
+        let input = ev.target().unwrap().unchecked_into::<HtmlInputElement>();
+        if let Some(files) = input.files() {
+            let files = (0..files.length())
+                .filter_map(|i| files.get(i))
+                .collect::<Vec<_>>();
+            add_action.dispatch(files);
+        }
+        input.set_value("");
+    };
+
+    view! {
+        <label class="join w-full mb-2">
+            <span class="label p-3 bg-base-300 join-item">"Add Images"</span>
+            <input
+                on:change=on_files_change
+                type="file"
+                class="file-input file-input-bordered w-full join-item"
+                accept="image/*"
+                multiple=true
+            />
+        </label>
+        <div class="grid grid-cols-[repeat(auto-fill,minmax(12rem,1fr))] gap-2">
+            <For
+                each=move || gallery.get().0
+                key=|image| (image.key.clone(), image.step)
+                children=move |image| {
+                    let key = store_value(image.key.clone());
+                    view! {
+                        <div class="p-2 rounded bg-base-100 flex flex-col gap-2">
+                            <GalleryImg source=ImageSource::Local(image.key.clone()) class="h-32 w-full object-cover rounded" />
+                            <select
+                                on:change=move |ev| {
+                                    let step = event_target_value(&ev).parse::<usize>().ok();
+                                    key.with_value(|key| state.update(|gallery| gallery.set_step(key, step)));
+                                }
+                                class="select select-bordered select-sm"
+                                aria-label="Show with"
+                            >
+                                <option value="" selected=image.step.is_none()>"Whole Recipe"</option>
+                                {move || (1..=step_count.get().max(image.step.unwrap_or_default())).map(|number| view! {
+                                    <option value=number selected=image.step == Some(number)>{format!("Step {number}")}</option>
+                                }).collect_view()}
+                            </select>
+                            <div class="join">
+                                <button
+                                    on:click=move |_| key.with_value(|key| state.update(|gallery| gallery.move_image(key, true)))
+                                    type="button"
+                                    class="btn btn-sm join-item"
+                                    aria-label="Move earlier"
+                                >
+                                    "<"
+                                </button>
+                                <button
+                                    on:click=move |_| key.with_value(|key| state.update(|gallery| gallery.move_image(key, false)))
+                                    type="button"
+                                    class="btn btn-sm join-item"
+                                    aria-label="Move later"
+                                >
+                                    ">"
+                                </button>
+                                <button
+                                    on:click=move |_| cover_action.dispatch(key.get_value())
+                                    type="button"
+                                    class="btn btn-sm join-item"
+                                    disabled=move || cover_action.pending().get()
+                                >
+                                    "Make Cover"
+                                </button>
+                                <button
+                                    on:click=move |_| delete_action.dispatch(key.get_value())
+                                    type="button"
+                                    class="btn btn-sm btn-error join-item"
+                                >
+                                    "Delete"
+                                </button>
+                            </div>
+                        </div>
+                    }
+                }
+            />
+        </div>
+    }
+}
@@ -0,0 +1,274 @@
+use leptos::{
+    ev::{KeyboardEvent, PointerEvent},
+    html::{Canvas, Div},
+    *,
+};
+use mcc_frontend_core::image::{fit_size, CropRect, CARD_ASPECT_RATIO};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, CanvasRenderingContext2d, File, FilePropertyBag, HtmlCanvasElement, HtmlImageElement, Url,
+};
+
+/// Sizes (of the longest side) images can be resized to before uploading
+pub const IMAGE_MAX_SIZES: [u32; 4] = [800, 1200, 1600, 2400];
+pub const DEFAULT_IMAGE_MAX_SIZE: u32 = 1600;
+const JPEG_QUALITY: f64 = 0.85;
+// how far the arrow keys move the crop, as a fraction of the crop size
+const KEY_MOVE_FRACTION: f64 = 0.05;
+const MAX_ZOOM: f64 = 4.0;
+
+/// Where preparing & uploading an image is up to
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImageStage {
+    Reading,
+    Encoding,
+    Uploading,
+}
+
+impl ImageStage {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Reading => "Reading image...",
+            Self::Encoding => "Resizing image...",
+            Self::Uploading => "Uploading image...",
+        }
+    }
+
+    pub fn percent(&self) -> u32 {
+        match self {
+            Self::Reading => 20,
+            Self::Encoding => 50,
+            Self::Uploading => 80,
+        }
+    }
+}
+
+fn context_2d(canvas: &HtmlCanvasElement) -> Result<CanvasRenderingContext2d, JsValue> {
+    canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into()
+        .map_err(JsValue::from)
+}
+
+/// Draw an image file into a canvas upright, returning its size.
+/// Browsers apply the EXIF orientation when decoding (`image-orientation: from-image`),
+/// so the natural size & drawn pixels are already upright.
+pub async fn draw_upright(file: &File, canvas: &HtmlCanvasElement) -> Result<(f64, f64), JsValue> {
+    let image = HtmlImageElement::new()?;
+    let url = Url::create_object_url_with_blob(file)?;
+    image.set_src(&url);
+    let decoded = JsFuture::from(image.decode()).await;
+    Url::revoke_object_url(&url)?;
+    decoded?;
+
+    let (width, height) = (image.natural_width() as f64, image.natural_height() as f64);
+    canvas.set_width(width as u32);
+    canvas.set_height(height as u32);
+    context_2d(canvas)?.draw_image_with_html_image_element(&image, 0.0, 0.0)?;
+    Ok((width, height))
+}
+
+/// Re-encode part of a canvas as a JPEG no larger than max_size,
+/// which leaves behind any metadata the original file had
+pub async fn encode_jpeg(
+    source: &HtmlCanvasElement,
+    crop: CropRect,
+    max_size: u32,
+) -> Result<File, JsValue> {
+    let (width, height) = fit_size(crop.width, crop.height, max_size);
+    let canvas = document()
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width(width);
+    canvas.set_height(height);
+    let context = context_2d(&canvas)?;
+    context.draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+        source,
+        crop.x,
+        crop.y,
+        crop.width,
+        crop.height,
+        0.0,
+        0.0,
+        width as f64,
+        height as f64,
+    )?;
+
+    let blob = JsFuture::from(js_sys::Promise::new(&mut |resolve, reject| {
+        let on_blob_reject = reject.clone();
+        let callback = Closure::once_into_js(move |blob: JsValue| {
+            if blob.is_null() {
+                _ = on_blob_reject.call1(&JsValue::NULL, &"could not encode image".into());
+            } else {
+                _ = resolve.call1(&JsValue::NULL, &blob);
+            }
+        });
+        if let Err(err) = canvas.to_blob_with_type_and_encoder_options(
+            callback.unchecked_ref(),
+            "image/jpeg",
+            &JPEG_QUALITY.into(),
+        ) {
+            _ = reject.call1(&JsValue::NULL, &err);
+        }
+    }))
+    .await?
+    .dyn_into::<Blob>()?;
+    let mut options = FilePropertyBag::new();
+    options.type_("image/jpeg");
+    File::new_with_blob_sequence_and_options(&js_sys::Array::of1(&blob), "image.jpg", &options)
+}
+
+/// Upright, resized & metadata free copy of an image, keeping all of it
+pub async fn prepare_image(file: &File, max_size: u32) -> Result<File, JsValue> {
+    let canvas = document()
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    let (width, height) = draw_upright(file, &canvas).await?;
+    let whole = CropRect {
+        x: 0.0,
+        y: 0.0,
+        width,
+        height,
+    };
+    encode_jpeg(&canvas, whole, max_size).await
+}
+
+/// Pick the largest size to resize to
+#[component]
+pub fn ImageSizeSelect(max_size: RwSignal<u32>) -> impl IntoView {
+    view! {
+        <label class="join">
+            <span class="label p-3 bg-base-300 join-item">"Max Size"</span>
+            <select
+                on:change=move |ev| {
+                    if let Ok(size) = event_target_value(&ev).parse() {
+                        max_size.set(size);
+                    }
+                }
+                class="select select-bordered join-item"
+            >
+                {IMAGE_MAX_SIZES.into_iter().map(|size| view! {
+                    <option value=size selected=move || max_size.get() == size>{format!("{size}px")}</option>
+                }).collect_view()}
+            </select>
+        </label>
+    }
+}
+
+/// Show an upright image in a canvas, with a card shaped crop that can be dragged & zoomed.
+/// `size` is set once the image is drawn, the crop is in pixels of the image.
+#[component]
+pub fn ImageCropper(
+    canvas_ref: NodeRef<Canvas>,
+    size: RwSignal<Option<(f64, f64)>>,
+    crop: RwSignal<CropRect>,
+) -> impl IntoView {
+    let container_ref = create_node_ref::<Div>();
+    let zoom = create_rw_signal(1.0);
+    // pointer position the drag was last at
+    let dragging = create_rw_signal(None::<(i32, i32)>);
+
+    // how many image pixels one displayed pixel is
+    let scale = move || {
+        let shown = container_ref
+            .get_untracked()
+            .map(|v| v.offset_width() as f64)
+            .unwrap_or_default();
+        match size.get_untracked() {
+            Some((width, _)) if shown > 0.0 => width / shown,
+            _ => 1.0,
+        }
+    };
+    let move_by = move |dx: f64, dy: f64| {
+        if let Some((width, height)) = size.get_untracked() {
+            crop.update(|crop| *crop = crop.moved(dx, dy, width, height));
+        }
+    };
+
+    let on_pointer_down = move |ev: PointerEvent| {
+        ev.prevent_default();
+        dragging.set(Some((ev.client_x(), ev.client_y())));
+    };
+    let on_pointer_move = move |ev: PointerEvent| {
+        if let Some((x, y)) = dragging.get_untracked() {
+            let scale = scale();
+            move_by(
+                (ev.client_x() - x) as f64 * scale,
+                (ev.client_y() - y) as f64 * scale,
+            );
+            dragging.set(Some((ev.client_x(), ev.client_y())));
+        }
+    };
+    let on_key_down = move |ev: KeyboardEvent| {
+        let step = crop.with_untracked(|v| v.width) * KEY_MOVE_FRACTION;
+        let (dx, dy) = match ev.key().as_str() {
+            "ArrowLeft" => (-step, 0.0),
+            "ArrowRight" => (step, 0.0),
+            "ArrowUp" => (0.0, -step),
+            "ArrowDown" => (0.0, step),
+            _ => return,
+        };
+        ev.prevent_default();
+        move_by(dx, dy);
+    };
+    let on_zoom = move |ev| {
+        let Ok(value) = event_target_value(&ev).parse::<f64>() else {
+            return;
+        };
+        zoom.set(value);
+        if let Some((width, height)) = size.get_untracked() {
+            crop.update(|crop| *crop = crop.zoomed(width, height, CARD_ASPECT_RATIO, value));
+        }
+    };
+
+    // position of the crop, as percentages of the image
+    let crop_style = move || {
+        let Some((width, height)) = size.get() else {
+            return "display: none".to_owned();
+        };
+        crop.with(|crop| {
+            format!(
+                "left: {}%; top: {}%; width: {}%; height: {}%",
+                crop.x / width * 100.0,
+                crop.y / height * 100.0,
+                crop.width / width * 100.0,
+                crop.height / height * 100.0,
+            )
+        })
+    };
+
+    view! {
+        <div
+            node_ref=container_ref
+            on:pointermove=on_pointer_move
+            on:pointerup=move |_| dragging.set(None)
+            on:pointerleave=move |_| dragging.set(None)
+            class="relative overflow-hidden rounded touch-none select-none"
+        >
+            <canvas node_ref=canvas_ref class="block w-full h-auto" />
+            <div
+                on:pointerdown=on_pointer_down
+                on:keydown=on_key_down
+                style=crop_style
+                class="absolute border-2 border-white cursor-move shadow-[0_0_0_9999px_rgba(0,0,0,0.5)]"
+                tabindex="0"
+                role="slider"
+                aria-label="Crop position, move with the arrow keys"
+            />
+        </div>
+        <label class="flex items-center gap-2 mt-2">
+            <span class="label-text">"Zoom"</span>
+            <input
+                on:input=on_zoom
+                prop:value=move || zoom.get()
+                type="range"
+                min="1"
+                max=MAX_ZOOM
+                step="0.1"
+                class="range range-sm"
+            />
+        </label>
+    }
+}
@@ -1,12 +1,24 @@
-use leptos::*;
+use leptos::{html::Div, *};
 use leptos_router::A;
 
+use crate::components::{
+    labels::LabelBadge, recipe_image::RecipeImage, virtual_list::use_virtual_rows,
+};
+
+// height of a square card in the single column grid, incl. the gap
+const GRID_ROW_HEIGHT: f64 = 400.0;
+
 #[derive(Clone)]
 pub struct ImageLinkItem {
     pub key: String,
     pub href: String,
     pub title: String,
-    pub image_src: Option<String>,
+    /// id of the recipe image, loaded once near the screen
+    pub image_id: Option<String>,
+    /// shown over the image
+    pub tags: Vec<String>,
+    /// shown over the image, below the title
+    pub labels: Vec<String>,
 }
 
 #[component]
@@ -16,19 +28,30 @@ fn ImageLinkGridItem(item: ImageLinkItem) -> impl IntoView {
             href={item.href}
             class="bg-base-100 shadow-xl aspect-square w-full relative rounded-md">
             {move || {
-                if let Some(image_src) = &item.image_src {
+                if let Some(image_id) = &item.image_id {
                     view! {
-                        <img class="object-cover w-full h-full rounded-lg hover:brightness-50 hover:duration-200"
-                            src={image_src}
+                        <RecipeImage
+                            image_id=image_id.clone()
+                            class="object-cover w-full h-full rounded-lg hover:brightness-50 hover:duration-200"
                         />
-                    }.into_any()
+                    }.into_view()
                 } else {
                     view! { <div
                         class="w-full h-full rounded-md hover:bg-neutral-focus hover:duration-200">
                         </div>
-                    }.into_any()
+                    }.into_view()
                 }
             }}
+            <div class="absolute top-0 left-0 p-1 flex flex-wrap gap-1">
+                {item.tags.into_iter().map(|tag| view!{
+                    <span class="badge badge-secondary shadow-md">{tag}</span>
+                }).collect_view()}
+            </div>
+            <div class="absolute bottom-10 left-0 p-1 flex flex-wrap gap-1">
+                {item.labels.into_iter().map(|label| view!{
+                    <LabelBadge label=label />
+                }).collect_view()}
+            </div>
             <span
                 class="absolute bottom-0 left-0 p-1 w-full bg-[#000000cc] rounded-b-md whitespace-nowrap \
                     overflow-hidden text-ellipsis text-lg font-bold text-slate-300">
@@ -38,12 +61,35 @@ fn ImageLinkGridItem(item: ImageLinkItem) -> impl IntoView {
     }
 }
 
+/// Grid of links, only rendering the rows near the screen
 #[component]
-pub fn ImageLinksBox(items: ReadSignal<Vec<ImageLinkItem>>) -> impl IntoView {
+pub fn ImageLinksBox(#[prop(into)] items: Signal<Vec<ImageLinkItem>>) -> impl IntoView {
+    let grid_ref = create_node_ref::<Div>();
+    let rows = use_virtual_rows(
+        grid_ref,
+        Signal::derive(move || items.with(Vec::len)),
+        GRID_ROW_HEIGHT,
+    );
+    // rows left out are replaced by padding, so the grid's gap stays the same
+    let padding = move || {
+        format!(
+            "padding-top: calc(0.75rem + {}px); padding-bottom: calc(0.75rem + {}px)",
+            rows.before(),
+            rows.after(),
+        )
+    };
+
     view! {
-        <div class="grid gap-4 grid-cols-1 md:grid-cols-3 lg:grid-cols-5 rounded p-3 bg-base-200">
+        <div
+            node_ref=grid_ref
+            style=padding
+            class="grid gap-4 grid-cols-1 md:grid-cols-3 lg:grid-cols-5 rounded p-3 bg-base-200"
+        >
             <For
-                each=move || items.get()
+                each=move || {
+                    let visible = rows.visible.get();
+                    items.with(|v| v.get(visible.start..visible.end).map(<[_]>::to_vec).unwrap_or_default())
+                }
                 key=move |item| item.key.to_owned()
                 children=move |item: ImageLinkItem| view!{
                     <ImageLinkGridItem item={item}/>
@@ -1,6 +1,11 @@
 use leptos::*;
 use mcc_frontend_core::api::sanitise_base_url;
 use mcc_frontend_types::{Fraction, HourMinuteSecond};
+
+use crate::{
+    components::labels::{chip_class, label_text},
+    contexts::prelude::{use_label_meta, CurrentLabelMeta},
+};
 use regex::Regex;
 use std::collections::HashSet;
 use url::Url;
@@ -302,10 +307,19 @@ pub fn LabelSelector<F>(
     #[prop(optional)] compact: bool,
     #[prop(into)] selected: Signal<HashSet<String>>,
     on_change: F,
+    /// needed when there are multiple selectors on the page
+    #[prop(optional, into)]
+    list_id: Option<String>,
+    #[prop(optional, into)] placeholder: Option<String>,
+    /// ignore label colours & icons, e.g. when selecting tags
+    #[prop(optional)]
+    unstyled: bool,
 ) -> impl IntoView
 where
     F: Fn(HashSet<String>) + 'static + Copy,
 {
+    let CurrentLabelMeta { metadata, .. } = use_label_meta();
+    let list_id = list_id.unwrap_or_else(|| "labels".to_owned());
     let label_input = create_rw_signal(String::default());
     let label_input_invalid = Signal::derive(move || {
         if !label_input.get().is_empty() && !allow_new && !labels.get().contains(&label_input.get())
@@ -337,9 +351,19 @@ where
                     each=move || selected.get().into_iter()
                     key=|name| name.to_owned()
                     children=move |label_name| {
+                        let style_name = label_name.clone();
+                        let icon_name = label_name.clone();
                         view!{
-                        <div class="inline-flex items-center bg-primary p-1 gap-2 rounded-lg shadow-md">
-                            <span class="text-primary-content">{&label_name}</span>
+                        <div class=move || format!(
+                            "inline-flex items-center p-1 gap-2 rounded-lg shadow-md {}",
+                            chip_class((!unstyled).then(|| metadata.get().color(&style_name)).flatten()),
+                        )>
+                            <span>
+                                {move || match unstyled {
+                                    true => icon_name.clone(),
+                                    false => label_text(metadata.get().icon(&icon_name), &icon_name),
+                                }}
+                            </span>
                             <button
                                 aria-label=format!("remove label '{}'", &label_name)
                                 on:click=move |_| on_delete(&label_name)
@@ -368,11 +392,11 @@ where
                         // class="input-error"
                         class:input-error=move || label_input_invalid.get()
                         type="text"
-                        placeholder="e.g. High Protein..."
-                        list="labels"
+                        placeholder=placeholder.unwrap_or_else(|| "e.g. High Protein...".to_owned())
+                        list=list_id.clone()
                         maxlength="60"
                     />
-                    <datalist id="labels">
+                    <datalist id=list_id>
                         {move || {
                             let selected = selected.get();
                             labels.get().difference(&selected).map(|label|
@@ -0,0 +1,52 @@
+use leptos::*;
+use mcc_frontend_types::label::LabelColor;
+
+use crate::contexts::prelude::{use_label_meta, CurrentLabelMeta};
+
+pub fn badge_class(color: Option<LabelColor>) -> &'static str {
+    match color {
+        Some(LabelColor::Primary) => "badge-primary",
+        Some(LabelColor::Secondary) => "badge-secondary",
+        Some(LabelColor::Accent) => "badge-accent",
+        Some(LabelColor::Neutral) => "badge-neutral",
+        Some(LabelColor::Info) => "badge-info",
+        Some(LabelColor::Success) => "badge-success",
+        Some(LabelColor::Warning) => "badge-warning",
+        Some(LabelColor::Error) => "badge-error",
+        None => "badge-outline",
+    }
+}
+
+/// Background & text classes, for chips that are not badges
+pub fn chip_class(color: Option<LabelColor>) -> &'static str {
+    match color {
+        Some(LabelColor::Secondary) => "bg-secondary text-secondary-content",
+        Some(LabelColor::Accent) => "bg-accent text-accent-content",
+        Some(LabelColor::Neutral) => "bg-neutral text-neutral-content",
+        Some(LabelColor::Info) => "bg-info text-info-content",
+        Some(LabelColor::Success) => "bg-success text-success-content",
+        Some(LabelColor::Warning) => "bg-warning text-warning-content",
+        Some(LabelColor::Error) => "bg-error text-error-content",
+        Some(LabelColor::Primary) | None => "bg-primary text-primary-content",
+    }
+}
+
+/// Label name with its icon
+pub fn label_text(icon: Option<String>, label: &str) -> String {
+    match icon {
+        Some(icon) => format!("{icon} {label}"),
+        None => label.to_owned(),
+    }
+}
+
+/// A label styled with the account's label colours & icons
+#[component]
+pub fn LabelBadge(#[prop(into)] label: String) -> impl IntoView {
+    let CurrentLabelMeta { metadata, .. } = use_label_meta();
+    let text = label.clone();
+    view! {
+        <span class=move || format!("badge whitespace-nowrap shadow-md {}", badge_class(metadata.get().color(&label)))>
+            {move || label_text(metadata.get().icon(&text), &text)}
+        </span>
+    }
+}
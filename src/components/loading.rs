@@ -1,16 +1,22 @@
-use leptos::*;
+use leptos::{html::Div, *};
+use mcc_frontend_types::page::PagingMode;
 
-#[derive(Debug, Clone, Copy)]
+use crate::helpers::{observe_visibility, viewport_height};
+
+// start loading the next page a little before the bottom is reached
+const LOAD_MORE_MARGIN: f64 = 400.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum LoadingItemsState {
-    Loaded(usize),
+    Loaded { has_more: bool },
     Loading,
     Failed,
 }
 
+/// Placed after a list, loads the next page when scrolled near (or "More" is clicked)
 #[component]
 pub fn BufferedPageLoader<F, R>(
     items_state: Signal<LoadingItemsState>,
-    items_per_page: Signal<usize>,
     load_more_action: F,
     retry_action: R,
 ) -> impl IntoView
@@ -18,26 +24,43 @@ where
     F: Fn() + 'static + Copy,
     R: Fn() + 'static + Copy,
 {
+    let sentinel_ref = create_node_ref::<Div>();
+    let visible = create_rw_signal(false);
+    observe_visibility(sentinel_ref, LOAD_MORE_MARGIN, move |v| visible.set(v));
+
+    // the observer is only told about changes, so when more items did not push
+    // the bottom off the screen the next page is loaded straight away
+    create_effect(move |_| {
+        if visible.get() && items_state.get() == (LoadingItemsState::Loaded { has_more: true }) {
+            // wait for the new items to render, they may have been restored further down
+            request_animation_frame(move || {
+                let still_near = sentinel_ref.get_untracked().is_some_and(|sentinel| {
+                    sentinel.get_bounding_client_rect().top() < viewport_height() + LOAD_MORE_MARGIN
+                });
+                if still_near
+                    && items_state.get_untracked() == (LoadingItemsState::Loaded { has_more: true })
+                {
+                    load_more_action();
+                }
+            });
+        }
+    });
+
     view! {
-        <div class="flex justify-center">
+        <div node_ref=sentinel_ref class="flex justify-center">
             {move || {
                 match items_state.get() {
-                    LoadingItemsState::Loaded(items) => {
-                        if items == items_per_page.get() {
-                            view!{
-                                <button
-                                    on:click=move |_| load_more_action()
-                                    class="btn btn-block"
-                                    type="button"
-                                >"More"</button>
-                            }.into_any()
-                        } else {
-                            view! {
-                                <div class="text-center"
-                                >"Reached Bottom"</div>
-                            }.into_any()
-                        }
-                    },
+                    LoadingItemsState::Loaded { has_more: true } => view!{
+                        <button
+                            on:click=move |_| load_more_action()
+                            class="btn btn-block"
+                            type="button"
+                        >"More"</button>
+                    }.into_any(),
+                    LoadingItemsState::Loaded { has_more: false } => view! {
+                        <div class="text-center"
+                        >"Reached Bottom"</div>
+                    }.into_any(),
                     LoadingItemsState::Loading => view!{
                         <div class="loading loading-ring loading-lg"></div>
                     }.into_any(),
@@ -53,3 +76,85 @@ where
         </div>
     }
 }
+
+/// How many items are shown, with a switch between scrolling & numbered pages
+#[component]
+pub fn ListSummary<F>(
+    loaded: Signal<usize>,
+    /// what the items are, e.g. "recipes"
+    noun: &'static str,
+    paging: Signal<PagingMode>,
+    on_paging_change: F,
+) -> impl IntoView
+where
+    F: Fn(PagingMode) + 'static,
+{
+    view! {
+        <div class="flex flex-wrap items-center justify-between gap-2 px-3">
+            <span class="text-sm" aria-live="polite">
+                {move || format!("Showing {} {noun}", loaded.get())}
+            </span>
+            <label class="label cursor-pointer gap-2">
+                <span class="label-text">"Numbered Pages"</span>
+                <input
+                    prop:checked=move || paging.get() == PagingMode::Numbered
+                    on:change=move |ev| on_paging_change(if event_target_checked(&ev) {
+                        PagingMode::Numbered
+                    } else {
+                        PagingMode::Scroll
+                    })
+                    type="checkbox"
+                    class="toggle toggle-sm"
+                />
+            </label>
+        </div>
+    }
+}
+
+/// Previous & next buttons either side of the current page's number,
+/// there are no buttons for other pages as how many there are is not known
+#[component]
+pub fn NumberedPager<F, R>(
+    items_state: Signal<LoadingItemsState>,
+    page: Signal<usize>,
+    on_page: F,
+    retry_action: R,
+) -> impl IntoView
+where
+    F: Fn(usize) + 'static + Copy,
+    R: Fn() + 'static + Copy,
+{
+    let has_more = move || items_state.get() == LoadingItemsState::Loaded { has_more: true };
+
+    view! {
+        <nav class="flex flex-col items-center gap-2" aria-label="pages">
+            <Show when=move || items_state.get() == LoadingItemsState::Failed fallback=|| ()>
+                <button
+                    on:click=move |_| retry_action()
+                    class="btn btn-block"
+                    type="button"
+                >"Retry"</button>
+            </Show>
+            <div class="join shadow-lg">
+                <button
+                    on:click=move |_| on_page(page.get_untracked() - 1)
+                    disabled=move || page.get() <= 1
+                    class="btn join-item"
+                    type="button"
+                    aria-label="Previous Page"
+                >"«"</button>
+                <button class="btn join-item btn-active" type="button" aria-current="page">{page}</button>
+                <button
+                    on:click=move |_| on_page(page.get_untracked() + 1)
+                    disabled=move || !has_more()
+                    class="btn join-item"
+                    type="button"
+                    aria-label="Next Page"
+                >"»"</button>
+            </div>
+            <Show when=move || items_state.get() == LoadingItemsState::Loading fallback=|| ()>
+                <div class="loading loading-ring loading-lg"></div>
+            </Show>
+        </nav>
+    }
+}
@@ -0,0 +1,114 @@
+use leptos::*;
+use mcc_frontend_types::markdown::{parse_markdown, Block, Inline};
+
+fn inline_view(inlines: Vec<Inline>) -> View {
+    inlines
+        .into_iter()
+        .map(|inline| match inline {
+            Inline::Text(v) => v.into_view(),
+            Inline::Strong(v) => view! { <strong>{inline_view(v)}</strong> }.into_view(),
+            Inline::Emphasis(v) => view! { <em>{inline_view(v)}</em> }.into_view(),
+            Inline::Code(v) => {
+                view! { <code class="bg-base-300 px-1 rounded">{v}</code> }.into_view()
+            }
+            Inline::Link { content, url } => view! {
+                <a href=url class="link" target="_blank" rel="noopener noreferrer">{inline_view(content)}</a>
+            }
+            .into_view(),
+            Inline::LineBreak => view! { <br/> }.into_view(),
+        })
+        .collect_view()
+}
+
+fn block_view(block: Block) -> View {
+    match block {
+        Block::Paragraph(v) => view! { <p class="mb-2">{inline_view(v)}</p> }.into_view(),
+        // headings sit below the page's own, so start from h3
+        Block::Heading { level, content } => {
+            let content = inline_view(content);
+            match level {
+                1 => view! { <h3 class="text-lg font-bold mb-1">{content}</h3> }.into_view(),
+                2 => view! { <h4 class="font-bold mb-1">{content}</h4> }.into_view(),
+                3 => view! { <h5 class="font-bold mb-1">{content}</h5> }.into_view(),
+                _ => view! { <h6 class="font-bold mb-1">{content}</h6> }.into_view(),
+            }
+        }
+        Block::List { start, items } => {
+            let items = items
+                .into_iter()
+                .map(|v| view! { <li>{inline_view(v)}</li> })
+                .collect_view();
+            match start {
+                Some(start) => view! {
+                    <ol class="list-decimal pl-6 mb-2" start=start>{items}</ol>
+                }
+                .into_view(),
+                None => view! { <ul class="list-disc pl-6 mb-2">{items}</ul> }.into_view(),
+            }
+        }
+        Block::Quote(v) => view! {
+            <blockquote class="border-l-4 border-base-300 pl-2 mb-2 italic">
+                {v.into_iter().map(block_view).collect_view()}
+            </blockquote>
+        }
+        .into_view(),
+        Block::Code(v) => view! {
+            <pre class="bg-base-300 p-2 mb-2 rounded overflow-x-auto"><code>{v}</code></pre>
+        }
+        .into_view(),
+        Block::Rule => view! { <hr class="my-2 border-base-300"/> }.into_view(),
+    }
+}
+
+/// Renders Markdown text, any HTML within it is shown as written
+#[component]
+pub fn Markdown(#[prop(into)] text: MaybeSignal<String>) -> impl IntoView {
+    view! {
+        <div class="break-words">
+            {move || text.with(|v| parse_markdown(v)).into_iter().map(block_view).collect_view()}
+        </div>
+    }
+}
+
+/// A text area for Markdown, optionally beside a preview of it
+#[component]
+pub fn MarkdownInput<F>(
+    #[prop(into)] value: Signal<String>,
+    on_input: F,
+    #[prop(optional, into)] placeholder: Option<String>,
+    #[prop(optional)] required: bool,
+    /// classes for the text area, e.g. its height
+    #[prop(optional, into)]
+    class: Option<String>,
+) -> impl IntoView
+where
+    F: Fn(String) + 'static + Copy,
+{
+    let preview = create_rw_signal(false);
+
+    view! {
+        <div class="grid gap-2" class=("md:grid-cols-2", move || preview.get())>
+            <textarea
+                prop:value=move || value.get()
+                on:input=move |ev| on_input(event_target_value(&ev))
+                class=format!("textarea textarea-bordered w-full {}", class.unwrap_or_default())
+                placeholder=placeholder
+                required=required
+            />
+            <Show when=move || preview.get()>
+                <div class="p-2 rounded bg-base-100 overflow-y-auto">
+                    <Markdown text=value />
+                </div>
+            </Show>
+        </div>
+        <label class="label cursor-pointer justify-start gap-2">
+            <input
+                prop:checked=move || preview.get()
+                on:input=move |ev| preview.set(event_target_checked(&ev))
+                type="checkbox"
+                class="toggle toggle-sm"
+            />
+            <span class="text-sm">"Preview (Markdown)"</span>
+        </label>
+    }
+}
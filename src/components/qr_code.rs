@@ -0,0 +1,23 @@
+use leptos::*;
+use mcc_frontend_core::qr_code::qr_code_path;
+
+/// A QR code of the text (e.g. a link), nothing when it is too long to encode
+#[component]
+pub fn QrCode(text: String, #[prop(optional, into)] class: String) -> impl IntoView {
+    let label = format!("QR code for {text}");
+    qr_code_path(&text).map(|(size, path)| {
+        view! {
+            <svg
+                xmlns="http://www.w3.org/2000/svg"
+                viewBox=format!("0 0 {size} {size}")
+                shape-rendering="crispEdges"
+                role="img"
+                aria-label=label
+                class=class
+            >
+                <rect width="100%" height="100%" fill="white" />
+                <path d=path fill="black" />
+            </svg>
+        }
+    })
+}
@@ -0,0 +1,752 @@
+use std::{
+    collections::HashSet,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use leptos::{html::Button, *};
+use mcc_frontend_types::{
+    query::RecipesFilter,
+    recipe::{Info, InfoYields, Ingredient, Step},
+    sub_recipe::{RecipeLink, BATCH_UNIT},
+    HourMinuteSecond,
+};
+
+use crate::{
+    components::{
+        input::{FractionalInput, HmsInput},
+        markdown::MarkdownInput,
+    },
+    contexts::prelude::{use_api, use_login, use_toasts, CurrentApi, CurrentLogin},
+    helpers::{api_error_to_toast, logout_on_401},
+};
+
+// how many recipes the recipe picker shows
+const PICKER_PER_PAGE: usize = 10;
+
+static NEXT_KEY: AtomicUsize = AtomicUsize::new(0);
+
+/// A row of an editable list, keyed so rows keep their inputs & focus while others change
+pub struct Keyed<T: 'static> {
+    pub key: usize,
+    pub value: RwSignal<T>,
+}
+
+impl<T> Clone for Keyed<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Keyed<T> {}
+
+impl<T> Keyed<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            key: NEXT_KEY.fetch_add(1, Ordering::Relaxed),
+            value: create_rw_signal(value),
+        }
+    }
+}
+
+/// Items that can be section headers within their list
+pub trait Sectioned {
+    fn section(name: &str) -> Self;
+    fn section_name(&self) -> Option<&str>;
+}
+
+impl Sectioned for Ingredient {
+    fn section(name: &str) -> Self {
+        Ingredient::section(name)
+    }
+
+    fn section_name(&self) -> Option<&str> {
+        Ingredient::section_name(self)
+    }
+}
+
+impl Sectioned for Step {
+    fn section(name: &str) -> Self {
+        Step::section(name)
+    }
+
+    fn section_name(&self) -> Option<&str> {
+        Step::section_name(self)
+    }
+}
+
+pub fn keyed<T>(values: Vec<T>) -> Vec<Keyed<T>> {
+    values.into_iter().map(Keyed::new).collect()
+}
+
+/// Current values of the rows, tracking each of them
+pub fn row_values<T: Clone>(rows: &[Keyed<T>]) -> Vec<T> {
+    rows.iter().map(|row| row.value.get()).collect()
+}
+
+fn move_row<T>(rows: RwSignal<Vec<Keyed<T>>>, key: usize, to: usize) {
+    rows.update(|rows| {
+        if let Some(from) = rows.iter().position(|v| v.key == key) {
+            let row = rows.remove(from);
+            rows.insert(to.min(rows.len()), row);
+        }
+    })
+}
+
+fn insert_row<T>(rows: RwSignal<Vec<Keyed<T>>>, index: usize, value: T) {
+    rows.update(|rows| rows.insert(index.min(rows.len()), Keyed::new(value)))
+}
+
+/// State shared by the rows of a list editor
+struct ListState<T: 'static> {
+    rows: RwSignal<Vec<Keyed<T>>>,
+    selected: RwSignal<HashSet<usize>>,
+    dragging: RwSignal<Option<usize>>,
+    drop_target: RwSignal<Option<usize>>,
+}
+
+impl<T> Clone for ListState<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for ListState<T> {}
+
+impl<T> ListState<T> {
+    fn delete(&self, keys: HashSet<usize>) {
+        self.rows
+            .update(|rows| rows.retain(|v| !keys.contains(&v.key)));
+        self.selected
+            .update(|selected| selected.retain(|v| !keys.contains(v)));
+    }
+
+    fn stop_dragging(&self) {
+        self.dragging.set(None);
+        self.drop_target.set(None);
+    }
+}
+
+#[component]
+fn ListRow<T, F, IV>(
+    state: ListState<T>,
+    item: Keyed<T>,
+    item_name: &'static str,
+    section_name: &'static str,
+    row: F,
+) -> impl IntoView
+where
+    T: Sectioned + Default + 'static,
+    F: Fn(Keyed<T>, Signal<usize>) -> IV + 'static + Copy,
+    IV: IntoView,
+{
+    let ListState {
+        rows,
+        selected,
+        dragging,
+        drop_target,
+    } = state;
+    let key = item.key;
+    let position = move |key: usize| rows.with(|rows| rows.iter().position(|v| v.key == key));
+    let index = Signal::derive(move || position(key).unwrap_or_default());
+    // a row doesn't change between being a section & an item while editing
+    let is_section = item.value.with_untracked(|v| v.section_name().is_some());
+    let number = Signal::derive(move || {
+        rows.with(|rows| {
+            rows.iter()
+                .take(index.get() + 1)
+                .filter(|v| v.value.with_untracked(|v| v.section_name().is_none()))
+                .count()
+        })
+    });
+    let handle_ref = create_node_ref::<Button>();
+
+    let move_by = move |offset: isize| {
+        let to = index.get_untracked() as isize + offset;
+        if to < 0 || to as usize >= rows.with_untracked(|v| v.len()) {
+            return;
+        }
+        move_row(rows, key, to as usize);
+        // the row's elements are moved, which loses focus
+        request_animation_frame(move || {
+            if let Some(handle) = handle_ref.get_untracked() {
+                _ = handle.focus();
+            }
+        });
+    };
+
+    let on_handle_keydown = move |ev: ev::KeyboardEvent| {
+        match ev.key().as_str() {
+            "ArrowUp" => move_by(-1),
+            "ArrowDown" => move_by(1),
+            _ => return,
+        }
+        ev.prevent_default();
+    };
+
+    let on_dragstart = move |ev: ev::DragEvent| {
+        // firefox only drags with some data set
+        if let Some(data) = ev.data_transfer() {
+            _ = data.set_data("text/plain", item_name);
+            data.set_effect_allowed("move");
+        }
+        dragging.set(Some(key));
+    };
+
+    let on_dragover = move |ev: ev::DragEvent| {
+        if dragging.get_untracked().is_some() {
+            ev.prevent_default();
+            drop_target.set(Some(key));
+        }
+    };
+
+    let on_drop = move |ev: ev::DragEvent| {
+        ev.prevent_default();
+        if let (Some(from), Some(to)) = (dragging.get_untracked(), position(key)) {
+            move_row(rows, from, to);
+        }
+        state.stop_dragging();
+    };
+
+    let on_select = move |ev| {
+        selected.update(|v| {
+            if event_target_checked(&ev) {
+                v.insert(key);
+            } else {
+                v.remove(&key);
+            }
+        })
+    };
+
+    view! {
+        <li
+            on:dragover=on_dragover
+            on:drop=on_drop
+            class="mb-4 p-4 rounded bg-base-200"
+            class=("outline", move || drop_target.get() == Some(key))
+            class=("outline-primary", move || drop_target.get() == Some(key))
+            class=("opacity-50", move || dragging.get() == Some(key))
+        >
+            <div class="flex flex-wrap items-center gap-2 mb-2">
+                <input
+                    prop:checked=move || selected.with(|v| v.contains(&key))
+                    on:change=on_select
+                    type="checkbox"
+                    class="checkbox checkbox-sm"
+                    aria-label=format!("Select {item_name}")
+                />
+                <button
+                    node_ref=handle_ref
+                    on:dragstart=on_dragstart
+                    on:dragend=move |_| state.stop_dragging()
+                    on:keydown=on_handle_keydown
+                    draggable="true"
+                    type="button"
+                    class="btn btn-sm btn-ghost cursor-move"
+                    title="Drag, or use the arrow keys, to move"
+                    aria-label=format!("Move {item_name}")>
+                    "⠿"
+                </button>
+                <span class="font-bold mr-auto">{move || if is_section {
+                    section_name.to_owned()
+                } else {
+                    format!("{item_name} {}", number.get())
+                }}</span>
+                <div class="join shadow-lg">
+                    <button
+                        on:click=move |_| move_by(-1)
+                        disabled=move || index.get() == 0
+                        type="button"
+                        class="btn btn-sm join-item">
+                        "Up"
+                    </button>
+                    <button
+                        on:click=move |_| move_by(1)
+                        disabled=move || index.get() + 1 == rows.with(|v| v.len())
+                        type="button"
+                        class="btn btn-sm join-item">
+                        "Down"
+                    </button>
+                    <button
+                        on:click=move |_| insert_row(rows, index.get_untracked() + 1, T::default())
+                        type="button"
+                        class="btn btn-sm join-item">
+                        "Insert Below"
+                    </button>
+                    <button
+                        on:click=move |_| state.delete(HashSet::from([key]))
+                        type="button"
+                        class="btn btn-sm join-item">
+                        "X"
+                    </button>
+                </div>
+            </div>
+            {if is_section {
+                view! {
+                    <input
+                        prop:value=move || item.value.with(|v| v.section_name().unwrap_or_default().to_owned())
+                        on:input=move |ev| item.value.set(T::section(&event_target_value(&ev)))
+                        type="text"
+                        class="input input-bordered w-full font-bold"
+                        placeholder=format!("{section_name} Name, e.g. For the sauce")
+                        required=true
+                    />
+                }.into_view()
+            } else {
+                row(item, number).into_view()
+            }}
+        </li>
+    }
+}
+
+/// Rows that can be reordered (dragging or arrow keys on the handle), inserted anywhere, deleted together
+/// & split up by section headers
+#[component]
+fn ListEditor<T, F, IV>(
+    rows: RwSignal<Vec<Keyed<T>>>,
+    item_name: &'static str,
+    /// what section headers are called
+    section_name: &'static str,
+    row: F,
+) -> impl IntoView
+where
+    T: Sectioned + Default + 'static,
+    F: Fn(Keyed<T>, Signal<usize>) -> IV + 'static + Copy,
+    IV: IntoView,
+{
+    let state = ListState {
+        rows,
+        selected: create_rw_signal(HashSet::new()),
+        dragging: create_rw_signal(None),
+        drop_target: create_rw_signal(None),
+    };
+    let selected = state.selected;
+
+    let all_selected = move || {
+        let count = selected.with(|v| v.len());
+        count > 0 && count == rows.with(|v| v.len())
+    };
+    let on_select_all = move |ev| {
+        selected.set(if event_target_checked(&ev) {
+            rows.with(|rows| rows.iter().map(|v| v.key).collect())
+        } else {
+            HashSet::new()
+        })
+    };
+
+    view! {
+        <div class="flex flex-wrap items-center gap-2 mb-2">
+            <label class="label cursor-pointer gap-2">
+                <input
+                    prop:checked=all_selected
+                    on:change=on_select_all
+                    type="checkbox"
+                    class="checkbox checkbox-sm"
+                />
+                <span>"Select All"</span>
+            </label>
+            <button
+                on:click=move |_| state.delete(selected.get_untracked())
+                disabled=move || selected.with(|v| v.is_empty())
+                type="button"
+                class="btn btn-sm ml-auto">
+                {move || format!("Delete Selected ({})", selected.with(|v| v.len()))}
+            </button>
+            <button on:click=move |_| insert_row(rows, 0, T::default()) type="button" class="btn btn-sm">"Insert At Start"</button>
+        </div>
+        <ol>
+            <For
+                each=move || rows.get()
+                key=|item| item.key
+                children=move |item| view! {
+                    <ListRow state=state item=item item_name=item_name section_name=section_name row=row />
+                }
+            />
+        </ol>
+        <div class="flex gap-2">
+            <button
+                on:click=move |_| insert_row(rows, usize::MAX, T::default())
+                type="button"
+                class="btn shadow-lg grow">
+                {format!("Add {item_name}")}
+            </button>
+            <button
+                on:click=move |_| insert_row(rows, usize::MAX, T::section(""))
+                type="button"
+                class="btn shadow-lg">
+                {format!("Add {section_name}")}
+            </button>
+        </div>
+    }
+}
+
+/// Search recipes by title to pick one
+#[component]
+fn RecipePicker<F>(on_pick: F) -> impl IntoView
+where
+    F: Fn(RecipeLink) + 'static + Copy,
+{
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { set_login, .. } = use_login();
+    let query = create_rw_signal(String::default());
+
+    let search_action = create_action(move |title: &String| {
+        let api = api.get_untracked().expect("api expected to exist");
+        let filters = RecipesFilter {
+            per_page: PICKER_PER_PAGE,
+            title: Some(title.clone()).filter(|v| !v.trim().is_empty()),
+            ..Default::default()
+        };
+        async move {
+            match api.get_recipes(&filters).await {
+                Ok(recipes) => recipes,
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "searching recipes"));
+                    logout_on_401(&set_login, &err);
+                    Vec::new()
+                }
+            }
+        }
+    });
+    let search = move || search_action.dispatch(query.get_untracked());
+
+    view! {
+        <div class="mb-2 p-2 rounded bg-base-200">
+            <div class="join w-full">
+                <input
+                    prop:value=move || query.get()
+                    on:input=move |ev| query.set(event_target_value(&ev))
+                    on:keydown=move |ev| {
+                        // the editor is a form, so enter would save it
+                        if ev.key() == "Enter" {
+                            ev.prevent_default();
+                            search();
+                        }
+                    }
+                    type="search"
+                    class="input input-bordered input-sm join-item w-full"
+                    placeholder="Search Recipes..."
+                    aria-label="Search recipes to link"
+                />
+                <button on:click=move |_| search() type="button" class="btn btn-sm join-item">"Search"</button>
+            </div>
+            {move || search_action.value().get().map(|recipes| {
+                if recipes.is_empty() {
+                    return view! { <p class="text-sm mt-2">"No recipes found"</p> }.into_view();
+                }
+                view! {
+                    <ul class="menu menu-compact mt-2">
+                        {recipes.into_iter().map(|recipe| {
+                            let title = recipe.title.clone();
+                            let link = RecipeLink { id: recipe.id, title: recipe.title };
+                            view! {
+                                <li>
+                                    <button on:click=move |_| on_pick(link.clone()) type="button">
+                                        {title}
+                                    </button>
+                                </li>
+                            }
+                        }).collect_view()}
+                    </ul>
+                }.into_view()
+            })}
+        </div>
+    }
+}
+
+#[component]
+fn IngredientFields(ingredient: RwSignal<Ingredient>) -> impl IntoView {
+    let picking = create_rw_signal(false);
+    let on_pick = move |link: RecipeLink| {
+        ingredient.update(|i| {
+            i.name = link.to_name();
+            if i.unit_type.trim().is_empty() {
+                i.unit_type = BATCH_UNIT.to_owned();
+            }
+            if i.amount == 0.0 {
+                i.amount = 1.0;
+            }
+        });
+        picking.set(false);
+    };
+    // only the link, so typing in the other fields does not rebuild the name input
+    let linked_recipe = create_memo(move |_| ingredient.with(|i| i.linked_recipe()));
+
+    view! {
+        {move || match linked_recipe.get() {
+            Some(link) => view! {
+                <div class="flex items-center gap-2 mb-2">
+                    <span class="badge badge-primary badge-lg">"Recipe: " {link.title.clone()}</span>
+                    <button
+                        on:click=move |_| ingredient.update(|i| i.name = link.title.clone())
+                        type="button"
+                        class="btn btn-sm"
+                    >
+                        "Unlink"
+                    </button>
+                </div>
+            }.into_view(),
+            None => view! {
+                <div class="join w-full mb-2">
+                    <input
+                        prop:value=move || ingredient.get().name
+                        on:input=move |ev| ingredient.update(|i| i.name = event_target_value(&ev))
+                        type="text"
+                        class="input input-bordered w-full join-item"
+                        placeholder="e.g. Carrots"
+                        required=true
+                    />
+                    <button
+                        on:click=move |_| picking.update(|v| *v = !*v)
+                        type="button"
+                        class="btn join-item"
+                        class:btn-active=move || picking.get()
+                    >
+                        "Link Recipe"
+                    </button>
+                </div>
+                <Show when=move || picking.get() fallback=|| ()>
+                    <RecipePicker on_pick=on_pick />
+                </Show>
+            }.into_view(),
+        }}
+        <div class="grid grid-cols-[8rem_auto] gap-2 mb-2">
+            <FractionalInput
+                value=ingredient.get_untracked().amount
+                on_input=move |amount| ingredient.update(|i| i.amount = amount)
+                class="input input-bordered w-full".to_owned()
+                placeholder="e.g. 15".to_owned()
+                required=true
+            />
+            <input
+                prop:value=move || ingredient.get().unit_type
+                on:input=move |ev| ingredient.update(|i| i.unit_type = event_target_value(&ev))
+                type="text"
+                class="input input-bordered w-full"
+                placeholder="e.g. g"
+                list="units"
+                required=true
+            />
+        </div>
+        <input
+            prop:value=move || ingredient.get().description.unwrap_or_default()
+            on:input=move |ev| ingredient.update(|i| i.description = Some(event_target_value(&ev)))
+            type="text"
+            class="textarea textarea-bordered w-full"
+            placeholder="e.g. Diced..."
+        />
+    }
+}
+
+#[component]
+pub fn IngredientsEditor(ingredients: RwSignal<Vec<Keyed<Ingredient>>>) -> impl IntoView {
+    let ingredient_row =
+        |item: Keyed<Ingredient>, _| view! { <IngredientFields ingredient=item.value /> };
+    view! {
+        <ListEditor
+            rows=ingredients
+            item_name="Ingredient"
+            section_name="Section"
+            row=ingredient_row
+        />
+        <datalist id="units">
+            <option value="g" />
+            <option value="kg" />
+            <option value="ml" />
+            <option value="l" />
+            <option value="tsp" />
+            <option value="tbsp" />
+            <option value="cup" />
+            <option value="oz" />
+            <option value="lb" />
+            <option value="pinch" />
+            <option value="dash" />
+            <option value="slice" />
+            <option value="can" />
+            <option value="bottle" />
+            <option value="jar" />
+            <option value="head" />
+            <option value="stalk" />
+            <option value="bunch" />
+            <option value="handful" />
+        </datalist>
+    }
+}
+
+#[component]
+fn StepFields(step: RwSignal<Step>, #[prop(into)] number: Signal<usize>) -> impl IntoView {
+    view! {
+        <input
+            on:input=move |ev| step.update(|s| s.title = {
+                let value = event_target_value(&ev);
+                if value.is_empty() {
+                    None
+                } else {
+                    Some(value)
+                }
+            })
+            prop:value=move || step.get().title.unwrap_or_default()
+            type="text"
+            class="input input-bordered w-full mb-2"
+            placeholder=move || format!("Step {}", number.get())
+        />
+        <MarkdownInput
+            value=Signal::derive(move || step.get().description)
+            on_input=move |value| step.update(|s| s.description = value)
+            placeholder="e.g. First you will need..."
+            required=true
+        />
+    }
+}
+
+#[component]
+pub fn StepsEditor(steps: RwSignal<Vec<Keyed<Step>>>) -> impl IntoView {
+    let step_row =
+        |item: Keyed<Step>, number| view! { <StepFields step=item.value number=number /> };
+    view! {
+        <ListEditor
+            rows=steps
+            item_name="Step"
+            section_name="Group"
+            row=step_row
+        />
+    }
+}
+
+/// Edits serving size, times, flags & source of a recipe
+#[component]
+pub fn InfoEditor(info: RwSignal<Info>) -> impl IntoView {
+    view! {
+        <div>
+            // yields
+            <div class="my-4">
+                <h2 class="text-lg mb-2">"Serving Size"</h2>
+                <div class="flex gap-1">
+                    <label class="join w-auto">
+                        <span class="label p-3 bg-base-300 join-item">"Amount"</span>
+                        <input
+                            prop:value=move || info.get().yields.unwrap_or_default().value
+                            on:input=move |ev| {
+                                if let Ok(value) = event_target_value(&ev).parse() {
+                                    info.update(|info| {
+                                        info.yields = Some(InfoYields {
+                                            value,
+                                            unit_type: info.yields.clone().unwrap_or_default().unit_type,
+                                        });
+                                    })
+                                }
+                            }
+                            type="number"
+                            class="input input-bordered w-24 join-item"
+                            min=1 required=true
+                        />
+                    </label>
+                    <label class="join">
+                        <span class="label p-3 bg-base-300 join-item">"Type"</span>
+                        <input
+                            prop:value=move || info.get().yields.unwrap_or_default().unit_type
+                            on:input=move |ev| {
+                                info.update(|info| {
+                                    info.yields = Some(InfoYields {
+                                        value: info.yields.clone().unwrap_or_default().value,
+                                        unit_type: event_target_value(&ev),
+                                    });
+                                })
+                            }
+                            type="text"
+                            class="input input-bordered join-item w-full"
+                            list="yield-units"
+                            placeholder="e.g. servings"
+                            required=true
+                        />
+                        <datalist id="yield-units">
+                            <option value="servings" />
+                            <option value="g" />
+                            <option value="kg" />
+                            <option value="ml" />
+                            <option value="l" />
+                            <option value="tsp" />
+                            <option value="tbsp" />
+                            <option value="cup" />
+                            <option value="oz" />
+                            <option value="lb" />
+                            <option value="can" />
+                            <option value="bottle" />
+                            <option value="jar" />
+                        </datalist>
+                    </label>
+                </div>
+            </div>
+            // times
+            <div class="my-4">
+                <h2 class="text-lg mb-1">"Times"</h2>
+                <div class="form-control">
+                    <span class="mb-2">"Prep Time"</span>
+                    <HmsInput
+                        value=Signal::derive( move || HourMinuteSecond::from_secs(info.get().prep_time))
+                        on_input=move |value| info.update(|info| info.prep_time = value.as_secs())
+                        required=true
+                    />
+                    <span class="my-2">"Cook Time"</span>
+                    <HmsInput
+                        value=Signal::derive( move || HourMinuteSecond::from_secs(info.get().cook_time))
+                        on_input=move |value| info.update(|info| info.cook_time = value.as_secs())
+                        required=true
+                    />
+                </div>
+            </div>
+            // flags
+            <div class="my-4">
+                <h2 class="text-lg mb-2">"Flags"</h2>
+                <div class="form-control">
+                    <label class="label cursor-pointer">
+                        <span>"Freezable"</span>
+                        <input
+                            prop:checked=move || info.get().freezable
+                            on:input=move |ev| {
+                                info.update(|info| {
+                                    info.freezable = event_target_checked(&ev);
+                                })
+                            }
+                            type="checkbox"
+                            class="checkbox"
+                        />
+                    </label>
+                </div>
+                <div class="form-control">
+                    <label class="label cursor-pointer">
+                        <span>"Microwave Only"</span>
+                        <input
+                            prop:checked=move || info.get().microwave_only
+                            on:input=move |ev| {
+                                info.update(|info| {
+                                    info.microwave_only = event_target_checked(&ev);
+                                })
+                            }
+                            type="checkbox"
+                            class="checkbox"
+                        />
+                    </label>
+                </div>
+            </div>
+            // source
+            <div class="my-4">
+                <h2 class="text-lg mb-2">"Recipe Source"</h2>
+                <label class="join w-full">
+                    <span class="label p-3 bg-base-300 join-item">"Source"</span>
+                    <input
+                        prop:value=move || info.get().source.unwrap_or_default()
+                        on:input=move |ev| {
+                            info.update(|info| {
+                                info.source = Some(event_target_value(&ev));
+                            })
+                        }
+                        type="text"
+                        class="input input-bordered join-item w-full"
+                        placeholder="e.g. Mom's Recipe Book"
+                    />
+                </label>
+            </div>
+        </div>
+    }
+}
@@ -0,0 +1,221 @@
+use chrono::{Local, Utc};
+use leptos::*;
+use mcc_frontend_core::storage;
+use mcc_frontend_types::{
+    history::{diff_recipes, diff_text, RecipeChange, RecipeRevision, TextPart},
+    recipe::{Ingredient, Recipe, UpdateRecipe},
+    Fraction,
+};
+
+use crate::{
+    components::input::DropdownConfirm,
+    contexts::prelude::{use_api, use_login, use_toasts, CurrentApi, CurrentLogin},
+    helpers::{api_error_to_toast, logout_on_401, storage_error_to_toast},
+};
+
+fn ingredient_text(ingredient: &Ingredient) -> String {
+    if let Some(name) = ingredient.section_name() {
+        return format!("Section {name}");
+    }
+    format!(
+        "{} {} {}",
+        Fraction::from(ingredient.amount),
+        ingredient.unit_type,
+        ingredient.display_name()
+    )
+}
+
+#[component]
+fn TextDiff(diff: Vec<TextPart>) -> impl IntoView {
+    diff.into_iter()
+        .map(|part| match part {
+            TextPart::Same(v) => v.into_view(),
+            TextPart::Added(v) => {
+                view! { <ins class="bg-success/30 no-underline">{v}</ins> }.into_view()
+            }
+            TextPart::Removed(v) => view! { <del class="bg-error/30">{v}</del> }.into_view(),
+        })
+        .collect_view()
+}
+
+#[component]
+fn ChangeRow(change: RecipeChange) -> impl IntoView {
+    match change {
+        RecipeChange::Text { field, diff } => view! {
+            <li><span class="font-bold">{field}": "</span><TextDiff diff=diff /></li>
+        },
+        RecipeChange::Info { field, from, to } => view! {
+            <li><span class="font-bold">{field}": "</span><del class="bg-error/30">{from}</del>" → "<ins class="bg-success/30 no-underline">{to}</ins></li>
+        },
+        RecipeChange::Names {
+            field,
+            added,
+            removed,
+        } => view! {
+            <li class="flex flex-wrap gap-1">
+                <span class="font-bold">{field}": "</span>
+                {added.into_iter().map(|v| view!{ <span class="badge badge-success">"+ "{v}</span> }).collect_view()}
+                {removed.into_iter().map(|v| view!{ <span class="badge badge-error">"- "{v}</span> }).collect_view()}
+            </li>
+        },
+        RecipeChange::IngredientAdded(v) => view! {
+            <li><span class="font-bold">"Ingredient Added: "</span><ins class="bg-success/30 no-underline">{ingredient_text(&v)}</ins></li>
+        },
+        RecipeChange::IngredientRemoved(v) => view! {
+            <li><span class="font-bold">"Ingredient Removed: "</span><del class="bg-error/30">{ingredient_text(&v)}</del></li>
+        },
+        RecipeChange::IngredientChanged { from, to } => view! {
+            <li>
+                <span class="font-bold">"Ingredient Changed: "</span>
+                <TextDiff diff=diff_text(&ingredient_text(&from), &ingredient_text(&to)) />
+            </li>
+        },
+        RecipeChange::StepAdded { number, step } => view! {
+            <li><span class="font-bold">{format!("Step {number} Added: ")}</span><ins class="bg-success/30 no-underline">{step.description}</ins></li>
+        },
+        RecipeChange::StepRemoved { number, step } => view! {
+            <li><span class="font-bold">{format!("Step {number} Removed: ")}</span><del class="bg-error/30">{step.description}</del></li>
+        },
+        RecipeChange::StepChanged { number, diff } => view! {
+            <li><span class="font-bold">{format!("Step {number} Changed: ")}</span><TextDiff diff=diff /></li>
+        },
+    }
+}
+
+#[component]
+fn RevisionRow<F>(
+    revision: RecipeRevision,
+    /// the version saved over this revision
+    next: Recipe,
+    on_restore: F,
+) -> impl IntoView
+where
+    F: Fn() + 'static + Copy,
+{
+    let changes = diff_recipes(&revision.recipe, &next);
+    let saved_at = revision
+        .saved_at
+        .with_timezone(&Local)
+        .format("%Y-%m-%d %H:%M")
+        .to_string();
+
+    view! {
+        <div class="p-2 rounded bg-base-100">
+            <div class="flex items-center gap-2">
+                <span class="font-bold mr-auto">{saved_at}</span>
+                <span class="text-sm">{format!("{} Changes Since", changes.len())}</span>
+                <DropdownConfirm
+                    title="Restore"
+                    confirm_aria="Confirm restoring this version"
+                    on_confirm=on_restore
+                    class="dropdown-left"
+                />
+            </div>
+            <details>
+                <summary class="cursor-pointer text-sm">"Show Changes"</summary>
+                <ul class="text-sm flex flex-col gap-1 mt-1">
+                    {changes.into_iter().map(|change| view!{ <ChangeRow change=change /> }).collect_view()}
+                </ul>
+            </details>
+        </div>
+    }
+}
+
+/// Earlier versions of a recipe, remembering the version before every save
+#[component]
+pub fn RecipeHistoryPanel(recipe: RwSignal<Recipe>) -> impl IntoView {
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, set_login } = use_login();
+    let history = create_rw_signal(
+        login
+            .get_untracked()
+            .map(|v| storage::read_recipe_history(&v))
+            .unwrap_or_default(),
+    );
+
+    // the recipe only changes here once a save was successful,
+    // images are not restored so changing only those is not remembered
+    create_effect(move |previous: Option<Recipe>| {
+        let current = recipe.get();
+        let changed = |previous: &Recipe| {
+            let previous = Recipe {
+                image_id: current.image_id.clone(),
+                ..previous.clone()
+            };
+            previous != current
+        };
+        if let Some(previous) = previous.filter(changed) {
+            history.update(|history| {
+                history.push(previous, Utc::now());
+                if let Some(login) = login.get_untracked() {
+                    if let Err(err) = storage::set_recipe_history(&login, history) {
+                        toasts.push(storage_error_to_toast(&err, "the recipe's history"));
+                    }
+                }
+            });
+        }
+        current
+    });
+
+    let restore_action = create_action(move |revision: &Recipe| {
+        let api = api.get_untracked().expect("api expected to exist");
+        let revision = revision.clone();
+        async move {
+            match api
+                .patch_update_recipe(revision.id.clone(), &UpdateRecipe::all_of(revision.clone()))
+                .await
+            {
+                // the image is not part of a revision
+                Ok(_) => recipe.update(|recipe| {
+                    *recipe = Recipe {
+                        image_id: recipe.image_id.clone(),
+                        ..revision
+                    }
+                }),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "restoring recipe"));
+                    logout_on_401(&set_login, &err);
+                }
+            }
+        }
+    });
+
+    let revisions = move || {
+        let current = recipe.get();
+        let revisions = history.with(|v| v.revisions(&current.id).to_vec());
+        let nexts = revisions
+            .iter()
+            .skip(1)
+            .map(|v| v.recipe.clone())
+            .chain([current])
+            .collect::<Vec<_>>();
+        // newest first
+        revisions.into_iter().zip(nexts).rev().collect::<Vec<_>>()
+    };
+
+    view! {
+        <div class="flex flex-col gap-2">
+            {move || {
+                let revisions = revisions();
+                if revisions.is_empty() {
+                    return view!{
+                        <p>"No earlier versions yet, the recipe is remembered here before each edit."</p>
+                    }.into_view();
+                }
+                revisions.into_iter().map(|(revision, next)| {
+                    let restore = revision.recipe.clone();
+                    let restore = store_value(restore);
+                    view!{
+                        <RevisionRow
+                            revision=revision
+                            next=next
+                            on_restore=move || restore_action.dispatch(restore.get_value())
+                        />
+                    }
+                }).collect_view()
+            }}
+            <p class="text-sm">"History is kept in this browser only."</p>
+        </div>
+    }
+}
@@ -0,0 +1,164 @@
+use leptos::{
+    html::{Div, Img},
+    *,
+};
+use mcc_frontend_core::image::CropRect;
+use mcc_frontend_types::image::{average_color, recipe_image_url, ImagePlaceholder};
+use wasm_bindgen::{JsCast, JsValue};
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement, HtmlImageElement};
+
+use crate::{
+    contexts::prelude::{use_login, use_media, CurrentLogin},
+    helpers::observe_visibility,
+};
+
+// start loading a little before images scroll into view
+const LAZY_MARGIN: f64 = 200.0;
+// width of the tiny copy placeholders are made from
+const PLACEHOLDER_WIDTH: f64 = 16.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ImageStatus {
+    Waiting,
+    Loading,
+    Loaded,
+    Failed,
+}
+
+/// Make a placeholder by drawing an image tiny, fails for images from
+/// other origins that did not allow it (the canvas is tainted)
+fn make_placeholder<F>(width: f64, height: f64, draw: F) -> Result<ImagePlaceholder, JsValue>
+where
+    F: FnOnce(&CanvasRenderingContext2d, f64, f64) -> Result<(), JsValue>,
+{
+    let (thumb_width, thumb_height) = (
+        PLACEHOLDER_WIDTH,
+        (PLACEHOLDER_WIDTH * height / width).round().max(1.0),
+    );
+    let canvas = document()
+        .create_element("canvas")?
+        .dyn_into::<HtmlCanvasElement>()?;
+    canvas.set_width(thumb_width as u32);
+    canvas.set_height(thumb_height as u32);
+    let context = canvas
+        .get_context("2d")?
+        .ok_or_else(|| JsValue::from_str("no 2d context"))?
+        .dyn_into::<CanvasRenderingContext2d>()
+        .map_err(JsValue::from)?;
+    draw(&context, thumb_width, thumb_height)?;
+    let pixels = context.get_image_data(0.0, 0.0, thumb_width, thumb_height)?;
+    Ok(ImagePlaceholder {
+        color: average_color(&pixels.data()),
+        thumbnail: canvas.to_data_url_with_type("image/jpeg")?,
+    })
+}
+
+pub fn placeholder_from_image(image: &HtmlImageElement) -> Result<ImagePlaceholder, JsValue> {
+    let (width, height) = (image.natural_width() as f64, image.natural_height() as f64);
+    make_placeholder(width, height, |context, w, h| {
+        context.draw_image_with_html_image_element_and_dw_and_dh(image, 0.0, 0.0, w, h)
+    })
+}
+
+/// Placeholder for the part of a canvas that is being uploaded
+pub fn placeholder_from_canvas(
+    canvas: &HtmlCanvasElement,
+    crop: CropRect,
+) -> Result<ImagePlaceholder, JsValue> {
+    make_placeholder(crop.width, crop.height, |context, w, h| {
+        context.draw_image_with_html_canvas_element_and_sw_and_sh_and_dx_and_dy_and_dw_and_dh(
+            canvas,
+            crop.x,
+            crop.y,
+            crop.width,
+            crop.height,
+            0.0,
+            0.0,
+            w,
+            h,
+        )
+    })
+}
+
+/// A recipe image that only loads once near the screen. The average colour
+/// & a blurred tiny copy are shown while loading, if the image was seen before.
+#[component]
+pub fn RecipeImage(
+    image_id: String,
+    /// classes of the image, e.g. how it fits
+    #[prop(into, optional)]
+    class: String,
+) -> impl IntoView {
+    let CurrentLogin { login, .. } = use_login();
+    let media = use_media();
+    let media_url = login
+        .get_untracked()
+        .expect("expected login to exist")
+        .media_url;
+    let container_ref = create_node_ref::<Div>();
+    let image_ref = create_node_ref::<Img>();
+    let status = create_rw_signal(ImageStatus::Waiting);
+
+    observe_visibility(container_ref, LAZY_MARGIN, move |visible| {
+        if visible && status.get_untracked() == ImageStatus::Waiting {
+            status.set(ImageStatus::Loading);
+        }
+    });
+
+    let id = store_value(image_id.clone());
+    let placeholder = move || {
+        media
+            .placeholders
+            .with(|v| id.with_value(|id| v.get(id).cloned()))
+    };
+    let on_load = move |_| {
+        status.set(ImageStatus::Loaded);
+        if placeholder().is_none() {
+            if let Some(image) = image_ref.get_untracked() {
+                if let Ok(new_placeholder) = placeholder_from_image(&image) {
+                    id.with_value(|id| media.set_placeholder(id, new_placeholder));
+                }
+            }
+        }
+    };
+
+    let src = recipe_image_url(&media_url, &image_id);
+
+    // memos, so the image is only created once
+    let started = create_memo(move |_| status.get() != ImageStatus::Waiting);
+    let failed = create_memo(move |_| status.get() == ImageStatus::Failed);
+    let loading = create_memo(move |_| status.get() != ImageStatus::Loaded);
+
+    view! {
+        <div
+            node_ref=container_ref
+            class="relative w-full h-full overflow-hidden rounded-lg"
+            style=move || placeholder().map(|v| format!("background-color: {}", v.color))
+        >
+            {move || (loading.get() && !failed.get()).then(placeholder).flatten().map(|v| view! {
+                <img
+                    class="absolute inset-0 w-full h-full object-cover blur-lg scale-110"
+                    src=v.thumbnail
+                    alt=""
+                    aria-hidden="true"
+                />
+            })}
+            {move || (started.get() && !failed.get()).then(|| view! {
+                <img
+                    node_ref=image_ref
+                    on:load=on_load
+                    on:error=move |_| status.set(ImageStatus::Failed)
+                    class=format!("relative transition-opacity duration-300 {}", class)
+                    class:opacity-0=move || loading.get()
+                    src=src.clone()
+                    decoding="async"
+                />
+            })}
+            {move || failed.get().then(|| view! {
+                <div class="w-full h-full flex items-center justify-center bg-neutral text-neutral-content text-sm">
+                    "Image Unavailable"
+                </div>
+            })}
+        </div>
+    }
+}
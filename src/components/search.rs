@@ -0,0 +1,59 @@
+use leptos::*;
+use leptos_router::{use_navigate, NavigateOptions};
+use mcc_frontend_core::query::to_query_string;
+use mcc_frontend_types::query::SearchQuery;
+use web_sys::SubmitEvent;
+
+pub fn search_href(q: &str) -> String {
+    format!(
+        "/recipes/search?{}",
+        to_query_string(&SearchQuery { q: q.to_owned() })
+    )
+}
+
+/// Text with the given byte ranges wrapped in `<mark>`
+#[component]
+pub fn Highlighted(#[prop(into)] text: String, highlights: Vec<(usize, usize)>) -> impl IntoView {
+    let mut parts = Vec::new();
+    let mut last = 0;
+    for (start, end) in highlights {
+        if start < last {
+            continue;
+        }
+        parts.push(text[last..start].to_owned().into_view());
+        parts.push(
+            view! { <mark class="bg-warning text-warning-content rounded-sm">{text[start..end].to_owned()}</mark> }
+                .into_view(),
+        );
+        last = end;
+    }
+    parts.push(text[last..].to_owned().into_view());
+    parts.collect_view()
+}
+
+/// Search box that opens the recipe search page
+#[component]
+pub fn SearchBox() -> impl IntoView {
+    let q = create_rw_signal(String::default());
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let navigate = use_navigate();
+        navigate(&search_href(&q.get_untracked()), NavigateOptions::default());
+        q.set(String::default());
+    };
+
+    view! {
+        <form on:submit=on_submit class="join w-full">
+            <input
+                prop:value=move || q.get()
+                on:input=move |ev| q.set(event_target_value(&ev))
+                type="search"
+                class="input input-bordered input-sm join-item w-full"
+                placeholder="Search Recipes..."
+                aria-label="Search recipes"
+            />
+            <button type="submit" class="btn btn-sm join-item">"Search"</button>
+        </form>
+    }
+}
@@ -0,0 +1,27 @@
+use leptos::*;
+use leptos_router::A;
+use mcc_frontend_core::query::to_query_string;
+use mcc_frontend_types::query::RecipesFilter;
+use std::collections::HashSet;
+
+/// Link to the recipes page, showing only recipes with the tag
+pub fn tag_href(tag: &str) -> String {
+    let filters = RecipesFilter {
+        tags: Some(HashSet::from([tag.to_owned()])),
+        ..Default::default()
+    };
+    format!("/recipes?{}", to_query_string(&filters))
+}
+
+#[component]
+pub fn TagLinks(#[prop(into)] tags: Signal<Vec<String>>) -> impl IntoView {
+    view! {
+        <div class="flex flex-wrap gap-2">
+            {move || {
+                tags.get().into_iter().map(|tag| view!{
+                    <A href=tag_href(&tag) class="badge badge-secondary badge-lg shadow-md">{tag}</A>
+                }).collect_view()
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,124 @@
+use std::ops::Deref;
+
+use leptos::*;
+use mcc_frontend_core::virtual_list::{visible_items, VisibleItems};
+use wasm_bindgen::JsCast;
+
+use crate::helpers::viewport_height;
+
+// marks children of the container that are not rows, e.g. spacers of a table
+const SPACER_ATTRIBUTE: &str = "data-spacer";
+
+/// Which rows of a long list are rendered, see [`use_virtual_rows`]
+#[derive(Clone, Copy)]
+pub struct VirtualRows {
+    pub visible: Memo<VisibleItems>,
+    row_height: RwSignal<f64>,
+}
+
+impl VirtualRows {
+    /// Space in pixels taking the place of rows before those rendered
+    pub fn before(&self) -> f64 {
+        self.visible.get().rows_before as f64 * self.row_height.get()
+    }
+
+    /// Space in pixels taking the place of rows after those rendered
+    pub fn after(&self) -> f64 {
+        self.visible.get().rows_after as f64 * self.row_height.get()
+    }
+}
+
+/// How many columns a grid is shown with, 1 for anything else
+fn column_count(element: &web_sys::Element) -> usize {
+    window()
+        .get_computed_style(element)
+        .ok()
+        .flatten()
+        .and_then(|style| style.get_property_value("grid-template-columns").ok())
+        .filter(|v| v != "none")
+        .map(|v| v.split_whitespace().count().max(1))
+        .unwrap_or(1)
+}
+
+/// Distance between the tops of the first & last rendered rows, divided by the rows between
+fn measure_row_height(element: &web_sys::Element, columns: usize) -> Option<f64> {
+    let children = element.children();
+    let tops = (0..children.length())
+        .filter_map(|i| children.item(i))
+        .filter(|v| !v.has_attribute(SPACER_ATTRIBUTE))
+        .filter_map(|v| v.dyn_into::<web_sys::HtmlElement>().ok())
+        .map(|v| v.offset_top() as f64)
+        .collect::<Vec<_>>();
+    let rows = tops.len().div_ceil(columns);
+    if rows < 2 {
+        return None;
+    }
+    let height = (tops[(rows - 1) * columns] - tops[0]) / (rows - 1) as f64;
+    (height > 0.0).then_some(height)
+}
+
+/// Only render the rows of a long list that are near the screen (scrolled with the window),
+/// `container_ref` being the element the rows are direct children of,
+/// any other children (e.g. spacer rows) need a `data-spacer` attribute.
+///
+/// Rows are taken to be the same height, measured once rendered, until then
+/// `estimated_row_height` is used. It is best guessed on the large side,
+/// so restoring the scroll position is not cut short by the list being too short.
+/// Grids have as many items in a row as they show columns.
+pub fn use_virtual_rows<E>(
+    container_ref: NodeRef<E>,
+    len: Signal<usize>,
+    estimated_row_height: f64,
+) -> VirtualRows
+where
+    E: html::ElementDescriptor + Clone + Deref + 'static,
+    E::Target: AsRef<web_sys::Element>,
+{
+    // how far the window is scrolled past the top of the list, and its height
+    let viewport = create_rw_signal((0.0, viewport_height()));
+    let columns = create_rw_signal(1);
+    let row_height = create_rw_signal(estimated_row_height);
+
+    let measure = move || {
+        let Some(container) = container_ref.get_untracked() else {
+            return;
+        };
+        let element: &web_sys::Element = (*container).as_ref();
+        let new_viewport = (-element.get_bounding_client_rect().top(), viewport_height());
+        if new_viewport != viewport.get_untracked() {
+            viewport.set(new_viewport);
+        }
+        let new_columns = column_count(element);
+        if new_columns != columns.get_untracked() {
+            columns.set(new_columns);
+        }
+        if let Some(height) = measure_row_height(element, new_columns) {
+            if (height - row_height.get_untracked()).abs() > 0.5 {
+                row_height.set(height);
+            }
+        }
+    };
+
+    let scroll_handle = window_event_listener(ev::scroll, move |_| measure());
+    let resize_handle = window_event_listener(ev::resize, move |_| measure());
+    on_cleanup(move || {
+        scroll_handle.remove();
+        resize_handle.remove();
+    });
+
+    let visible = create_memo(move |_| {
+        let (offset, height) = viewport.get();
+        visible_items(len.get(), columns.get(), row_height.get(), offset, height)
+    });
+
+    // measure again once different rows have rendered
+    create_effect(move |_| {
+        visible.track();
+        request_animation_frame(measure);
+    });
+
+    VirtualRows {
+        visible,
+        row_height,
+    }
+}
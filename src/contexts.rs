@@ -1,14 +1,22 @@
 pub mod api;
+pub mod labels;
 pub mod login;
+pub mod media;
 pub mod modal_controller;
+pub mod pantry;
+pub mod search;
+pub mod shortcuts;
 pub mod toasts;
 
 /// Module used to re-export frequently used items, to reduce imports.
 pub mod prelude {
     pub use super::api::{use_api, CurrentApi};
+    pub use super::labels::{use_label_meta, CurrentLabelMeta};
     pub use super::login::{use_login, CurrentLogin};
-    pub use super::modal_controller::{
-        use_modal_controller, ModalController, ModalViewer, ModalViewerProps,
-    };
+    pub use super::media::{use_media, CurrentMedia};
+    pub use super::modal_controller::{use_modal_controller, ModalController, ModalViewer};
+    pub use super::pantry::{use_pantry, CurrentPantry};
+    pub use super::search::{use_search_index, CurrentSearchIndex};
+    pub use super::shortcuts::{use_shortcuts, Shortcuts};
     pub use super::toasts::*;
 }
@@ -0,0 +1,91 @@
+use leptos::*;
+use mcc_frontend_core::{
+    api::{Api, ApiError},
+    storage,
+};
+use mcc_frontend_types::{
+    label::{LabelChange, LabelMeta, LabelMetadata},
+    StoredLogin,
+};
+
+use super::{
+    login::{use_login, CurrentLogin},
+    toasts::{use_toasts, Toasts},
+};
+use crate::helpers::storage_error_to_toast;
+
+/// Label colours, icons & parents for the logged in account
+#[derive(Copy, Clone)]
+pub struct CurrentLabelMeta {
+    pub metadata: ReadSignal<LabelMetadata>,
+    set_metadata: WriteSignal<LabelMetadata>,
+    /// every label in use, see [`Self::known_labels`]
+    known: StoredValue<Option<Vec<String>>>,
+    login: ReadSignal<Option<StoredLogin>>,
+    toasts: Toasts,
+}
+
+impl CurrentLabelMeta {
+    pub fn new() -> Self {
+        let CurrentLogin { login, .. } = use_login();
+        let (metadata, set_metadata) = create_signal(LabelMetadata::default());
+        let known = store_value(None);
+
+        create_effect(move |_| {
+            let metadata = login
+                .get()
+                .map(|v| storage::read_label_metadata(&v))
+                .unwrap_or_default();
+            set_metadata.set(metadata);
+            known.set_value(None);
+        });
+
+        Self {
+            metadata,
+            set_metadata,
+            known,
+            login,
+            toasts: use_toasts(),
+        }
+    }
+
+    fn update(&self, f: impl FnOnce(&mut LabelMetadata)) {
+        // a label may have been given a parent, or renamed
+        self.known.set_value(None);
+        self.set_metadata.update(|metadata| {
+            f(metadata);
+            if let Some(login) = self.login.get_untracked() {
+                if let Err(err) = storage::set_label_metadata(&login, metadata) {
+                    self.toasts.push(storage_error_to_toast(
+                        &err,
+                        "label colours, icons & parents",
+                    ));
+                }
+            }
+        });
+    }
+
+    pub fn set(&self, label: &str, meta: LabelMeta) {
+        self.update(|metadata| metadata.set(label, meta));
+    }
+
+    pub fn apply_change(&self, change: &LabelChange) {
+        self.update(|metadata| metadata.apply_change(change));
+    }
+
+    /// Every label in use, loaded from the server the first time it is needed
+    /// and again after labels are changed here.
+    /// Used to tell which labels have children, which only change along with the metadata.
+    pub async fn known_labels(&self, api: &Api) -> Result<Vec<String>, ApiError> {
+        if let Some(known) = self.known.get_value() {
+            return Ok(known);
+        }
+        let known = api.get_labels().await?;
+        self.known.set_value(Some(known.clone()));
+        Ok(known)
+    }
+}
+
+pub fn use_label_meta() -> CurrentLabelMeta {
+    use_context::<CurrentLabelMeta>().expect("unable to get current label meta context")
+}
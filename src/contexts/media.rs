@@ -0,0 +1,50 @@
+use leptos::*;
+use mcc_frontend_core::storage;
+use mcc_frontend_types::{
+    image::{ImagePlaceholder, ImagePlaceholders},
+    StoredLogin,
+};
+
+use super::login::{use_login, CurrentLogin};
+
+/// Placeholders for images already seen
+#[derive(Copy, Clone)]
+pub struct CurrentMedia {
+    pub placeholders: ReadSignal<ImagePlaceholders>,
+    set_placeholders: WriteSignal<ImagePlaceholders>,
+    login: ReadSignal<Option<StoredLogin>>,
+}
+
+impl CurrentMedia {
+    pub fn new() -> Self {
+        let CurrentLogin { login, .. } = use_login();
+        let (placeholders, set_placeholders) = create_signal(ImagePlaceholders::default());
+
+        create_effect(move |_| {
+            let placeholders = login
+                .get()
+                .map(|v| storage::read_image_placeholders(&v))
+                .unwrap_or_default();
+            set_placeholders.set(placeholders);
+        });
+
+        Self {
+            placeholders,
+            set_placeholders,
+            login,
+        }
+    }
+
+    pub fn set_placeholder(&self, image_id: &str, placeholder: ImagePlaceholder) {
+        self.set_placeholders.update(|placeholders| {
+            placeholders.insert(image_id, placeholder);
+            if let Some(login) = self.login.get_untracked() {
+                storage::set_image_placeholders(&login, placeholders);
+            }
+        });
+    }
+}
+
+pub fn use_media() -> CurrentMedia {
+    use_context::<CurrentMedia>().expect("unable to get current media context")
+}
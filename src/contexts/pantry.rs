@@ -0,0 +1,60 @@
+use leptos::*;
+use mcc_frontend_core::api::Api;
+use mcc_frontend_types::pantry::{Item, Location};
+
+use super::login::{use_login, CurrentLogin};
+use crate::helpers::load_all_pantry_items;
+
+/// Every pantry item & location of the logged in account, for lookups outside the pantry page
+/// (e.g. the command palette). Loaded the first time they are needed, then again after changes.
+#[derive(Copy, Clone)]
+pub struct CurrentPantry {
+    /// none until loaded, or after [`Self::invalidate`]
+    pub contents: RwSignal<Option<(Vec<Item>, Vec<Location>)>>,
+    loading: StoredValue<bool>,
+    /// bumped on every change, so a load started before one is not kept
+    version: StoredValue<usize>,
+}
+
+impl CurrentPantry {
+    pub fn new() -> Self {
+        let CurrentLogin { login, .. } = use_login();
+        let contents = create_rw_signal(None);
+        // forget the previous account's pantry
+        create_effect(move |_| login.with(|_| contents.set(None)));
+
+        Self {
+            contents,
+            loading: store_value(false),
+            version: store_value(0),
+        }
+    }
+
+    /// Load the items & locations unless already loaded,
+    /// failures just leave them unloaded until the next time they are needed
+    pub async fn load(&self, api: &Api) {
+        if self.contents.with_untracked(Option::is_some) || self.loading.get_value() {
+            return;
+        }
+        let version = self.version.get_value();
+        self.loading.set_value(true);
+        let items = load_all_pantry_items(api).await;
+        let locations = api.get_pantry_locations().await;
+        self.loading.set_value(false);
+        if let (Ok(items), Ok(locations)) = (items, locations) {
+            if self.version.get_value() == version {
+                self.contents.set(Some((items, locations)));
+            }
+        }
+    }
+
+    /// Forget what was loaded, called after pantry items or locations are changed
+    pub fn invalidate(&self) {
+        self.version.update_value(|v| *v += 1);
+        self.contents.set(None);
+    }
+}
+
+pub fn use_pantry() -> CurrentPantry {
+    use_context::<CurrentPantry>().expect("unable to get current pantry context")
+}
@@ -0,0 +1,126 @@
+use leptos::*;
+use mcc_frontend_core::{
+    api::{Api, ApiError},
+    storage,
+};
+use mcc_frontend_types::{
+    recipe::{count_tags, Recipe},
+    search::{SearchDocument, SearchIndex},
+    StoredLogin,
+};
+use std::collections::HashMap;
+
+use super::login::{use_login, CurrentLogin};
+use crate::helpers::load_all_recipes;
+
+/// Full-text search index of the logged in account's recipes
+#[derive(Copy, Clone)]
+pub struct CurrentSearchIndex {
+    pub index: ReadSignal<SearchIndex>,
+    set_index: WriteSignal<SearchIndex>,
+    /// whether the index is being brought up to date
+    pub refreshing: RwSignal<bool>,
+    /// tags of each recipe, once every recipe has been loaded
+    recipe_tags: RwSignal<Option<HashMap<String, Vec<String>>>>,
+    /// how many recipes use each tag, see [`Self::load_tags`]
+    pub tag_counts: Memo<Option<Vec<(String, usize)>>>,
+    login: ReadSignal<Option<StoredLogin>>,
+}
+
+impl CurrentSearchIndex {
+    pub fn new() -> Self {
+        let CurrentLogin { login, .. } = use_login();
+        let (index, set_index) = create_signal(SearchIndex::default());
+        let recipe_tags = create_rw_signal(None::<HashMap<String, Vec<String>>>);
+
+        create_effect(move |_| {
+            let index = login
+                .get()
+                .map(|v| storage::read_search_index(&v))
+                .unwrap_or_default();
+            set_index.set(index);
+            recipe_tags.set(None);
+        });
+
+        Self {
+            index,
+            set_index,
+            refreshing: create_rw_signal(false),
+            recipe_tags,
+            tag_counts: create_memo(move |_| {
+                recipe_tags.with(|tags| {
+                    tags.as_ref()
+                        .map(|tags| count_tags(tags.values().map(Vec::as_slice)))
+                })
+            }),
+            login,
+        }
+    }
+
+    /// Update the index, saving it when `f` reports a change
+    fn update(&self, f: impl FnOnce(&mut SearchIndex) -> bool) {
+        self.set_index.update(|index| {
+            if f(index) {
+                if let Some(login) = self.login.get_untracked() {
+                    storage::set_search_index(&login, index);
+                }
+            }
+        });
+    }
+
+    pub fn upsert(&self, recipe: &Recipe) {
+        self.update(|index| index.upsert(SearchDocument::from(recipe)));
+        self.recipe_tags.update(|tags| {
+            if let Some(tags) = tags {
+                tags.insert(recipe.id.clone(), recipe.tags.clone());
+            }
+        });
+    }
+
+    pub fn remove(&self, id: &str) {
+        self.update(|index| index.remove(id));
+        self.recipe_tags.update(|tags| {
+            if let Some(tags) = tags {
+                tags.remove(id);
+            }
+        });
+    }
+
+    /// Remove everything, ready for a rebuild with [`Self::refresh`]
+    pub fn clear(&self) {
+        self.update(|index| {
+            *index = SearchIndex::default();
+            true
+        });
+    }
+
+    /// Load every recipe, re-indexing only those that changed
+    pub async fn refresh(&self, api: &Api) -> Result<(), ApiError> {
+        self.refreshing.set(true);
+        let recipes = load_all_recipes(api).await;
+        self.refreshing.set(false);
+        let recipes = recipes?;
+        self.recipe_tags.set(Some(
+            recipes
+                .iter()
+                .map(|recipe| (recipe.id.clone(), recipe.tags.clone()))
+                .collect(),
+        ));
+        let documents = recipes.iter().map(SearchDocument::from).collect();
+        self.update(|index| index.refresh(documents));
+        Ok(())
+    }
+
+    /// Load every recipe for [`Self::tag_counts`], only the first time they are needed
+    /// (later changes are kept up to date by [`Self::upsert`] & [`Self::remove`])
+    pub async fn load_tags(&self, api: &Api) -> Result<(), ApiError> {
+        if self.recipe_tags.with_untracked(Option::is_some) || self.refreshing.get_untracked() {
+            return Ok(());
+        }
+        self.refresh(api).await
+    }
+}
+
+pub fn use_search_index() -> CurrentSearchIndex {
+    use_context::<CurrentSearchIndex>().expect("unable to get current search index context")
+}
@@ -0,0 +1,74 @@
+use std::rc::Rc;
+
+use leptos::*;
+
+/// An action offered by the current page, through a keyboard shortcut and the command palette
+#[derive(Clone)]
+pub struct PageAction {
+    id: usize,
+    /// a single key (as in `KeyboardEvent.key`) pressed without modifiers
+    pub key: Option<&'static str>,
+    pub title: String,
+    pub action: Rc<dyn Fn()>,
+}
+
+/// Keyboard shortcuts & actions of the current page, along with the overlays showing them
+#[derive(Copy, Clone)]
+pub struct Shortcuts {
+    pub actions: ReadSignal<Vec<PageAction>>,
+    set_actions: WriteSignal<Vec<PageAction>>,
+    next_id: StoredValue<usize>,
+    pub palette_open: RwSignal<bool>,
+    pub help_open: RwSignal<bool>,
+}
+
+impl Shortcuts {
+    pub fn new() -> Self {
+        let (actions, set_actions) = create_signal(Vec::new());
+        Self {
+            actions,
+            set_actions,
+            next_id: store_value(0),
+            palette_open: create_rw_signal(false),
+            help_open: create_rw_signal(false),
+        }
+    }
+
+    /// Offer an action until the calling component is cleaned up
+    pub fn register(&self, key: Option<&'static str>, title: &str, action: impl Fn() + 'static) {
+        let id = self.next_id.get_value();
+        self.next_id.set_value(id + 1);
+        self.set_actions.update(|actions| {
+            actions.push(PageAction {
+                id,
+                key,
+                title: title.to_owned(),
+                action: Rc::new(action),
+            })
+        });
+        let set_actions = self.set_actions;
+        on_cleanup(move || set_actions.update(|actions| actions.retain(|v| v.id != id)));
+    }
+
+    /// Run the most recently registered action for a key, returning whether there was one
+    pub fn run_key(&self, key: &str) -> bool {
+        let action = self.actions.with_untracked(|actions| {
+            actions
+                .iter()
+                .rev()
+                .find(|v| v.key == Some(key))
+                .map(|v| v.action.clone())
+        });
+        match action {
+            Some(action) => {
+                action();
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+pub fn use_shortcuts() -> Shortcuts {
+    use_context::<Shortcuts>().expect("unable to get shortcuts context")
+}
@@ -1,8 +1,37 @@
-use leptos::*;
-use mcc_frontend_core::api::{ApiError, ApiInternalError};
-use mcc_frontend_types::StoredLogin;
+use leptos::{leptos_dom::helpers::TimeoutHandle, *};
+use mcc_frontend_core::{
+    api::{Api, ApiError, ApiInternalError},
+    storage::{read_scroll_position, set_scroll_position, StorageError},
+};
+use mcc_frontend_types::{
+    label::LabelMetadata,
+    page::PagingMode,
+    pantry::Item,
+    query::{PantryFilter, RecipesFilter},
+    recipe::Recipe,
+    sub_recipe::linked_recipe_ids,
+    StoredLogin,
+};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    future::Future,
+    ops::Deref,
+    rc::Rc,
+    time::Duration,
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+use web_sys::{IntersectionObserver, IntersectionObserverEntry, IntersectionObserverInit};
 
-use crate::contexts::prelude::Toast;
+use crate::contexts::prelude::{CurrentLabelMeta, Toast};
+
+// page size used when every item needs to be loaded
+const LOAD_ALL_PER_PAGE: usize = 100;
+
+// how long scrolling has to stop for before its position is remembered
+const SCROLL_SAVE_DELAY: Duration = Duration::from_millis(200);
+
+type ObserverCallback = Closure<dyn FnMut(js_sys::Array, IntersectionObserver)>;
 
 /// Logout the user if the API returns a 401.
 /// Returns true if the user was logged out
@@ -44,3 +73,349 @@ pub fn api_error_to_toast(error: &ApiError, when: &str) -> Toast {
         },
     }
 }
+
+/// Convert a failure to save to the browser's storage to a toast message,
+/// 'what' describes what was not saved
+pub fn storage_error_to_toast(error: &StorageError, what: &str) -> Toast {
+    log::error!("Storage error handled: {error:?}, when saving {what}");
+    Toast {
+        message: format!("Could not save {what} in this browser, its storage may be full"),
+    }
+}
+
+/// Load pages `first_page..=last_page` in order,
+/// used to restore a list (e.g. after navigating back) before continuing page by page.
+///
+/// `load` is given the page & items per page.
+///
+/// Returns every item and whether there may be items after the last page.
+/// The server does not say how many items there are, so there may be more when
+/// the last page is full (a list ending exactly on a page is followed by an empty page).
+pub async fn load_pages<T, F, Fut>(
+    first_page: usize,
+    last_page: usize,
+    per_page: usize,
+    load: F,
+) -> Result<(Vec<T>, bool), ApiError>
+where
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, ApiError>>,
+{
+    let last_page = last_page.max(first_page);
+    let mut items = Vec::new();
+    let mut last_len = 0;
+    for page in first_page..=last_page {
+        let loaded = load(page, per_page).await?;
+        last_len = loaded.len();
+        items.extend(loaded);
+    }
+    Ok((items, per_page != 0 && last_len == per_page))
+}
+
+/// Items matching a filter the server can not apply, with how far through the server's
+/// list they were read. Kept between calls to [`load_filtered_pages`] for the same filter.
+pub struct FilteredPages<K, T> {
+    key: Option<K>,
+    next_page: usize,
+    matched: Vec<T>,
+    exhausted: bool,
+}
+
+impl<K, T> Default for FilteredPages<K, T> {
+    fn default() -> Self {
+        Self {
+            key: None,
+            next_page: 1,
+            matched: Vec::new(),
+            exhausted: false,
+        }
+    }
+}
+
+/// Load pages `first_page..=last_page` of a list that is partly filtered by the client,
+/// like [`load_pages`] but server pages are read until there are enough items that `keep`,
+/// so pages are not left short (or empty) by the items left out.
+///
+/// What was read is kept in `state` for the next page of the same `key`,
+/// it is read again from the start when loading from the first page.
+pub async fn load_filtered_pages<K, T, F, Fut, P>(
+    state: StoredValue<FilteredPages<K, T>>,
+    key: K,
+    first_page: usize,
+    last_page: usize,
+    per_page: usize,
+    load: F,
+    keep: P,
+) -> Result<(Vec<T>, bool), ApiError>
+where
+    K: PartialEq + 'static,
+    T: Clone + 'static,
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, ApiError>>,
+    P: Fn(&T) -> bool,
+{
+    let last_page = last_page.max(first_page);
+    let mut filtered = state.try_update_value(std::mem::take).unwrap_or_default();
+    if first_page == 1 || filtered.key.as_ref() != Some(&key) {
+        filtered = FilteredPages {
+            key: Some(key),
+            ..Default::default()
+        };
+    }
+    // one more than is shown, to know whether there are more
+    let wanted = last_page * per_page + 1;
+    let mut result = Ok(());
+    while !filtered.exhausted && filtered.matched.len() < wanted {
+        match load(filtered.next_page, LOAD_ALL_PER_PAGE).await {
+            Ok(loaded) => {
+                filtered.exhausted = loaded.len() < LOAD_ALL_PER_PAGE;
+                filtered.next_page += 1;
+                filtered
+                    .matched
+                    .extend(loaded.into_iter().filter(|item| keep(item)));
+            }
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        }
+    }
+    let start = (first_page.saturating_sub(1) * per_page).min(filtered.matched.len());
+    let end = (last_page * per_page).min(filtered.matched.len());
+    let loaded = filtered.matched[start..end].to_vec();
+    let has_more = filtered.matched.len() > end;
+    // what was read before an error is still kept, so a retry carries on from there
+    state.set_value(filtered);
+    result.map(|_| (loaded, has_more))
+}
+
+/// Key a list's scroll position is remembered under, from its path & query string.
+/// When scrolling the loaded pages are all one list, so the page is left out.
+pub fn scroll_key(path: &str, query: &str, paging: PagingMode) -> String {
+    let query = match paging {
+        PagingMode::Scroll => query
+            .split('&')
+            .filter(|v| !v.starts_with("page="))
+            .collect::<Vec<_>>()
+            .join("&"),
+        PagingMode::Numbered => query.to_owned(),
+    };
+    format!("{path}?{query}")
+}
+
+/// Remember a scroll position for this browser session
+pub fn remember_scroll_position(key: &str, position: f64) {
+    // only saves the user scrolling back, so a full storage is not worth a toast
+    if let Err(error) = set_scroll_position(key, position) {
+        log::error!("Storage error handled: {error:?}, when saving scroll position");
+    }
+}
+
+/// Remember the window scroll position under a key (while not paused),
+/// saved once scrolling stops rather than on every scroll event.
+/// Stops when the current component is cleaned up, saving any position not yet saved.
+pub fn track_scroll_position<K>(key: K, paused: Signal<bool>)
+where
+    K: Fn() -> String + 'static,
+{
+    let pending = Rc::new(RefCell::new(None::<(String, f64, TimeoutHandle)>));
+    let handle = window_event_listener(ev::scroll, {
+        let pending = pending.clone();
+        move |_| {
+            if paused.get_untracked() {
+                return;
+            }
+            let save = {
+                let pending = pending.clone();
+                move || {
+                    if let Some((key, position, _)) = pending.take() {
+                        remember_scroll_position(&key, position);
+                    }
+                }
+            };
+            if let Ok(timeout) = set_timeout_with_handle(save, SCROLL_SAVE_DELAY) {
+                let position = window().scroll_y().unwrap_or_default();
+                if let Some((_, _, previous)) = pending.replace(Some((key(), position, timeout))) {
+                    previous.clear();
+                }
+            }
+        }
+    });
+    on_cleanup(move || {
+        handle.remove();
+        if let Some((key, position, timeout)) = pending.take() {
+            timeout.clear();
+            remember_scroll_position(&key, position);
+        }
+    });
+}
+
+/// Scroll to a position remembered by [`track_scroll_position`], once rendered
+pub fn restore_scroll_position(key: &str) {
+    if let Some(position) = read_scroll_position(key) {
+        request_animation_frame(move || window().scroll_to_with_x_and_y(0.0, position));
+    }
+}
+
+/// Height of the browser window's viewport in pixels
+pub fn viewport_height() -> f64 {
+    window()
+        .inner_height()
+        .ok()
+        .and_then(|v| v.as_f64())
+        .unwrap_or_default()
+}
+
+/// Call `on_change` whenever an element comes within `margin` pixels of the screen
+/// (or goes out of it), stops when the current component is cleaned up.
+/// Browsers without an IntersectionObserver are always told it is visible.
+pub fn observe_visibility<E, F>(node_ref: NodeRef<E>, margin: f64, on_change: F)
+where
+    E: html::ElementDescriptor + Clone + Deref + 'static,
+    E::Target: AsRef<web_sys::Element>,
+    F: Fn(bool) + 'static,
+{
+    let on_change = Rc::new(on_change);
+    let observer = store_value(None::<(IntersectionObserver, ObserverCallback)>);
+    node_ref.on_load(move |element| {
+        let callback: ObserverCallback = {
+            let on_change = on_change.clone();
+            Closure::new(move |entries: js_sys::Array, _| {
+                if let Some(entry) = entries.iter().last() {
+                    on_change(
+                        entry
+                            .unchecked_into::<IntersectionObserverEntry>()
+                            .is_intersecting(),
+                    );
+                }
+            })
+        };
+        let mut options = IntersectionObserverInit::new();
+        options.root_margin(&format!("{margin}px"));
+        match IntersectionObserver::new_with_options(callback.as_ref().unchecked_ref(), &options) {
+            Ok(new_observer) => {
+                new_observer.observe((*element).as_ref());
+                observer.set_value(Some((new_observer, callback)));
+            }
+            Err(_) => on_change(true),
+        }
+    });
+    on_cleanup(move || {
+        observer.update_value(|observer| {
+            if let Some((observer, _)) = observer.take() {
+                observer.disconnect();
+            }
+        })
+    });
+}
+
+/// Load every page, for when the whole collection is needed (e.g. counting tags)
+pub async fn load_all<T, F, Fut>(load: F) -> Result<Vec<T>, ApiError>
+where
+    F: Fn(usize, usize) -> Fut,
+    Fut: Future<Output = Result<Vec<T>, ApiError>>,
+{
+    let mut items = Vec::new();
+    for page in 1.. {
+        let loaded = load(page, LOAD_ALL_PER_PAGE).await?;
+        let is_last = loaded.len() < LOAD_ALL_PER_PAGE;
+        items.extend(loaded);
+        if is_last {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+pub async fn load_all_recipes(api: &Api) -> Result<Vec<Recipe>, ApiError> {
+    load_all(|page, per_page| {
+        let filters = RecipesFilter {
+            page,
+            per_page,
+            ..Default::default()
+        };
+        async move { api.get_recipes(&filters).await }
+    })
+    .await
+}
+
+pub async fn load_all_pantry_items(api: &Api) -> Result<Vec<Item>, ApiError> {
+    load_all(|page, per_page| {
+        let filters = PantryFilter {
+            page,
+            per_page,
+            ..Default::default()
+        };
+        async move { api.get_pantry_items(&filters).await }
+    })
+    .await
+}
+
+/// Labels that the server should filter by,
+/// parent labels are left out and have to be checked with [`LabelMetadata::matches`].
+pub async fn server_labels(
+    api: &Api,
+    label_meta: &CurrentLabelMeta,
+    metadata: &LabelMetadata,
+    selected: &Option<HashSet<String>>,
+) -> Result<Option<HashSet<String>>, ApiError> {
+    match selected {
+        Some(selected) if !selected.is_empty() => {
+            let known = label_meta.known_labels(api).await?;
+            Ok(Some(metadata.server_labels(selected, &known)))
+        }
+        _ => Ok(selected.clone()),
+    }
+}
+
+/// Load every recipe linked from the recipes' ingredients, following links recursively.
+/// The recipes given are included, so links between them are not loaded again.
+/// Each recipe is only loaded once so links that form a cycle still finish,
+/// linked recipes that no longer exist are left out.
+pub async fn load_linked_recipes(
+    api: &Api,
+    from: &[Recipe],
+) -> Result<HashMap<String, Recipe>, ApiError> {
+    let mut recipes = from
+        .iter()
+        .map(|v| (v.id.clone(), v.clone()))
+        .collect::<HashMap<_, _>>();
+    let mut visited = recipes.keys().cloned().collect::<HashSet<_>>();
+    let mut pending = from
+        .iter()
+        .flat_map(|v| linked_recipe_ids(&v.ingredients))
+        .collect::<Vec<_>>();
+    while let Some(id) = pending.pop() {
+        if !visited.insert(id.clone()) {
+            continue;
+        }
+        match api.get_recipe_by_id(id.clone()).await {
+            Ok(linked) => {
+                pending.extend(linked_recipe_ids(&linked.ingredients));
+                recipes.insert(id, linked);
+            }
+            Err(ApiError::Response(e)) if e.status_code == 404 => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(recipes)
+}
+
+/// Load recipes by id keeping their order, for lists kept by the client (e.g. collections).
+///
+/// Returns the recipes and the ids of those that no longer exist.
+pub async fn load_recipes(
+    api: &Api,
+    ids: &[String],
+) -> Result<(Vec<Recipe>, Vec<String>), ApiError> {
+    let mut recipes = Vec::new();
+    let mut missing = Vec::new();
+    for id in ids {
+        match api.get_recipe_by_id(id.clone()).await {
+            Ok(recipe) => recipes.push(recipe),
+            Err(ApiError::Response(e)) if e.status_code == 404 => missing.push(id.clone()),
+            Err(err) => return Err(err),
+        }
+    }
+    Ok((recipes, missing))
+}
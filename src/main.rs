@@ -7,8 +7,15 @@ pub(crate) mod helpers;
 pub(crate) mod modals;
 pub(crate) mod pages;
 
-use components::drawer::*;
+use components::{command_palette::*, drawer::*};
 use contexts::prelude::*;
+use mcc_frontend_core::query::to_query_string;
+use mcc_frontend_types::{pantry::Item, query::PantryFilter};
+use modals::{
+    edit_pantry::{EditItemModal, NewItemModal},
+    edit_recipe::NewRecipeModal,
+    CreationMode,
+};
 use pages::*;
 
 fn main() {
@@ -22,10 +29,57 @@ pub fn AppMain() -> impl IntoView {
     let drawer_links = vec![
         DrawerLink::new("/", "Home"),
         DrawerLink::new("/recipes", "Recipes"),
+        DrawerLink::new("/collections", "Collections"),
         DrawerLink::new("/pantry", "Pantry"),
+        DrawerLink::new("/labels", "Labels"),
     ];
+    let shortcuts = use_shortcuts();
+    let modal_controller = use_modal_controller();
+    let navigate = store_value(use_navigate());
+
+    // actions that can be run from any page, through the command palette
+    let on_new_recipe_action = move |recipe_id: Option<String>| {
+        if let Some(recipe_id) = recipe_id {
+            navigate.with_value(|navigate| {
+                navigate(&format!("/recipes/{}", recipe_id), Default::default())
+            });
+        }
+        modal_controller.close();
+    };
+    let on_new_item_action = move |creation: Option<(CreationMode, Item)>| {
+        let Some((mode, new_item)) = creation else {
+            modal_controller.close();
+            return;
+        };
+        let filter = PantryFilter {
+            name: Some(new_item.name.clone()),
+            ..Default::default()
+        };
+        navigate.with_value(|navigate| {
+            navigate(
+                &format!("/pantry?{}", to_query_string(&filter)),
+                Default::default(),
+            )
+        });
+        match mode {
+            CreationMode::CreateAndEdit => modal_controller.open(view! {
+                <EditItemModal item=new_item on_action=move |_| modal_controller.close() />
+            }),
+            CreationMode::Create => modal_controller.close(),
+        }
+    };
+    shortcuts.register(None, "New Recipe", move || {
+        modal_controller.open(view! {<NewRecipeModal on_action=on_new_recipe_action />})
+    });
+    shortcuts.register(None, "New Pantry Item", move || {
+        modal_controller.open(view! {<NewItemModal on_action=on_new_item_action />})
+    });
+
     view! {
-        <Drawer links=drawer_links>
+        <KeyboardShortcuts/>
+        <CommandPalette/>
+        <ShortcutsHelp/>
+        <Drawer links=drawer_links search=true>
             <Outlet />
         </Drawer>
     }
@@ -33,10 +87,16 @@ pub fn AppMain() -> impl IntoView {
 
 #[component]
 pub fn App() -> impl IntoView {
+    // first, so the other contexts can report problems
+    provide_context(Toasts::new());
     provide_context(CurrentLogin::new());
     provide_context(CurrentApi::new());
-    provide_context(Toasts::new());
+    provide_context(CurrentLabelMeta::new());
+    provide_context(CurrentMedia::new());
+    provide_context(CurrentSearchIndex::new());
+    provide_context(CurrentPantry::new());
     provide_context(ModalController::new());
+    provide_context(Shortcuts::new());
 
     let CurrentLogin { login, .. } = use_login();
 
@@ -52,10 +112,18 @@ pub fn App() -> impl IntoView {
                 <ProtectedRoute path="/" redirect_path="/login" condition=has_auth view=move || view! {<AppMain/>}>
                     <Route path="/" view=move || view! { <Home/>}/>
                     <Route path="/recipes" view=move || view! {<Recipes/>} />
+                    <Route path="/recipes/tags" view=move || view! {<RecipeTags/>} />
+                    <Route path="/recipes/search" view=move || view! {<RecipeSearch/>} />
                     <Route path="recipes/:id" view=move || view! {<RecipePage/>} />
+                    <Route path="recipes/:id/edit" view=move || view! {<RecipeEditPage/>} />
+                    <Route path="/collections" view=move || view! {<CollectionsPage/>} />
+                    <Route path="collections/:id" view=move || view! {<CollectionPage/>} />
                     <Route path="/pantry" view=move || view! {<Pantry/>} />
+                    <Route path="/labels" view=move || view! {<Labels/>} />
                 </ProtectedRoute>
+                <ProtectedRoute path="recipes/print" redirect_path="/login" condition=has_auth view=move || view! {<CookbookPrint/>} />
                 <ProtectedRoute path="recipes/:id/print" redirect_path="/login" condition=has_auth view=move || view! {<RecipePrint/>} />
+                <ProtectedRoute path="collections/:id/print" redirect_path="/login" condition=has_auth view=move || view! {<CollectionPrint/>} />
                 <ProtectedRoute path="/signup" redirect_path="/" condition=has_no_auth view=move || view! {<Signup/>} />
                 <ProtectedRoute path="/login" redirect_path="/" condition=has_no_auth view=move || view! {<Login/>} />
                 <Route path="/logout" view=move || view! {<Logout/>} />
@@ -1,5 +1,6 @@
 pub mod base;
 pub mod edit_pantry;
 pub mod edit_recipe;
+pub mod labels;
 
 pub use base::CreationMode;
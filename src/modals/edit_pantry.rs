@@ -1,7 +1,9 @@
+pub mod bulk_edit;
 pub mod edit_item;
 pub mod locations;
 pub mod new_item;
 
+pub use bulk_edit::*;
 pub use edit_item::EditItemModal;
 pub use locations::LocationsModal;
 pub use new_item::NewItemModal;
@@ -0,0 +1,350 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use leptos::*;
+use std::collections::HashSet;
+
+use mcc_frontend_core::api::{Api, ApiError};
+use mcc_frontend_types::pantry::{BulkItemAction, CreateItem, Item};
+
+use crate::{
+    components::input::LabelSelector,
+    contexts::prelude::{use_api, use_pantry, use_toasts, CurrentApi},
+    helpers::api_error_to_toast,
+    modals::base::ModalFormBase,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BulkEditKind {
+    Move,
+    Labels,
+    Expiry,
+    Quantity,
+    Delete,
+}
+
+impl BulkEditKind {
+    fn title(&self) -> &'static str {
+        match self {
+            Self::Move => "Move Items",
+            Self::Labels => "Edit Item Labels",
+            Self::Expiry => "Set Item Expiry",
+            Self::Quantity => "Adjust Item Quantity",
+            Self::Delete => "Delete Items",
+        }
+    }
+}
+
+/// Outcome of a bulk edit, where at least one item succeeded
+#[derive(Debug, Clone)]
+pub struct BulkEditResult {
+    pub action: BulkItemAction,
+    /// items before the action was applied, used to undo
+    pub previous: Vec<Item>,
+    /// items after the action was applied (empty when deleting)
+    pub updated: Vec<Item>,
+}
+
+impl BulkEditResult {
+    fn merge(&mut self, other: BulkEditResult) {
+        self.previous.extend(other.previous);
+        self.updated.extend(other.updated);
+    }
+}
+
+/// Apply an action to each item, one request at a time.
+///
+/// Returns the successful result (if any) and the items that failed.
+pub async fn run_bulk_action<P>(
+    api: &Api,
+    items: Vec<Item>,
+    action: BulkItemAction,
+    on_progress: P,
+) -> (Option<BulkEditResult>, Vec<(Item, ApiError)>)
+where
+    P: Fn(usize),
+{
+    let mut previous = Vec::new();
+    let mut updated = Vec::new();
+    let mut failed = Vec::new();
+    for (i, item) in items.into_iter().enumerate() {
+        let response = match action.apply(&item) {
+            Some(new_item) => api
+                .patch_pantry_item(&item.id, &action.to_update(&new_item))
+                .await
+                .map(|_| Some(new_item)),
+            None => api.delete_pantry_item_by_id(&item.id).await.map(|_| None),
+        };
+        match response {
+            Ok(new_item) => {
+                updated.extend(new_item);
+                previous.push(item);
+            }
+            Err(err) => failed.push((item, err)),
+        }
+        on_progress(i + 1);
+    }
+    let result = (!previous.is_empty()).then_some(BulkEditResult {
+        action,
+        previous,
+        updated,
+    });
+    (result, failed)
+}
+
+/// Revert a bulk edit, deleted items are re-created so will have new ids.
+///
+/// Returns the restored items and the number of items that could not be restored.
+pub async fn undo_bulk_action(api: &Api, result: BulkEditResult) -> (Vec<Item>, usize) {
+    let mut restored = Vec::new();
+    let mut failed = 0;
+    for item in result.previous {
+        let response = match result.action {
+            BulkItemAction::Delete => {
+                api.post_pantry_item(
+                    &item.location_id,
+                    &CreateItem {
+                        name: item.name.clone(),
+                        quantity: item.quantity.clone(),
+                        notes: item.notes.clone(),
+                        expiry: item.expiry,
+                        labels: item.labels.clone(),
+                    },
+                )
+                .await
+            }
+            _ => api
+                .patch_pantry_item(&item.id, &result.action.to_update(&item))
+                .await
+                .map(|_| item),
+        };
+        match response {
+            Ok(item) => restored.push(item),
+            Err(_) => failed += 1,
+        }
+    }
+    (restored, failed)
+}
+
+/// Apply an action to many items.
+///
+/// `on_update` is called with the items that succeeded after each attempt,
+/// `on_action` is called on close with everything that succeeded (for undo).
+#[component]
+pub fn BulkEditModal<U, F>(
+    kind: BulkEditKind,
+    items: Vec<Item>,
+    on_update: U,
+    on_action: F,
+) -> impl IntoView
+where
+    U: Fn(BulkEditResult) + 'static + Copy,
+    F: Fn(Option<BulkEditResult>) + 'static + Copy,
+{
+    let toasts = use_toasts();
+    let pantry = use_pantry();
+    let CurrentApi { api, .. } = use_api();
+    let total = items.len();
+    let items = store_value(items);
+    let location_id = create_rw_signal(String::default());
+    let removing_labels = create_rw_signal(false);
+    let selected_labels = create_rw_signal(HashSet::<String>::new());
+    let expiry = create_rw_signal::<Option<DateTime<Utc>>>(None);
+    let quantity = create_rw_signal::<f32>(0.0);
+    // (done, total) of the current attempt
+    let progress = create_rw_signal((0, 0));
+    let failed = create_rw_signal::<Vec<(Item, ApiError)>>(Vec::new());
+    let succeeded = store_value::<Option<BulkEditResult>>(None);
+
+    let locations = create_resource(
+        move || kind,
+        move |kind| async move {
+            if kind != BulkEditKind::Move {
+                return vec![];
+            }
+            let api = api.get_untracked().expect("api expected to be set");
+            match api.get_pantry_locations().await {
+                Ok(v) => v,
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading locations"));
+                    vec![]
+                }
+            }
+        },
+    );
+
+    let labels = create_resource(
+        move || kind,
+        move |kind| async move {
+            if kind != BulkEditKind::Labels {
+                return HashSet::new();
+            }
+            let api = api.get_untracked().expect("api expected to be set");
+            match api.get_labels().await {
+                Ok(v) => HashSet::from_iter(v),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading labels"));
+                    HashSet::new()
+                }
+            }
+        },
+    );
+
+    let run = create_action(move |_: &()| {
+        let api = api.get_untracked().expect("api expected to be set");
+        let labels = selected_labels.get_untracked().into_iter().collect();
+        let new_action = match kind {
+            BulkEditKind::Move => BulkItemAction::Move(location_id.get_untracked()),
+            BulkEditKind::Labels if removing_labels.get_untracked() => {
+                BulkItemAction::RemoveLabels(labels)
+            }
+            BulkEditKind::Labels => BulkItemAction::AddLabels(labels),
+            BulkEditKind::Expiry => BulkItemAction::SetExpiry(expiry.get_untracked()),
+            BulkEditKind::Quantity => BulkItemAction::AdjustQuantity(quantity.get_untracked()),
+            BulkEditKind::Delete => BulkItemAction::Delete,
+        };
+        // only retry the failed items (with the same action) if some have already been run
+        let (action, to_run) = match (succeeded.get_value(), failed.get_untracked()) {
+            (_, failed) if failed.is_empty() => (new_action, items.get_value()),
+            (previous, failed) => (
+                previous.map(|v| v.action).unwrap_or(new_action),
+                failed.into_iter().map(|(item, _)| item).collect(),
+            ),
+        };
+        let to_run_len = to_run.len();
+        async move {
+            progress.set((0, to_run_len));
+            let (result, new_failed) = run_bulk_action(&api, to_run, action, |done| {
+                progress.set((done, to_run_len))
+            })
+            .await;
+            if let Some(result) = result {
+                pantry.invalidate();
+                on_update(result.clone());
+                succeeded.update_value(|v| match v {
+                    Some(v) => v.merge(result),
+                    None => *v = Some(result),
+                });
+            }
+            if new_failed.is_empty() {
+                on_action(succeeded.get_value());
+            } else {
+                toasts.push(api_error_to_toast(
+                    &new_failed[0].1,
+                    &format!("updating {} of {} items", new_failed.len(), total),
+                ));
+            }
+            failed.set(new_failed);
+        }
+    });
+
+    let global_loading = Signal::derive(move || {
+        run.pending().get() || locations.loading().get() || labels.loading().get()
+    });
+
+    view! {
+        <ModalFormBase
+            title=kind.title()
+            positive_text="Apply"
+            negative_text="Close"
+            loading=global_loading
+            on_positive=move || run.dispatch(())
+            on_positive_secondary=|| {}
+            on_negative=move || on_action(succeeded.get_value())
+        >
+            <p class="my-2">{format!("{total} items selected")}</p>
+            {match kind {
+                BulkEditKind::Move => view!{
+                    <div class="form-control">
+                        <label>
+                            <span class="label">"New Location"</span>
+                            <select
+                                on:change=move |ev| location_id.set(event_target_value(&ev))
+                                class="select select-bordered w-full"
+                                required=true
+                            >
+                                <option value="">"__Pick A Location__"</option>
+                                {move || {
+                                    locations.get().unwrap_or_default().into_iter().map(|location| {
+                                        view!{ <option value=location.id>{location.name}</option>}
+                                    }).collect_view()
+                                }}
+                            </select>
+                        </label>
+                    </div>
+                }.into_view(),
+                BulkEditKind::Labels => view!{
+                    <div class="form-control">
+                        <label class="label cursor-pointer">
+                            <span>"Remove Labels Instead Of Adding"</span>
+                            <input
+                                prop:checked=move || removing_labels.get()
+                                on:input=move |ev| removing_labels.set(event_target_checked(&ev))
+                                type="checkbox"
+                                class="checkbox"
+                            />
+                        </label>
+                        <LabelSelector
+                            labels=Signal::derive( move || labels.get().unwrap_or_default())
+                            allow_new=true
+                            selected=selected_labels
+                            on_change=move |new_labels| selected_labels.set(new_labels)
+                        />
+                    </div>
+                }.into_view(),
+                BulkEditKind::Expiry => view!{
+                    <div class="form-control">
+                        <label>
+                            <span class="label">"Expiry (leave empty to clear)"</span>
+                            <input
+                                on:input=move |ev| {
+                                    let value = event_target_value(&ev);
+                                    expiry.set(NaiveDate::parse_from_str(&value, "%Y-%m-%d").ok().map(|v| {
+                                        DateTime::<Utc>::from_naive_utc_and_offset(v.and_hms_milli_opt(0,0,0,0).unwrap(), Utc)
+                                    }));
+                                }
+                                type="date"
+                                class="input input-bordered w-full"
+                            />
+                        </label>
+                    </div>
+                }.into_view(),
+                BulkEditKind::Quantity => view!{
+                    <div class="form-control">
+                        <label>
+                            <span class="label">"Adjust By (e.g. -1 when one is used)"</span>
+                            <input
+                                prop:value=move || quantity.get()
+                                on:input=move |ev| {
+                                    if let Ok(value) = event_target_value(&ev).parse() {
+                                        quantity.set(value)
+                                    }
+                                }
+                                type="number"
+                                step="any"
+                                class="input input-bordered w-full"
+                                required=true
+                            />
+                        </label>
+                    </div>
+                }.into_view(),
+                BulkEditKind::Delete => view!{
+                    <p class="text-error">"Selected items will be deleted, this can be undone from the pantry page."</p>
+                }.into_view(),
+            }}
+            <Show when=move || progress.get().1 != 0>
+                <progress
+                    class="progress w-full my-2"
+                    value=move || progress.get().0
+                    max=move || progress.get().1
+                ></progress>
+            </Show>
+            <Show when=move || !failed.get().is_empty()>
+                <div class="alert alert-error flex-col items-start my-2">
+                    <span>{move || format!("{} items failed, apply again to retry:", failed.get().len())}</span>
+                    <ul class="list-disc list-inside">
+                        {move || failed.get().into_iter().map(|(item, _)| view!{ <li>{item.name}</li>}).collect_view()}
+                    </ul>
+                </div>
+            </Show>
+        </ModalFormBase>
+    }
+}
@@ -2,15 +2,19 @@ use chrono::{DateTime, Months, NaiveDate, Utc};
 use leptos::*;
 use std::collections::HashSet;
 
+use mcc_frontend_core::storage::set_scanned_product;
 use mcc_frontend_types::{
-    pantry::{Item, UpdateItem},
+    pantry::{Item, ScannedProduct, UpdateItem},
     SelectedUpdate,
 };
 
 use crate::{
-    components::input::LabelSelector,
-    contexts::prelude::{use_api, use_toasts, CurrentApi},
-    helpers::api_error_to_toast,
+    components::{
+        barcode::BarcodeInput,
+        input::{FractionalInput, LabelSelector},
+    },
+    contexts::prelude::{use_api, use_pantry, use_toasts, CurrentApi},
+    helpers::{api_error_to_toast, storage_error_to_toast},
     modals::base::ModalSaveCancel,
 };
 
@@ -20,8 +24,10 @@ where
     F: Fn(Option<Item>) + 'static + Copy,
 {
     let toasts = use_toasts();
+    let pantry = use_pantry();
     let CurrentApi { api, .. } = use_api();
     let item = create_rw_signal(item);
+    let barcode = create_rw_signal::<Option<String>>(None);
 
     let locations = create_resource(
         || {},
@@ -42,7 +48,7 @@ where
         move |_| async move {
             let api = api.get_untracked().expect("api expected to be set");
             match api.get_labels().await {
-                Ok(v) => HashSet::from_iter(v.into_iter()),
+                Ok(v) => HashSet::from_iter(v),
                 Err(err) => {
                     toasts.push(api_error_to_toast(&err, "loading labels"));
                     HashSet::new()
@@ -55,6 +61,7 @@ where
         let api = api.get_untracked().expect("api expected to be set");
         let id = item.get_untracked().id.clone();
         let item = item.get_untracked();
+        let barcode = barcode.get_untracked();
         let update = SelectedUpdate {
             fields: vec![
                 String::from("name"),
@@ -64,18 +71,27 @@ where
                 String::from("expiry"),
                 String::from("labels"),
             ],
-            model: UpdateItem {
-                name: item.name.clone(),
-                location_id: item.location_id.clone(),
-                quantity: item.quantity,
-                notes: item.notes.clone(),
-                expiry: item.expiry,
-                labels: item.labels.clone(),
-            },
+            model: UpdateItem::from(item.clone()),
         };
         async move {
             match api.patch_pantry_item(&id, &update).await {
-                Ok(_) => on_action(Some(item)),
+                Ok(_) => {
+                    pantry.invalidate();
+                    if let Some(barcode) = barcode {
+                        let saved = set_scanned_product(
+                            &barcode,
+                            ScannedProduct {
+                                name: item.name.clone(),
+                                labels: item.labels.clone(),
+                                location_id: Some(item.location_id.clone()),
+                            },
+                        );
+                        if let Err(err) = saved {
+                            toasts.push(storage_error_to_toast(&err, "the scanned product"));
+                        }
+                    }
+                    on_action(Some(item))
+                }
                 Err(err) => toasts.push(api_error_to_toast(&err, "saving item")),
             }
         }
@@ -127,18 +143,41 @@ where
             <div class="form-control">
                 <label>
                     <span class="label">"Quantity"</span>
-                    <input
-                        prop:value=move || item.get().quantity
-                        on:input=move |ev| {
-                            if let Ok(value) = event_target_value(&ev).parse() {
-                                item.update(|v| v.quantity = value)
-                            }
-                        }
-                        type="number"
-                        class="input input-bordered w-full"
-                        required=true
-                        min=1
-                    />
+                    <div class="grid grid-cols-[8rem_auto] gap-2">
+                        <FractionalInput
+                            value=item.get_untracked().quantity.amount
+                            on_input=move |amount| item.update(|v| v.quantity.amount = amount)
+                            class="input input-bordered w-full".to_owned()
+                            placeholder="e.g. 1/2".to_owned()
+                            required=true
+                        />
+                        <input
+                            prop:value=move || item.get().quantity.unit.unwrap_or_default()
+                            on:input=move |ev| item.update(|v| {
+                                let unit = event_target_value(&ev);
+                                v.quantity.unit = if unit.is_empty() { None } else { Some(unit) };
+                            })
+                            type="text"
+                            class="input input-bordered w-full"
+                            placeholder="e.g. bag"
+                            list="pantry-units"
+                            maxlength=20
+                        />
+                        <datalist id="pantry-units">
+                            <option value="g" />
+                            <option value="kg" />
+                            <option value="ml" />
+                            <option value="l" />
+                            <option value="oz" />
+                            <option value="lb" />
+                            <option value="bag" />
+                            <option value="box" />
+                            <option value="pack" />
+                            <option value="can" />
+                            <option value="bottle" />
+                            <option value="jar" />
+                        </datalist>
+                    </div>
                 </label>
             </div>
             <div class="form-control">
@@ -223,6 +262,12 @@ where
                     />
                 </label>
             </div>
+            <div class="form-control">
+                <label>
+                    <span class="label">"Remember Barcode"</span>
+                    <BarcodeInput value=barcode on_change=move |v| barcode.set(v) />
+                </label>
+            </div>
         </ModalSaveCancel>
     }
 }
@@ -8,7 +8,7 @@ use mcc_frontend_types::{
 use web_sys::SubmitEvent;
 
 use crate::{
-    contexts::prelude::{use_api, use_toasts, CurrentApi},
+    contexts::prelude::{use_api, use_pantry, use_toasts, CurrentApi},
     helpers::api_error_to_toast,
     modals::base::ModalNeutral,
 };
@@ -25,6 +25,7 @@ where
     E: Fn(Location) + 'static + Copy,
 {
     let toasts = use_toasts();
+    let pantry = use_pantry();
     let CurrentApi { api, .. } = use_api();
     let editing = create_rw_signal(false);
     let location = create_rw_signal(location);
@@ -34,7 +35,10 @@ where
         let id = location.get_untracked().id.clone();
         async move {
             match api.delete_pantry_location_by_id(&id).await {
-                Ok(_) => delete_callback(id),
+                Ok(_) => {
+                    pantry.invalidate();
+                    delete_callback(id)
+                }
                 Err(err) => toasts.push(api_error_to_toast(&err, "deleting pantry location")),
             }
         }
@@ -45,7 +49,10 @@ where
         let update = update.clone();
         async move {
             match api.patch_pantry_location(&update.0, &update.1).await {
-                Ok(_) => edit_callback(location.get_untracked()),
+                Ok(_) => {
+                    pantry.invalidate();
+                    edit_callback(location.get_untracked())
+                }
                 Err(err) => {
                     toasts.push(api_error_to_toast(&err, "updating pantry location"));
                     // restore editing state (we want to indicate it did not save)
@@ -112,7 +119,7 @@ where
                             prop:checked=move || editing.get()
                             on:input=move |ev| toggle_editing(event_target_checked(&ev))
                             type="checkbox"
-                            aria-label=move || format!("Edit {}", &location.get().name)
+                            aria-label=move || format!("Edit {}", location.get().name)
                         />
                         <div class="swap-off">Edit</div>
                         <div class="swap-on">Save</div>
@@ -123,7 +130,7 @@ where
                         // class="btn-disabled loading"
                         class:btn-disabled=move || global_loading.get() || editing.get()
                         class:loading=move || delete.pending().get()
-                        aria-label=move || format!("Delete '{}'", &location.get().name)
+                        aria-label=move || format!("Delete '{}'", location.get().name)
                     >"X"</button>
                 </div>
             </td>
@@ -137,6 +144,7 @@ where
     F: Fn() + 'static,
 {
     let toasts = use_toasts();
+    let pantry = use_pantry();
     let CurrentApi { api, .. } = use_api();
     let new_location = create_rw_signal(CreateLocation::default());
 
@@ -156,10 +164,13 @@ where
         let new_location = new_location.clone();
         async move {
             match api.post_pantry_location(&new_location).await {
-                Ok(location) => locations.update(|v| {
-                    v.as_mut()
-                        .map(|v| v.insert(location.id.to_owned(), location));
-                }),
+                Ok(location) => {
+                    pantry.invalidate();
+                    locations.update(|v| {
+                        v.as_mut()
+                            .map(|v| v.insert(location.id.to_owned(), location));
+                    })
+                }
                 Err(err) => toasts.push(api_error_to_toast(&err, "creating pantry location")),
             }
         }
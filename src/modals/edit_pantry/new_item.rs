@@ -1,10 +1,13 @@
 use leptos::*;
+use std::collections::HashSet;
 
-use mcc_frontend_types::pantry::{CreateItem, Item};
+use mcc_frontend_core::storage::{read_scanned_product, set_scanned_product};
+use mcc_frontend_types::pantry::{CreateItem, Item, ScannedProduct};
 
 use crate::{
-    contexts::prelude::{use_api, use_toasts, CurrentApi},
-    helpers::api_error_to_toast,
+    components::{barcode::BarcodeInput, input::LabelSelector},
+    contexts::prelude::{use_api, use_pantry, use_toasts, CurrentApi},
+    helpers::{api_error_to_toast, storage_error_to_toast},
     modals::base::{CreationMode, ModalCreateWithModeCancel},
 };
 
@@ -14,9 +17,12 @@ where
     F: Fn(Option<(CreationMode, Item)>) + 'static + Copy,
 {
     let toasts = use_toasts();
+    let pantry = use_pantry();
     let CurrentApi { api, .. } = use_api();
     let name = create_rw_signal(String::default());
     let location_id = create_rw_signal(String::default());
+    let item_labels = create_rw_signal(HashSet::<String>::default());
+    let barcode = create_rw_signal::<Option<String>>(None);
 
     let locations = create_resource(
         || {},
@@ -32,23 +38,56 @@ where
         },
     );
 
+    let labels = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to be set");
+            match api.get_labels().await {
+                Ok(v) => HashSet::from_iter(v),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading labels"));
+                    HashSet::new()
+                }
+            }
+        },
+    );
+
     let new_item = create_action(move |mode: &CreationMode| {
         let mode = *mode;
         let api = api.get_untracked().expect("api expected to be set");
         let name = name.get_untracked();
         let location_id = location_id.get_untracked();
+        let labels = item_labels.get_untracked().into_iter().collect::<Vec<_>>();
+        let barcode = barcode.get_untracked();
         async move {
             match api
                 .post_pantry_item(
                     &location_id,
                     &CreateItem {
-                        name,
+                        name: name.clone(),
+                        labels: labels.clone(),
                         ..Default::default()
                     },
                 )
                 .await
             {
-                Ok(v) => on_action(Some((mode, v))),
+                Ok(v) => {
+                    pantry.invalidate();
+                    if let Some(barcode) = barcode {
+                        let saved = set_scanned_product(
+                            &barcode,
+                            ScannedProduct {
+                                name,
+                                labels,
+                                location_id: Some(location_id),
+                            },
+                        );
+                        if let Err(err) = saved {
+                            toasts.push(storage_error_to_toast(&err, "the scanned product"));
+                        }
+                    }
+                    on_action(Some((mode, v)))
+                }
                 Err(err) => {
                     toasts.push(api_error_to_toast(&err, "creating new item"));
                 }
@@ -56,8 +95,20 @@ where
         }
     });
 
-    let global_loading =
-        Signal::derive(move || new_item.pending().get() || locations.loading().get());
+    let on_barcode_change = move |new_barcode: Option<String>| {
+        if let Some(product) = new_barcode.as_deref().and_then(read_scanned_product) {
+            name.set(product.name);
+            item_labels.set(HashSet::from_iter(product.labels));
+            if let Some(id) = product.location_id {
+                location_id.set(id);
+            }
+        }
+        barcode.set(new_barcode);
+    };
+
+    let global_loading = Signal::derive(move || {
+        new_item.pending().get() || locations.loading().get() || labels.loading().get()
+    });
 
     view! {
         <ModalCreateWithModeCancel
@@ -66,6 +117,12 @@ where
             on_creation=move |mode| new_item.dispatch(mode)
             on_cancel=move || on_action(None)
         >
+            <div class="form-control">
+                <label>
+                    <span class="label">"Barcode"</span>
+                    <BarcodeInput value=barcode on_change=on_barcode_change />
+                </label>
+            </div>
             <div class="form-control">
                 <label>
                     <span class="label">"Item Name"</span>
@@ -91,12 +148,27 @@ where
                         <option value="">"__Pick A Location__"</option>
                         {move || {
                             locations.get().unwrap_or_default().into_iter().map(|location| {
-                                view!{ <option value=location.id>{location.name}</option>}
+                                let id = location.id.clone();
+                                view!{ <option
+                                    value=location.id
+                                    prop:selected=move || location_id.get() == id
+                                >{location.name}</option>}
                             }).collect_view()
                         }}
                     </select>
                 </label>
             </div>
+            <div class="form-control">
+                <label>
+                    <span class="label">"Labels"</span>
+                    <LabelSelector
+                        labels=Signal::derive( move || labels.get().unwrap_or_default())
+                        allow_new=true
+                        selected=item_labels
+                        on_change=move |new_labels| item_labels.set(new_labels)
+                    />
+                </label>
+            </div>
         </ModalCreateWithModeCancel>
     }
 }
@@ -1,19 +1,11 @@
-pub mod description;
+pub mod duplicate;
 pub mod image;
-pub mod info;
-pub mod ingredients;
 pub mod labels;
-pub mod long_description;
 pub mod new;
-pub mod steps;
-pub mod title;
+pub mod tags;
 
-pub use description::*;
+pub use duplicate::*;
 pub use image::*;
-pub use info::*;
-pub use ingredients::*;
 pub use labels::*;
-pub use long_description::*;
 pub use new::*;
-pub use steps::*;
-pub use title::*;
+pub use tags::*;
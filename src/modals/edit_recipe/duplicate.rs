@@ -0,0 +1,114 @@
+use leptos::*;
+use mcc_frontend_core::storage;
+use mcc_frontend_types::recipe::{CreateRecipe, Recipe};
+
+use crate::{
+    contexts::prelude::{use_api, use_login, use_toasts, CurrentApi, CurrentLogin},
+    helpers::{api_error_to_toast, logout_on_401, storage_error_to_toast},
+    modals::base::ModalCreateCancel,
+};
+
+/// Create a full copy of a recipe, giving the new recipe's id
+#[component]
+pub fn DuplicateRecipeModal<F>(recipe: Recipe, on_action: F) -> impl IntoView
+where
+    F: Fn(Option<String>) + 'static + Copy,
+{
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, set_login } = use_login();
+    let title = create_rw_signal(format!("{} (Copy)", recipe.title));
+    let as_variant = create_rw_signal(true);
+    let copy_image = create_rw_signal(recipe.image_id.is_some());
+    let has_image = recipe.image_id.is_some();
+    let recipe = store_value(recipe);
+
+    let duplicate = create_action(move |_: &()| {
+        let api = api.get_untracked().expect("api expected to be set");
+        let login = login.get_untracked().expect("login expected to be set");
+        let recipe = recipe.get_value();
+        let new_recipe = CreateRecipe::copy_of(&recipe, title.get_untracked());
+        let copy_image = copy_image.get_untracked();
+        let as_variant = as_variant.get_untracked();
+        async move {
+            let copy = match api.post_new_recipe(&new_recipe).await {
+                Ok(v) => v,
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "duplicating recipe"));
+                    logout_on_401(&set_login, &err);
+                    return;
+                }
+            };
+            // the copy is still usable without its image, so only warn when that fails
+            if let Some(image_id) = recipe.image_id.filter(|_| copy_image) {
+                let uploaded = match api.get_recipe_image(&login.media_url, &image_id).await {
+                    Ok(file) => api.post_recipe_image(copy.id.clone(), file).await,
+                    Err(err) => Err(err),
+                };
+                if let Err(err) = uploaded {
+                    toasts.push(api_error_to_toast(&err, "copying recipe image"));
+                }
+            }
+            if as_variant {
+                let mut variants = storage::read_recipe_variants(&login);
+                variants.link(&copy.id, &recipe.id);
+                if let Err(err) = storage::set_recipe_variants(&login, &variants) {
+                    toasts.push(storage_error_to_toast(
+                        &err,
+                        "the link to the original recipe",
+                    ));
+                }
+            }
+            on_action(Some(copy.id));
+        }
+    });
+
+    view! {
+        <ModalCreateCancel
+            title="Duplicate Recipe"
+            loading=duplicate.pending()
+            on_creation=move || duplicate.dispatch(())
+            on_cancel=move || on_action(None)
+        >
+            <div class="form-control">
+                <span class="label">"Copy everything into a new recipe"</span>
+                <label class="join">
+                    <span class="label p-3 bg-base-300 join-item">"Title"</span>
+                    <input
+                        prop:value=move || title.get()
+                        on:input=move |ev| title.set(event_target_value(&ev))
+                        type="text"
+                        class="input input-bordered w-full join-item"
+                        placeholder="e.g. Vegetarian Lasagne"
+                        required=true
+                        maxlength=60
+                    />
+                </label>
+            </div>
+            <div class="form-control mt-2">
+                <label class="label cursor-pointer">
+                    <span>"Link As A Variant Of This Recipe"</span>
+                    <input
+                        prop:checked=move || as_variant.get()
+                        on:input=move |ev| as_variant.set(event_target_checked(&ev))
+                        type="checkbox"
+                        class="checkbox"
+                    />
+                </label>
+            </div>
+            <Show when=move || has_image>
+                <div class="form-control">
+                    <label class="label cursor-pointer">
+                        <span>"Copy Image"</span>
+                        <input
+                            prop:checked=move || copy_image.get()
+                            on:input=move |ev| copy_image.set(event_target_checked(&ev))
+                            type="checkbox"
+                            class="checkbox"
+                        />
+                    </label>
+                </div>
+            </Show>
+        </ModalCreateCancel>
+    }
+}
@@ -1,13 +1,22 @@
 use crate::{
-    contexts::prelude::{use_api, use_toasts, CurrentApi},
-    helpers::api_error_to_toast,
+    components::image_cropper::{
+        draw_upright, encode_jpeg, ImageCropper, ImageSizeSelect, ImageStage,
+        DEFAULT_IMAGE_MAX_SIZE,
+    },
+    components::recipe_image::placeholder_from_canvas,
+    contexts::prelude::{use_api, use_media, use_toasts, CurrentApi, Toast},
+    helpers::{api_error_to_toast, storage_error_to_toast},
     modals::base::ModalSaveCancel,
 };
 use leptos::ev::Event;
-use leptos::*;
+use leptos::{html::Canvas, *};
+use mcc_frontend_core::{
+    image::{CropRect, CARD_ASPECT_RATIO},
+    storage,
+};
 
 use wasm_bindgen::JsCast;
-use web_sys::{File, HtmlInputElement};
+use web_sys::HtmlInputElement;
 
 #[component]
 pub fn EditImageModal<F>(id: String, image_id: Option<String>, on_action: F) -> impl IntoView
@@ -16,34 +25,96 @@ where
 {
     let toasts = use_toasts();
     let CurrentApi { api, .. } = use_api();
+    let media = use_media();
     let image_id = create_rw_signal(image_id);
-    let image_file = create_rw_signal::<Option<File>>(Option::default());
+    let has_image = create_rw_signal(false);
+    let stage = create_rw_signal(None::<ImageStage>);
+    let canvas_ref = create_node_ref::<Canvas>();
+    let size = create_rw_signal(None::<(f64, f64)>);
+    let crop = create_rw_signal(CropRect::centered(1.0, 1.0, CARD_ASPECT_RATIO, 1.0));
+    let max_size =
+        create_rw_signal(storage::read_image_max_size().unwrap_or(DEFAULT_IMAGE_MAX_SIZE));
+    create_effect(move |_| {
+        if let Err(err) = storage::set_image_max_size(max_size.get()) {
+            toasts.push(storage_error_to_toast(&err, "the image size"));
+        }
+    });
+
+    let failed = move |when: &str| {
+        stage.set(None);
+        toasts.push(Toast {
+            message: format!("Could not process image, when {when}"),
+        });
+    };
+
+    let load_action = create_action(move |file: &web_sys::File| {
+        let file = file.clone();
+        async move {
+            let canvas = canvas_ref
+                .get_untracked()
+                .expect("canvas expected to exist");
+            stage.set(Some(ImageStage::Reading));
+            match draw_upright(&file, &canvas).await {
+                Ok((width, height)) => {
+                    size.set(Some((width, height)));
+                    crop.set(CropRect::centered(width, height, CARD_ASPECT_RATIO, 1.0));
+                    has_image.set(true);
+                    stage.set(None);
+                }
+                Err(_) => {
+                    has_image.set(false);
+                    failed("reading image");
+                }
+            }
+        }
+    });
 
     let save_action = create_action(move |_: &()| {
         let id = id.clone();
         let api = api.get_untracked().expect("api expected to be set");
         async move {
-            match image_file.get() {
-                Some(image_file) => match api.post_recipe_image(id, image_file).await {
-                    Ok(v) => on_action(Some(Some(v))),
-                    Err(err) => {
-                        toasts.push(api_error_to_toast(&err, "setting new recipe image"));
-                    }
-                },
-                None => match api.delete_recipe_image(id).await {
+            if !has_image.get_untracked() {
+                match api.delete_recipe_image(id).await {
                     Ok(_) => on_action(Some(None)),
                     Err(err) => {
                         toasts.push(api_error_to_toast(&err, "deleting recipe image"));
                     }
-                },
+                }
+                return;
+            }
+            let canvas = canvas_ref
+                .get_untracked()
+                .expect("canvas expected to exist");
+            stage.set(Some(ImageStage::Encoding));
+            let Ok(image_file) =
+                encode_jpeg(&canvas, crop.get_untracked(), max_size.get_untracked()).await
+            else {
+                failed("resizing image");
+                return;
             };
+            // the placeholder is ready for when the grid first shows the new image
+            let placeholder = placeholder_from_canvas(&canvas, crop.get_untracked()).ok();
+            stage.set(Some(ImageStage::Uploading));
+            match api.post_recipe_image(id, image_file).await {
+                Ok(v) => {
+                    if let Some(placeholder) = placeholder {
+                        media.set_placeholder(&v, placeholder);
+                    }
+                    on_action(Some(Some(v)))
+                }
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "setting new recipe image"));
+                }
+            }
+            stage.set(None);
         }
     });
 
     let on_image_change = move |ev: Event| {
         let input = ev.target().unwrap().unchecked_into::<HtmlInputElement>();
-        let file = input.files().unwrap().get(0).unwrap();
-        image_file.set(Some(file));
+        if let Some(file) = input.files().and_then(|files| files.get(0)) {
+            load_action.dispatch(file);
+        }
     };
 
     view! {
@@ -66,13 +137,30 @@ where
                     />
                 </label>
             </div>
+            <div class="form-control mb-2" class:hidden=move || !has_image.get()>
+                <span class="label">"Crop & Resize"</span>
+                <ImageCropper canvas_ref=canvas_ref size=size crop=crop />
+                <div class="mt-2">
+                    <ImageSizeSelect max_size=max_size />
+                </div>
+                <p class="text-sm mt-2">"Location and camera details are removed before uploading."</p>
+            </div>
+            {move || stage.get().map(|stage| view! {
+                <div class="mb-2">
+                    <span class="label">{stage.label()}</span>
+                    <progress class="progress progress-primary w-full" value=stage.percent() max="100" />
+                </div>
+            })}
             {move || {
                 if image_id.get().is_some() {
                     return Some(view!{
                         <div class="form-control mb-2">
                             <span class="label">"Remove Existing"</span>
                             <button
-                                on:click=move |_| save_action.dispatch(())
+                                on:click=move |_| {
+                                    has_image.set(false);
+                                    save_action.dispatch(());
+                                }
                                 class="btn btn-outline btn-error"
                                 type="button"
                             >
@@ -22,7 +22,7 @@ where
         move |_| async move {
             let api = api.get_untracked().expect("api expected to be set");
             match api.get_labels().await {
-                Ok(labels) => HashSet::from_iter(labels.into_iter()),
+                Ok(labels) => HashSet::from_iter(labels),
                 Err(err) => {
                     toasts.push(api_error_to_toast(&err, "loading labels"));
                     HashSet::new()
@@ -31,7 +31,7 @@ where
         },
     );
     let labels: leptos::RwSignal<HashSet<std::string::String>> =
-        create_rw_signal(HashSet::from_iter(labels.into_iter()));
+        create_rw_signal(HashSet::from_iter(labels));
 
     let update_labels = create_action(move |_: &()| {
         let id = id.clone();
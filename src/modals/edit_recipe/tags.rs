@@ -0,0 +1,91 @@
+use leptos::*;
+use mcc_frontend_types::recipe::UpdateRecipe;
+use std::collections::HashSet;
+
+use crate::{
+    components::input::LabelSelector,
+    contexts::prelude::{use_api, use_search_index, use_toasts, CurrentApi},
+    helpers::api_error_to_toast,
+    modals::base::ModalSaveCancel,
+};
+
+#[component]
+pub fn EditTagsModal<F>(id: String, tags: Vec<String>, on_action: F) -> impl IntoView
+where
+    F: Fn(Option<Vec<String>>) + 'static + Copy,
+{
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+
+    let search_index = use_search_index();
+    spawn_local(async move {
+        let api = api.get_untracked().expect("api expected to be set");
+        if let Err(err) = search_index.load_tags(&api).await {
+            toasts.push(api_error_to_toast(&err, "loading tags"));
+        }
+    });
+    let existing_tags = Signal::derive(move || {
+        search_index
+            .tag_counts
+            .get()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .collect::<HashSet<_>>()
+    });
+    let tags = create_rw_signal(HashSet::<String>::from_iter(tags));
+
+    let update_tags = create_action(move |_: &()| {
+        let id = id.clone();
+        let api = api.get_untracked().expect("api expected to be set");
+        let mut tags = tags.get_untracked().into_iter().collect::<Vec<String>>();
+        tags.sort();
+        async move {
+            match api
+                .patch_update_recipe(
+                    id,
+                    &UpdateRecipe {
+                        tags: Some(tags.clone()),
+                        ..Default::default()
+                    },
+                )
+                .await
+            {
+                Ok(_) => on_action(Some(tags)),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "saving recipe tags"));
+                }
+            }
+        }
+    });
+
+    let on_change = move |selected: HashSet<String>| {
+        // tags are matched exactly, so avoid stray whitespace creating near duplicates
+        tags.set(
+            selected
+                .into_iter()
+                .map(|v| v.trim().to_owned())
+                .filter(|v| !v.is_empty())
+                .collect(),
+        );
+    };
+
+    view! {
+        <ModalSaveCancel
+            title="Edit Tags"
+            loading=update_tags.pending()
+            on_save=move || update_tags.dispatch(())
+            on_cancel=move || on_action(None)
+        >
+            <LabelSelector
+                labels=existing_tags
+                allow_new=true
+                selected=tags
+                on_change=on_change
+                list_id="tags"
+                unstyled=true
+                placeholder="e.g. Weeknight..."
+            />
+        </ModalSaveCancel>
+    }
+}
@@ -0,0 +1,210 @@
+use leptos::*;
+
+use mcc_frontend_core::api::{Api, ApiError};
+use mcc_frontend_types::{
+    label::LabelChange,
+    pantry::{Item, UpdateItem},
+    recipe::{Recipe, UpdateRecipe},
+    SelectedUpdate,
+};
+
+use crate::{
+    contexts::prelude::{use_api, use_pantry, use_toasts, CurrentApi},
+    helpers::api_error_to_toast,
+    modals::base::ModalFormBase,
+};
+
+/// Recipes and pantry items to update, with their new labels
+#[derive(Debug, Clone, Default)]
+pub struct LabelChangePlan {
+    pub recipes: Vec<(Recipe, Vec<String>)>,
+    pub items: Vec<(Item, Vec<String>)>,
+}
+
+impl LabelChangePlan {
+    pub fn new(change: &LabelChange, recipes: &[Recipe], items: &[Item]) -> Self {
+        Self {
+            recipes: change.plan_recipes(recipes),
+            items: change.plan_items(items),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.recipes.len() + self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Apply the new labels, one request at a time.
+///
+/// Returns the part of the plan that failed and the last error.
+pub async fn run_label_change<P>(
+    api: &Api,
+    plan: LabelChangePlan,
+    on_progress: P,
+) -> (LabelChangePlan, Option<ApiError>)
+where
+    P: Fn(usize),
+{
+    let mut failed = LabelChangePlan::default();
+    let mut last_error = None;
+    let mut done = 0;
+    for (recipe, labels) in plan.recipes {
+        let update = UpdateRecipe {
+            labels: Some(labels.clone()),
+            ..Default::default()
+        };
+        if let Err(err) = api.patch_update_recipe(recipe.id.clone(), &update).await {
+            failed.recipes.push((recipe, labels));
+            last_error = Some(err);
+        }
+        done += 1;
+        on_progress(done);
+    }
+    for (item, labels) in plan.items {
+        let update = SelectedUpdate {
+            fields: vec!["labels".to_owned()],
+            model: UpdateItem {
+                labels: labels.clone(),
+                ..UpdateItem::from(item.clone())
+            },
+        };
+        if let Err(err) = api.patch_pantry_item(&item.id, &update).await {
+            failed.items.push((item, labels));
+            last_error = Some(err);
+        }
+        done += 1;
+        on_progress(done);
+    }
+    (failed, last_error)
+}
+
+#[component]
+fn PlanRow(name: String, old: Vec<String>, new: Vec<String>) -> impl IntoView {
+    view! {
+        <tr>
+            <td>{name}</td>
+            <td class="line-through opacity-60">{old.join(", ")}</td>
+            <td>{new.join(", ")}</td>
+        </tr>
+    }
+}
+
+/// Preview a label change (dry run) and then apply it.
+///
+/// `on_action` is called on close with whether anything was changed.
+#[component]
+pub fn LabelChangeModal<F>(
+    change: LabelChange,
+    recipes: Vec<Recipe>,
+    items: Vec<Item>,
+    on_action: F,
+) -> impl IntoView
+where
+    F: Fn(bool) + 'static + Copy,
+{
+    let toasts = use_toasts();
+    let pantry = use_pantry();
+    let CurrentApi { api, .. } = use_api();
+    let title = match &change.to {
+        Some(to) => format!("Replace With '{to}'"),
+        None => "Delete Labels".to_owned(),
+    };
+    let mut from = change.from.iter().cloned().collect::<Vec<_>>();
+    from.sort();
+    // what is left to do, starts as the full plan and then becomes what failed
+    let pending = create_rw_signal(LabelChangePlan::new(&change, &recipes, &items));
+    let total = pending.get_untracked().len();
+    let progress = create_rw_signal((0, 0));
+    let changed_any = store_value(false);
+
+    let run = create_action(move |_: &()| {
+        let api = api.get_untracked().expect("api expected to be set");
+        let plan = pending.get_untracked();
+        let plan_len = plan.len();
+        async move {
+            progress.set((0, plan_len));
+            let (failed, last_error) =
+                run_label_change(&api, plan, |done| progress.set((done, plan_len))).await;
+            if failed.len() < plan_len {
+                changed_any.set_value(true);
+                pantry.invalidate();
+            }
+            match last_error {
+                Some(err) => toasts.push(api_error_to_toast(
+                    &err,
+                    &format!("updating labels of {} of {plan_len}", failed.len()),
+                )),
+                None => on_action(changed_any.get_value()),
+            }
+            pending.set(failed);
+        }
+    });
+
+    view! {
+        <ModalFormBase
+            title=title
+            positive_text="Apply"
+            negative_text="Close"
+            loading=run.pending()
+            on_positive=move || {
+                if !pending.get_untracked().is_empty() {
+                    run.dispatch(())
+                }
+            }
+            on_positive_secondary=|| {}
+            on_negative=move || on_action(changed_any.get_value())
+        >
+            <p class="my-2">{format!("Labels: {}", from.join(", "))}</p>
+            <p class="my-2">
+                {move || {
+                    let pending = pending.get();
+                    if total == 0 {
+                        "Nothing uses these labels, so nothing will change.".to_owned()
+                    } else if progress.get().1 == 0 {
+                        format!(
+                            "Preview: {} recipes and {} pantry items will change.",
+                            pending.recipes.len(),
+                            pending.items.len(),
+                        )
+                    } else {
+                        format!("{} changes failed, apply again to retry.", pending.len())
+                    }
+                }}
+            </p>
+            <Show when=move || progress.get().1 != 0>
+                <progress
+                    class="progress w-full my-2"
+                    value=move || progress.get().0
+                    max=move || progress.get().1
+                ></progress>
+            </Show>
+            <div class="max-h-64 overflow-y-auto">
+                <table class="table table-sm">
+                    <thead>
+                        <tr>
+                            <th>"Name"</th>
+                            <th>"Before"</th>
+                            <th>"After"</th>
+                        </tr>
+                    </thead>
+                    <tbody>
+                        {move || {
+                            let pending = pending.get();
+                            let recipe_rows = pending.recipes.into_iter().map(|(recipe, labels)| view!{
+                                <PlanRow name=format!("Recipe: {}", recipe.title) old=recipe.labels new=labels />
+                            });
+                            let item_rows = pending.items.into_iter().map(|(item, labels)| view!{
+                                <PlanRow name=format!("Pantry: {}", item.name) old=item.labels new=labels />
+                            });
+                            recipe_rows.chain(item_rows).collect_view()
+                        }}
+                    </tbody>
+                </table>
+            </div>
+        </ModalFormBase>
+    }
+}
@@ -1,17 +1,27 @@
+pub mod collections;
 pub mod home;
+pub mod labels;
 pub mod login;
 pub mod logout;
 pub mod pantry;
 pub mod recipe;
+pub mod recipe_edit;
 pub mod recipe_print;
+pub mod recipe_search;
+pub mod recipe_tags;
 pub mod recipes;
 pub mod signup;
 
+pub use collections::*;
 pub use home::*;
+pub use labels::Labels;
 pub use login::*;
 pub use logout::*;
 pub use pantry::Pantry;
 pub use recipe::*;
+pub use recipe_edit::*;
 pub use recipe_print::*;
+pub use recipe_search::*;
+pub use recipe_tags::*;
 pub use recipes::*;
 pub use signup::*;
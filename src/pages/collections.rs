@@ -0,0 +1,353 @@
+use std::collections::HashMap;
+
+use leptos::{ev::SubmitEvent, *};
+use leptos_router::{use_navigate, use_params_map, A};
+use mcc_frontend_types::collection::Collection;
+
+use crate::{
+    components::{
+        collapse::CollapsableBox,
+        collections::use_collections,
+        image_links::{ImageLinkItem, ImageLinksBox},
+        input::DropdownConfirm,
+        markdown::{Markdown, MarkdownInput},
+    },
+    contexts::prelude::{use_api, use_login, use_toasts, CurrentApi, CurrentLogin},
+    helpers::{api_error_to_toast, load_all_recipes, load_recipes, logout_on_401},
+    pages::recipe_print::open_print,
+};
+
+/// Open a collection's printable cookbook in a new window
+pub fn open_collection_print(id: &str) {
+    open_print(&format!("/collections/{id}/print"));
+}
+
+#[component]
+pub fn CollectionsPage() -> impl IntoView {
+    let CurrentApi { api, .. } = use_api();
+    let state = use_collections();
+    let new_name = create_rw_signal(String::new());
+
+    // covers are recipe images, so the image of every recipe is needed
+    let image_ids = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            load_all_recipes(&api)
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .filter_map(|recipe| recipe.image_id.map(|image_id| (recipe.id, image_id)))
+                .collect::<HashMap<_, _>>()
+        },
+    );
+
+    let items = Signal::derive(move || {
+        let image_ids = image_ids.get().unwrap_or_default();
+        state.collections.with(|collections| {
+            collections
+                .0
+                .iter()
+                .map(|collection| ImageLinkItem {
+                    key: collection.id.clone(),
+                    href: format!("/collections/{}", collection.id),
+                    title: collection.name.clone(),
+                    image_id: collection
+                        .cover_recipe(|id| image_ids.contains_key(id))
+                        .and_then(|id| image_ids.get(id).cloned()),
+                    tags: vec![format!("{} Recipes", collection.recipe_ids.len())],
+                    labels: Vec::new(),
+                })
+                .collect()
+        })
+    });
+
+    let on_new_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let name = new_name.get_untracked().trim().to_owned();
+        if name.is_empty() {
+            return;
+        }
+        let mut id = String::new();
+        state.update(|collections| id = collections.create(name, None));
+        let navigator = use_navigate();
+        navigator(&format!("/collections/{id}"), Default::default());
+    };
+
+    view! {
+        <div class="rounded bg-base-200 p-4 mb-2">
+            <h1 class="text-3xl font-bold mb-4">"Collections"</h1>
+            <form on:submit=on_new_submit class="join shadow-lg">
+                <input
+                    prop:value=move || new_name.get()
+                    on:input=move |ev| new_name.set(event_target_value(&ev))
+                    type="text"
+                    class="input input-bordered join-item"
+                    placeholder="e.g. Christmas Menu"
+                    aria-label="New collection name"
+                    required=true
+                />
+                <button type="submit" class="btn btn-neutral join-item">"New Collection"</button>
+            </form>
+        </div>
+        <div class="p-4 rounded bg-base-200">
+            <Show
+                when=move || state.collections.with(|v| !v.0.is_empty())
+                fallback=|| view! {
+                    <p class="text-center">"No collections yet, create one here or add a recipe to one from its page."</p>
+                }
+            >
+                <ImageLinksBox items=items />
+            </Show>
+        </div>
+    }
+}
+
+/// Change a collection's name, description & cover
+#[component]
+fn CollectionEditForm<F>(
+    collection: Collection,
+    /// id & title of recipes that can be the cover, those with an image
+    covers: Vec<(String, String)>,
+    on_save: F,
+) -> impl IntoView
+where
+    F: Fn(Collection) + 'static,
+{
+    let name = create_rw_signal(collection.name.clone());
+    let description = create_rw_signal(collection.description.clone().unwrap_or_default());
+    let cover = create_rw_signal(collection.cover_recipe_id.clone());
+
+    let on_submit = move |ev: SubmitEvent| {
+        ev.prevent_default();
+        let description = description.get_untracked();
+        on_save(Collection {
+            name: name.get_untracked().trim().to_owned(),
+            description: (!description.trim().is_empty()).then_some(description),
+            cover_recipe_id: cover.get_untracked(),
+            ..collection.clone()
+        });
+    };
+
+    view! {
+        <form on:submit=on_submit class="flex flex-col gap-2 mb-4">
+            <label class="form-control">
+                <span class="label-text">"Name"</span>
+                <input
+                    prop:value=move || name.get()
+                    on:input=move |ev| name.set(event_target_value(&ev))
+                    type="text"
+                    class="input input-bordered"
+                    required=true
+                />
+            </label>
+            <span class="label-text">"Description"</span>
+            <MarkdownInput
+                value=description
+                on_input=move |v| description.set(v)
+                placeholder="What the collection is for..."
+            />
+            <label class="form-control">
+                <span class="label-text">"Cover"</span>
+                <select
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        cover.set((!value.is_empty()).then_some(value));
+                    }
+                    class="select select-bordered"
+                >
+                    <option value="" prop:selected=move || cover.get().is_none()>"__First Recipe With An Image__"</option>
+                    {covers.into_iter().map(|(id, title)| {
+                        let value = id.clone();
+                        view! {
+                            <option
+                                value=value.clone()
+                                prop:selected=move || cover.get().as_ref() == Some(&value)
+                            >{title}</option>
+                        }
+                    }).collect_view()}
+                </select>
+            </label>
+            <button type="submit" class="btn btn-neutral btn-wide mx-auto">"Save"</button>
+        </form>
+    }
+}
+
+#[component]
+pub fn CollectionPage() -> impl IntoView {
+    let params = use_params_map();
+    let id = Signal::derive(move || params.get().get("id").cloned().unwrap_or_default());
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { set_login, .. } = use_login();
+    let state = use_collections();
+    let collection = Signal::derive(move || state.collections.with(|v| v.get(&id.get()).cloned()));
+    let editing = create_rw_signal(false);
+
+    // recipes are only added from their own page, so reordering & removing need no reload
+    let recipes = create_resource(
+        move || id.get(),
+        move |id| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            let ids = state
+                .collections
+                .with_untracked(|v| v.get(&id).map(|v| v.recipe_ids.clone()))
+                .unwrap_or_default();
+            match load_recipes(&api, &ids).await {
+                Ok((recipes, missing)) => {
+                    if !missing.is_empty() {
+                        state.update(|collections| {
+                            for recipe_id in &missing {
+                                collections.remove_recipe(recipe_id);
+                            }
+                        });
+                    }
+                    Some(recipes)
+                }
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading collection recipes"));
+                    logout_on_401(&set_login, &err);
+                    None
+                }
+            }
+        },
+    );
+
+    // loaded recipes in the collection's order
+    let ordered = move || {
+        let recipes = recipes.get().flatten().unwrap_or_default();
+        collection
+            .get()
+            .map(|v| v.recipe_ids)
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|id| recipes.iter().find(|v| v.id == id).cloned())
+            .collect::<Vec<_>>()
+    };
+    let items = Signal::derive(move || {
+        ordered()
+            .into_iter()
+            .map(|recipe| ImageLinkItem {
+                key: recipe.id.clone(),
+                href: format!("/recipes/{}", recipe.id),
+                title: recipe.title,
+                image_id: recipe.image_id,
+                tags: recipe.tags,
+                labels: recipe.labels,
+            })
+            .collect()
+    });
+
+    let update_collection = move |f: &dyn Fn(&mut Collection)| {
+        state.update(|collections| {
+            if let Some(collection) = collections.get_mut(&id.get_untracked()) {
+                f(collection);
+            }
+        });
+    };
+    let on_save = move |updated: Collection| {
+        state.update(|collections| {
+            if let Some(collection) = collections.get_mut(&updated.id) {
+                *collection = updated;
+            }
+        });
+        editing.set(false);
+    };
+    let on_delete = move || {
+        state.update(|collections| collections.remove(&id.get_untracked()));
+        let navigator = use_navigate();
+        navigator("/collections", Default::default());
+    };
+
+    view! {
+        <Show
+            when=move || collection.with(Option::is_some)
+            fallback=|| view! {
+                <div class="rounded bg-base-200 p-4">
+                    <p class="mb-2">"Collection Not Found"</p>
+                    <A href="/collections" class="btn">"Collections"</A>
+                </div>
+            }
+        >
+            <div class="rounded bg-base-200 p-4 mb-2">
+                <h1 class="text-3xl font-bold mb-4">
+                    {move || collection.get().map(|v| v.name).unwrap_or_default()}
+                </h1>
+                <div class="flex flex-wrap gap-2 mb-4">
+                    <div class="flex flex-wrap gap-2 mr-auto">
+                        <button
+                            on:click=move |_| open_collection_print(&id.get_untracked())
+                            class="btn shadow-lg"
+                        >"Print Cookbook"</button>
+                        <button
+                            on:click=move |_| editing.update(|v| *v = !*v)
+                            class="btn shadow-lg"
+                        >"Edit"</button>
+                    </div>
+                    <DropdownConfirm
+                        title="Remove"
+                        confirm_aria="Remove collection, keeping its recipes"
+                        on_confirm=on_delete
+                        class="shadow-lg"
+                    />
+                </div>
+                {move || editing.get().then(|| {
+                    let covers = ordered()
+                        .into_iter()
+                        .filter(|v| v.image_id.is_some())
+                        .map(|v| (v.id, v.title))
+                        .collect::<Vec<_>>();
+                    collection.get().map(|collection| view! {
+                        <CollectionEditForm collection=collection covers=covers on_save=on_save />
+                    })
+                })}
+                <Markdown text=Signal::derive(move || {
+                    collection.get().and_then(|v| v.description).unwrap_or_default()
+                }) />
+            </div>
+            <div class="p-4 rounded bg-base-200 mb-2">
+                {move || match recipes.get() {
+                    None => view! { <div class="loading loading-ring loading-lg mx-auto block"></div> }.into_view(),
+                    Some(_) if items.with(Vec::is_empty) => view! {
+                        <p class="text-center">"No recipes yet, add them from a recipe's page."</p>
+                    }.into_view(),
+                    Some(_) => view! { <ImageLinksBox items=items /> }.into_view(),
+                }}
+            </div>
+            <CollapsableBox title="Order" class="bg-base-200">
+                <table class="table">
+                    <tbody>
+                        {move || ordered().into_iter().map(|recipe| {
+                            let (earlier_id, later_id, remove_id) =
+                                (recipe.id.clone(), recipe.id.clone(), recipe.id.clone());
+                            view! {
+                                <tr>
+                                    <td class="w-full">{recipe.title}</td>
+                                    <td>
+                                        <div class="join">
+                                            <button
+                                                on:click=move |_| update_collection(&|v| v.move_recipe(&earlier_id, true))
+                                                class="btn btn-sm join-item"
+                                                aria-label="Move Earlier"
+                                            >"↑"</button>
+                                            <button
+                                                on:click=move |_| update_collection(&|v| v.move_recipe(&later_id, false))
+                                                class="btn btn-sm join-item"
+                                                aria-label="Move Later"
+                                            >"↓"</button>
+                                            <button
+                                                on:click=move |_| update_collection(&|v| v.remove_recipe(&remove_id))
+                                                class="btn btn-sm btn-outline btn-error join-item"
+                                                aria-label="Remove From Collection"
+                                            >"X"</button>
+                                        </div>
+                                    </td>
+                                </tr>
+                            }
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            </CollapsableBox>
+        </Show>
+    }
+}
@@ -39,7 +39,7 @@ pub fn Home() -> impl IntoView {
             <h2 class="text-2xl mb-2">"Your Stats"</h2>
             {move || {
                 account_stats.get().map(|v| {
-                    let stats = v.map_or_else(|| AccountStats{ ..Default::default() }, |v| v);
+                    let stats = v.unwrap_or_else(|| AccountStats{ ..Default::default() });
                     let stats = vec![
                         Stat::new("Number Of Recipes", &stats.recipe_count.to_string(), None),
                         Stat::new("Number Of Pantry Items", &stats.pantry_item_count.to_string(), None),
@@ -0,0 +1,287 @@
+use leptos::*;
+use mcc_frontend_types::label::{
+    count_label_usage, LabelChange, LabelColor, LabelMeta, LabelMetadata, LabelUsage,
+};
+use std::collections::HashSet;
+
+use crate::{
+    components::labels::LabelBadge,
+    contexts::prelude::{
+        use_api, use_label_meta, use_login, use_modal_controller, use_toasts, CurrentApi,
+        CurrentLogin,
+    },
+    helpers::{api_error_to_toast, load_all_pantry_items, load_all_recipes, logout_on_401},
+    modals::labels::LabelChangeModal,
+};
+
+#[component]
+fn LabelRow<S>(
+    name: String,
+    usage: LabelUsage,
+    /// possible parents
+    others: Vec<String>,
+    #[prop(into)] selected: Signal<bool>,
+    select_action: S,
+) -> impl IntoView
+where
+    S: Fn(bool) + 'static,
+{
+    let label_meta = use_label_meta();
+    let meta = {
+        let name = name.clone();
+        Signal::derive(move || label_meta.metadata.get().get(&name))
+    };
+    let update_meta = {
+        let name = name.clone();
+        move |f: &dyn Fn(&mut LabelMeta)| {
+            let mut new_meta = meta.get_untracked();
+            f(&mut new_meta);
+            label_meta.set(&name, new_meta);
+        }
+    };
+    let update_color = update_meta.clone();
+    let update_parent = update_meta.clone();
+    let implied_parent = LabelMetadata::default().parent(&name);
+
+    view! {
+        <tr>
+            <td>
+                <input
+                    prop:checked=move || selected.get()
+                    on:input=move |ev| select_action(event_target_checked(&ev))
+                    type="checkbox"
+                    class="checkbox checkbox-sm"
+                    aria-label=format!("Select '{}'", &name)
+                />
+            </td>
+            <td><LabelBadge label=name.clone() /></td>
+            <td>{usage.recipes}</td>
+            <td>{usage.pantry_items}</td>
+            <td>
+                <input
+                    prop:value=move || meta.get().icon.unwrap_or_default()
+                    on:change=move |ev| {
+                        let icon = event_target_value(&ev).trim().to_owned();
+                        update_meta(&|meta| meta.icon = (!icon.is_empty()).then(|| icon.clone()));
+                    }
+                    type="text"
+                    class="input input-bordered input-sm w-16"
+                    placeholder="🏷️"
+                    maxlength="8"
+                    aria-label=format!("Icon for '{}'", &name)
+                />
+            </td>
+            <td>
+                <select
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        let color = LabelColor::ALL.into_iter().find(|v| v.name() == value);
+                        update_color(&|meta| meta.color = color);
+                    }
+                    class="select select-bordered select-sm"
+                    aria-label=format!("Colour for '{}'", &name)
+                >
+                    <option value="" prop:selected=move || meta.get().color.is_none()>"__Default__"</option>
+                    {LabelColor::ALL.into_iter().map(|color| view!{
+                        <option
+                            value=color.name()
+                            prop:selected=move || meta.get().color == Some(color)
+                        >{color.name()}</option>
+                    }).collect_view()}
+                </select>
+            </td>
+            <td>
+                <select
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        update_parent(&|meta| meta.parent = (!value.is_empty()).then(|| value.clone()));
+                    }
+                    class="select select-bordered select-sm"
+                    aria-label=format!("Parent for '{}'", &name)
+                >
+                    <option value="" prop:selected=move || meta.get().parent.is_none()>
+                        {implied_parent.map(|v| format!("__{v}__")).unwrap_or_else(|| "__None__".to_owned())}
+                    </option>
+                    {others.into_iter().map(|other| {
+                        let value = other.clone();
+                        view!{
+                            <option
+                                value=value.clone()
+                                prop:selected=move || meta.get().parent.as_ref() == Some(&value)
+                            >{other}</option>
+                        }
+                    }).collect_view()}
+                </select>
+            </td>
+        </tr>
+    }
+}
+
+#[component]
+pub fn Labels() -> impl IntoView {
+    let toasts = use_toasts();
+    let modal_controller = use_modal_controller();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { set_login, .. } = use_login();
+    let selected = create_rw_signal(HashSet::<String>::new());
+    let new_name = create_rw_signal(String::default());
+    let label_meta = use_label_meta();
+    let last_change = store_value::<Option<LabelChange>>(None);
+
+    // everything is needed to count usage and to find what a change will affect
+    let data = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            let response = async {
+                let labels = api.get_labels().await?;
+                let recipes = load_all_recipes(&api).await?;
+                let items = load_all_pantry_items(&api).await?;
+                Ok((labels, recipes, items))
+            };
+            match response.await {
+                Ok(v) => Some(v),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading labels"));
+                    logout_on_401(&set_login, &err);
+                    None
+                }
+            }
+        },
+    );
+
+    let usage = move || {
+        let Some((labels, recipes, items)) = data.get().flatten() else {
+            return Default::default();
+        };
+        let mut usage = count_label_usage(&recipes, &items);
+        // include parents that are only implied by a name, so they can be styled too
+        let labels = label_meta
+            .metadata
+            .get()
+            .with_parents(&usage.keys().cloned().chain(labels).collect());
+        for label in labels {
+            usage.entry(label).or_default();
+        }
+        usage
+    };
+
+    let on_change_action = move |changed: bool| {
+        if let Some(change) = last_change.get_value().filter(|_| changed) {
+            label_meta.apply_change(&change);
+            selected.update(|v| v.clear());
+            new_name.set(String::default());
+            data.refetch();
+        }
+        modal_controller.close();
+    };
+
+    let open_change = move |to: Option<String>| {
+        let Some((_, recipes, items)) = untrack(move || data.get()).flatten() else {
+            return;
+        };
+        let change = LabelChange {
+            from: selected.get_untracked(),
+            to,
+        };
+        last_change.set_value(Some(change.clone()));
+        modal_controller.open(view! {
+            <LabelChangeModal
+                change=change
+                recipes=recipes
+                items=items
+                on_action=on_change_action
+            />
+        });
+    };
+
+    let on_select = move |name: String, is_selected: bool| {
+        selected.update(|selected| {
+            if is_selected {
+                selected.insert(name);
+            } else {
+                selected.remove(&name);
+            }
+        });
+    };
+
+    view! {
+        <div class="rounded bg-base-200 p-4 mb-2">
+            <h1 class="text-3xl font-bold mb-4">"Labels"</h1>
+            <p>"Labels are shared between recipes and pantry items, changes are applied to both."</p>
+            <p>"Searching for a parent label (e.g. \"diet\") also finds its children (e.g. \"diet/vegan\")."</p>
+            <p>"Label colours, icons and parents are kept in this browser, other devices will not see them."</p>
+        </div>
+        <div class="rounded bg-base-300 p-4 mb-2 flex flex-wrap items-center gap-2 sticky top-20 z-30 shadow-lg">
+            <span class="mr-auto font-bold">{move || format!("{} Selected", selected.get().len())}</span>
+            <div class="join shadow-lg">
+                <input
+                    prop:value=move || new_name.get()
+                    on:input=move |ev| new_name.set(event_target_value(&ev))
+                    type="text"
+                    class="input input-bordered input-sm join-item"
+                    placeholder="New Name..."
+                    list="existing-labels"
+                    maxlength="60"
+                />
+                <datalist id="existing-labels">
+                    {move || usage().into_keys().map(|label| view!{ <option value=label />}).collect_view()}
+                </datalist>
+                <button
+                    on:click=move |_| open_change(Some(new_name.get_untracked().trim().to_owned()))
+                    disabled=move || selected.get().is_empty() || new_name.get().trim().is_empty()
+                    class="btn btn-sm join-item"
+                >
+                    {move || if selected.get().len() > 1 { "Merge" } else { "Rename" }}
+                </button>
+            </div>
+            <button
+                on:click=move |_| open_change(None)
+                disabled=move || selected.get().is_empty()
+                class="btn btn-sm btn-outline btn-error"
+            >"Delete"</button>
+            <button on:click=move |_| selected.update(|v| v.clear()) class="btn btn-sm">"Clear"</button>
+        </div>
+        <div class="rounded bg-base-200 p-4">
+            <div class="rounded bg-base-100 p-4">
+                {move || match data.get() {
+                    None => view!{ <div class="loading loading-ring loading-lg"></div>}.into_view(),
+                    Some(None) => view!{ <div>"Failed To Load :("</div>}.into_view(),
+                    Some(Some(_)) => view!{
+                        <table class="table">
+                            <thead>
+                                <tr>
+                                    <th></th>
+                                    <th>"Label"</th>
+                                    <th>"Recipes"</th>
+                                    <th>"Pantry Items"</th>
+                                    <th>"Icon"</th>
+                                    <th>"Colour"</th>
+                                    <th>"Parent"</th>
+                                </tr>
+                            </thead>
+                            <tbody>
+                                {
+                                let usage = usage();
+                                let names = usage.keys().cloned().collect::<Vec<_>>();
+                                usage.into_iter().map(|(name, usage)| view!{
+                                    <LabelRow
+                                        name=name.clone()
+                                        usage=usage
+                                        others=names.iter().filter(|v| **v != name).cloned().collect()
+                                        selected={
+                                            let name = name.clone();
+                                            Signal::derive(move || selected.get().contains(&name))
+                                        }
+                                        select_action=move |v| on_select(name.clone(), v)
+                                    />
+                                }).collect_view()
+                                }
+                            </tbody>
+                        </table>
+                    }.into_view(),
+                }}
+            </div>
+        </div>
+    }
+}
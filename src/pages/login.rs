@@ -30,6 +30,7 @@ pub fn Login() -> impl IntoView {
                 Ok(token) => {
                     log::debug!("login successful, token will expire at: {:?}", token.expiry);
                     Some(StoredLogin {
+                        username: details.username,
                         api_url,
                         media_url,
                         token,
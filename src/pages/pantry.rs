@@ -1,25 +1,50 @@
-use leptos::*;
-use mcc_frontend_types::{pantry::Item, query::PantryFilter, HumanDateFormats};
-use std::collections::HashSet;
+use leptos::{html::Tbody, *};
+use leptos_router::{use_location, use_navigate, NavigateOptions};
+use mcc_frontend_core::{
+    query::{from_query_string, to_query_string},
+    storage::{read_paging_mode, read_pantry_view, set_paging_mode, set_pantry_view},
+};
+use mcc_frontend_types::{
+    page::PagingMode,
+    pantry::{BulkItemAction, Item},
+    query::{PantryColumn, PantryFilter, PantrySort, PantryView},
+    HumanDateFormats,
+};
+use std::collections::{HashMap, HashSet};
 use web_sys::SubmitEvent;
 
 use crate::{
     components::{
         collapse::CollapsableBox,
         input::{LabelSelector, ThreeStateSelect},
-        loading::{BufferedPageLoader, LoadingItemsState},
+        labels::LabelBadge,
+        loading::{BufferedPageLoader, ListSummary, LoadingItemsState, NumberedPager},
+        virtual_list::use_virtual_rows,
     },
     contexts::{
         login::{use_login, CurrentLogin},
-        prelude::{use_api, use_modal_controller, use_toasts, CurrentApi},
+        prelude::{
+            use_api, use_label_meta, use_modal_controller, use_pantry, use_shortcuts, use_toasts,
+            CurrentApi, Toast,
+        },
+    },
+    helpers::{
+        api_error_to_toast, load_all, load_filtered_pages, load_pages, logout_on_401,
+        remember_scroll_position, restore_scroll_position, scroll_key, server_labels,
+        storage_error_to_toast, track_scroll_position, FilteredPages,
     },
-    helpers::{api_error_to_toast, logout_on_401},
     modals::{
-        edit_pantry::{EditItemModal, LocationsModal, NewItemModal},
+        edit_pantry::{
+            undo_bulk_action, BulkEditKind, BulkEditModal, BulkEditResult, EditItemModal,
+            LocationsModal, NewItemModal,
+        },
         CreationMode,
     },
 };
 
+// height of a table row whose name or labels wrap onto a second line
+const PANTRY_ROW_HEIGHT: f64 = 80.0;
+
 #[component]
 pub fn PantryFilterPanel<F>(
     #[prop(into)] filters: MaybeSignal<PantryFilter>,
@@ -33,27 +58,21 @@ where
         || {},
         move |()| async move {
             let api = api.get_untracked().expect("api expected to exist");
-            match api.get_labels().await {
-                Ok(v) => v,
-                Err(_) => {
-                    vec![]
-                }
-            }
+            api.get_labels().await.unwrap_or_default()
         },
     );
     let locations = create_resource(
         || {},
         move |()| async move {
             let api = api.get_untracked().expect("api expected to exist");
-            match api.get_pantry_locations().await {
-                Ok(v) => v,
-                Err(_) => {
-                    vec![]
-                }
-            }
+            api.get_pantry_locations().await.unwrap_or_default()
         },
     );
-    let filters = create_rw_signal(filters.get_untracked());
+    let current_filters = filters;
+    let label_meta = use_label_meta();
+    let filters = create_rw_signal(current_filters.get_untracked());
+    // follow changes made outside the panel, e.g. back navigation
+    create_effect(move |_| filters.set(current_filters.get()));
 
     let on_search_submission = move |ev: SubmitEvent| {
         ev.prevent_default();
@@ -97,7 +116,7 @@ where
                         <span class="label-text">"Labels"</span>
                         <div class="w-80 max-h-28">
                             <LabelSelector
-                                labels=Signal::derive( move || HashSet::from_iter(labels.get().unwrap_or_default().into_iter()))
+                                labels=Signal::derive( move || label_meta.metadata.get().with_parents(&HashSet::from_iter(labels.get().unwrap_or_default())))
                                 allow_new=false
                                 compact=true
                                 selected=Signal::derive( move || filters.get().labels.unwrap_or_default())
@@ -146,23 +165,98 @@ where
 }
 
 #[component]
-fn PantryItemRow<E, D>(item: Item, edit_action: E, delete_action: D) -> impl IntoView
+fn PantryViewPanel<F>(#[prop(into)] table_view: Signal<PantryView>, on_change: F) -> impl IntoView
 where
+    F: Fn(PantryView) + 'static + Copy,
+{
+    view! {
+        <div class="flex flex-wrap items-center gap-2">
+            <label class="label gap-2">
+                <span class="label-text">"Sort"</span>
+                <select
+                    on:change=move |ev| {
+                        let mut new_view = table_view.get_untracked();
+                        new_view.sort = PantrySort::ALL
+                            .into_iter()
+                            .find(|v| v.label() == event_target_value(&ev));
+                        on_change(new_view);
+                    }
+                    class="select select-bordered select-sm"
+                >
+                    <option value="" prop:selected=move || table_view.get().sort.is_none()>"__Default__"</option>
+                    {PantrySort::ALL.into_iter().map(|sort| view!{
+                        <option
+                            value=sort.label()
+                            prop:selected=move || table_view.get().sort == Some(sort)
+                        >{sort.label()}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <div class="dropdown dropdown-bottom">
+                <label tabindex="0" class="btn btn-sm">"Columns"</label>
+                <ul tabindex="0" class="dropdown-content menu bg-base-200 rounded z-30 w-40 shadow-lg">
+                    {PantryColumn::ALL.into_iter().map(|column| view!{
+                        <li>
+                            <label class="label cursor-pointer">
+                                <span class="label-text">{column.label()}</span>
+                                <input
+                                    prop:checked=move || table_view.get().columns.contains(&column)
+                                    on:input=move |ev| {
+                                        let mut new_view = table_view.get_untracked();
+                                        if event_target_checked(&ev) {
+                                            new_view.columns.insert(column);
+                                        } else {
+                                            new_view.columns.remove(&column);
+                                        }
+                                        on_change(new_view);
+                                    }
+                                    type="checkbox"
+                                    class="checkbox checkbox-sm"
+                                />
+                            </label>
+                        </li>
+                    }).collect_view()}
+                </ul>
+            </div>
+        </div>
+    }
+}
+
+#[component]
+fn PantryItemRow<S, E, D>(
+    item: Item,
+    columns: HashSet<PantryColumn>,
+    location_name: Option<String>,
+    #[prop(into)] selected: Signal<bool>,
+    select_action: S,
+    edit_action: E,
+    delete_action: D,
+) -> impl IntoView
+where
+    S: Fn(bool) + 'static,
     E: Fn() + 'static,
     D: Fn() + 'static,
 {
     view! {
         <tr>
+            <td>
+                <input
+                    prop:checked=move || selected.get()
+                    on:input=move |ev| select_action(event_target_checked(&ev))
+                    type="checkbox"
+                    class="checkbox checkbox-sm"
+                    aria-label=format!("Select '{}'", &item.name)
+                />
+            </td>
             <td class="flex justify-center">
                 {
-                    let chip_color;
-                    if item.is_expired() {
-                        chip_color = "bg-error";
+                    let chip_color = if item.is_expired() {
+                        "bg-error"
                     } else if item.is_expired_with_offset(7) {
-                        chip_color = "bg-warning";
+                        "bg-warning"
                     } else {
-                        chip_color = "bg-success";
-                    }
+                        "bg-success"
+                    };
                     view!{ <div class=format!("h-2 w-2 duration-300 sm:w-7 rounded-full {chip_color}")></div>}
                 }
             </td>
@@ -178,7 +272,24 @@ where
                 }
                 </time>
             </td>
-            <td class="hidden sm:table-cell">{item.quantity.to_string()}</td>
+            <td class="hidden sm:table-cell whitespace-nowrap">{item.quantity.to_string()}</td>
+            {columns.contains(&PantryColumn::Location).then(|| view!{
+                <td>{location_name.unwrap_or_else(|| "-".to_owned())}</td>
+            })}
+            {columns.contains(&PantryColumn::Labels).then(|| view!{
+                <td>
+                    <div class="flex flex-wrap gap-1">
+                        {item.labels.iter().map(|label| view!{
+                            <LabelBadge label=label.clone() />
+                        }).collect_view()}
+                    </div>
+                </td>
+            })}
+            {columns.contains(&PantryColumn::Notes).then(|| view!{
+                <td class="max-w-xs truncate" title=item.notes.clone()>
+                    {item.notes.clone().unwrap_or_default()}
+                </td>
+            })}
             <td class="flex justify-end">
                 <div class="join shadow-lg">
                     <button
@@ -197,27 +308,219 @@ where
     }
 }
 
+#[component]
+fn PantryTableHead<F>(
+    #[prop(into)] columns: Signal<HashSet<PantryColumn>>,
+    #[prop(into)] all_selected: Signal<bool>,
+    select_all_action: F,
+) -> impl IntoView
+where
+    F: Fn(bool) + 'static,
+{
+    view! {
+        <thead>
+            <tr>
+                <th>
+                    <input
+                        prop:checked=move || all_selected.get()
+                        on:input=move |ev| select_all_action(event_target_checked(&ev))
+                        type="checkbox"
+                        class="checkbox checkbox-sm"
+                        aria-label="Select All"
+                    />
+                </th>
+                <th></th>
+                <th>"Name"</th>
+                <th>"Expiry"</th>
+                <th class="hidden sm:table-cell">"Quantity"</th>
+                {move || {
+                    let columns = columns.get();
+                    [PantryColumn::Location, PantryColumn::Labels, PantryColumn::Notes]
+                        .into_iter()
+                        .filter(|v| columns.contains(v))
+                        .map(|v| view!{ <th>{v.label()}</th>})
+                        .collect_view()
+                }}
+                <th></th>
+            </tr>
+        </thead>
+    }
+}
+
+#[component]
+fn BulkActionsBar<F, C>(
+    #[prop(into)] count: Signal<usize>,
+    bulk_action: F,
+    clear_action: C,
+) -> impl IntoView
+where
+    F: Fn(BulkEditKind) + 'static + Copy,
+    C: Fn() + 'static,
+{
+    view! {
+        <div class="rounded bg-base-300 p-4 mb-2 flex flex-wrap items-center gap-2 sticky top-20 z-30 shadow-lg">
+            <span class="mr-auto font-bold">{move || format!("{} Selected", count.get())}</span>
+            <div class="join shadow-lg">
+                <button on:click=move |_| bulk_action(BulkEditKind::Move) class="btn btn-sm join-item">"Move"</button>
+                <button on:click=move |_| bulk_action(BulkEditKind::Labels) class="btn btn-sm join-item">"Labels"</button>
+                <button on:click=move |_| bulk_action(BulkEditKind::Expiry) class="btn btn-sm join-item">"Expiry"</button>
+                <button on:click=move |_| bulk_action(BulkEditKind::Quantity) class="btn btn-sm join-item">"Quantity"</button>
+                <button
+                    on:click=move |_| bulk_action(BulkEditKind::Delete)
+                    class="btn btn-sm btn-outline btn-error join-item"
+                >"Delete"</button>
+            </div>
+            <button on:click=move |_| clear_action() class="btn btn-sm">"Clear"</button>
+        </div>
+    }
+}
+
+fn pantry_url(filters: &PantryFilter, table_view: &PantryView) -> String {
+    let view_query = to_query_string(table_view);
+    match view_query.is_empty() {
+        true => format!("/pantry?{}", to_query_string(filters)),
+        false => format!("/pantry?{}&{view_query}", to_query_string(filters)),
+    }
+}
+
+/// Replace items with their updated version (matched by id)
+fn replace_items(items: &mut [Item], updated: &[Item]) {
+    for item in items.iter_mut() {
+        if let Some(updated) = updated.iter().find(|v| v.id == item.id) {
+            item.clone_from(updated);
+        }
+    }
+}
+
+fn remove_items(items: &mut Vec<Item>, removed: &[Item]) {
+    items.retain(|item| !removed.iter().any(|v| v.id == item.id));
+}
+
 #[component]
 pub fn Pantry() -> impl IntoView {
     let toasts = use_toasts();
+    let pantry = use_pantry();
     let modal_controller = use_modal_controller();
     let CurrentApi { api, .. } = use_api();
-    let CurrentLogin { set_login, .. } = use_login();
-    let filters = create_rw_signal(PantryFilter::default());
+    let CurrentLogin { login, set_login } = use_login();
+    let location = use_location();
+    let pathname = location.pathname.get_untracked();
+    let label_meta = use_label_meta();
+    let filters = create_rw_signal(from_query_string::<PantryFilter>(
+        &location.search.get_untracked(),
+    ));
+    // when set every page up to the current one is loaded, e.g. after navigating back
+    let restoring = create_rw_signal(true);
+    let paging = create_rw_signal(
+        login
+            .get_untracked()
+            .map(|login| read_paging_mode(&login))
+            .unwrap_or_default(),
+    );
+    // the url takes priority, so links can be shared
+    let table_view = create_rw_signal({
+        let from_url = from_query_string::<PantryView>(&location.search.get_untracked());
+        if from_url != PantryView::default() {
+            from_url
+        } else {
+            login
+                .get_untracked()
+                .and_then(|login| read_pantry_view(&login))
+                .unwrap_or_default()
+        }
+    });
     let items = create_rw_signal::<Vec<Item>>(Vec::default());
     let new_items = create_rw_signal::<Vec<Item>>(Vec::default());
+    let selected = create_rw_signal(HashSet::<String>::new());
+    let last_bulk = create_rw_signal::<Option<BulkEditResult>>(None);
+
+    // follow the url, for back/forward navigation
+    create_effect(move |_| {
+        let search = location.search.get();
+        if location.pathname.get_untracked() != pathname {
+            return;
+        }
+        let from_url = from_query_string::<PantryFilter>(&search);
+        if from_url != filters.get_untracked() {
+            restoring.set(true);
+            filters.set(from_url);
+        }
+        let view_from_url = from_query_string::<PantryView>(&search);
+        if view_from_url != PantryView::default() && view_from_url != table_view.get_untracked() {
+            table_view.set(view_from_url);
+        }
+    });
+
+    let current_scroll_key = move || {
+        scroll_key(
+            "/pantry",
+            &to_query_string(&filters.get_untracked()),
+            paging.get_untracked(),
+        )
+    };
+    track_scroll_position(current_scroll_key, restoring.into());
 
+    let filtered = store_value(FilteredPages::default());
+    // sorting is done here, so every matching item is loaded while a sort is picked
     let current_page = create_resource(
-        move || filters.get(),
-        move |filters| {
+        move || (filters.get(), table_view.with(|v| v.sort.is_some())),
+        move |(filters, load_all_items)| {
             let api = api.get_untracked().expect("api expected to exist");
+            let first_page =
+                if restoring.get_untracked() && paging.get_untracked() == PagingMode::Scroll {
+                    1
+                } else {
+                    filters.page
+                };
+            let metadata = label_meta.metadata.get_untracked();
             async move {
-                match api.get_pantry_items(&filters).await {
+                let response = async {
+                    let labels =
+                        server_labels(&api, &label_meta, &metadata, &filters.labels).await?;
+                    let load = |page: usize, per_page: usize| {
+                        let filters = PantryFilter {
+                            page,
+                            per_page,
+                            labels: labels.clone(),
+                            ..filters.clone()
+                        };
+                        let api = api.clone();
+                        async move { api.get_pantry_items(&filters).await }
+                    };
+                    let selected = filters.labels.clone().unwrap_or_default();
+                    if load_all_items {
+                        let items = load_all(load)
+                            .await?
+                            .into_iter()
+                            .filter(|item| metadata.matches(&selected, &item.labels))
+                            .collect::<Vec<_>>();
+                        return Ok((items, false));
+                    }
+                    // parent labels are not sent to the server, so are checked here
+                    if labels == filters.labels {
+                        return load_pages(first_page, filters.page, filters.per_page, load).await;
+                    }
+                    load_filtered_pages(
+                        filtered,
+                        PantryFilter {
+                            page: 1,
+                            ..filters.clone()
+                        },
+                        first_page,
+                        filters.page,
+                        filters.per_page,
+                        load,
+                        |item| metadata.matches(&selected, &item.labels),
+                    )
+                    .await
+                }
+                .await;
+                match response {
                     Ok(v) => Some(v),
                     Err(err) => {
                         toasts.push(api_error_to_toast(
                             &err,
-                            &format!("loading pantry items page {}", &filters.page),
+                            &format!("loading pantry items page {}", filters.page),
                         ));
                         logout_on_401(&set_login, &err);
                         None
@@ -227,23 +530,85 @@ pub fn Pantry() -> impl IntoView {
         },
     );
 
+    let location_names = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            api.get_pantry_locations()
+                .await
+                .unwrap_or_default()
+                .into_iter()
+                .map(|v| (v.id, v.name))
+                .collect::<HashMap<_, _>>()
+        },
+    );
+
+    create_effect(move |_| {
+        let table_view = table_view.get();
+        if let Some(login) = login.get_untracked() {
+            if let Err(err) = set_pantry_view(&login, &table_view) {
+                toasts.push(storage_error_to_toast(&err, "the table view"));
+            }
+        }
+        let url = pantry_url(&filters.get_untracked(), &table_view);
+        if url != format!("/pantry?{}", location.search.get_untracked()) {
+            let navigator = use_navigate();
+            navigator(
+                &url,
+                NavigateOptions {
+                    replace: true,
+                    scroll: false,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+
+    let columns = Signal::derive(move || table_view.get().columns);
+
+    let sorted = move |list: RwSignal<Vec<Item>>| {
+        let mut list = list.get();
+        if let Some(sort) = table_view.get().sort {
+            location_names
+                .with(|names| sort.sort(&mut list, names.as_ref().unwrap_or(&HashMap::new())));
+        }
+        list
+    };
+
+    let location_name = move |item: &Item| {
+        location_names.with(|names| {
+            names
+                .as_ref()
+                .and_then(|v| v.get(&item.location_id).cloned())
+        })
+    };
+
     create_effect(move |_| {
         // XXX this is not great, but it works (just ensure any reads everything is x.get_untracked()
-        if let Some(current_page) = current_page.get().flatten() {
+        if let Some((current_page, _)) = current_page.get().flatten() {
+            let is_restore = restoring.get_untracked();
             new_items.set(Vec::default());
             items.update(|v| {
-                if filters.get_untracked().page == 1 {
+                if is_restore
+                    || table_view.get_untracked().sort.is_some()
+                    || filters.get_untracked().page == 1
+                    || paging.get_untracked() == PagingMode::Numbered
+                {
                     v.clear()
                 }
                 v.extend(current_page);
             });
+            if is_restore {
+                restore_scroll_position(&current_scroll_key());
+                restoring.set(false);
+            }
         }
     });
 
     let loading_items_state = Signal::derive(move || {
         match (current_page.loading().get(), current_page.get().flatten()) {
             (true, _) => LoadingItemsState::Loading,
-            (false, Some(items)) => LoadingItemsState::Loaded(items.len()),
+            (false, Some((_, has_more))) => LoadingItemsState::Loaded { has_more },
             (false, None) => LoadingItemsState::Failed,
         }
     });
@@ -253,11 +618,14 @@ pub fn Pantry() -> impl IntoView {
         let id = id.clone();
         async move {
             match api.delete_pantry_item_by_id(&id).await {
-                Ok(_) => items.update(|items| {
-                    if let Some(i) = items.iter().position(|v| v.id == id) {
-                        items.remove(i);
-                    }
-                }),
+                Ok(_) => {
+                    pantry.invalidate();
+                    items.update(|items| {
+                        if let Some(i) = items.iter().position(|v| v.id == id) {
+                            items.remove(i);
+                        }
+                    })
+                }
                 Err(err) => toasts.push(api_error_to_toast(&err, "deleting pantry item")),
             }
         }
@@ -268,11 +636,14 @@ pub fn Pantry() -> impl IntoView {
         let id = id.clone();
         async move {
             match api.delete_pantry_item_by_id(&id).await {
-                Ok(_) => new_items.update(|items| {
-                    if let Some(i) = items.iter().position(|v| v.id == id) {
-                        items.remove(i);
-                    }
-                }),
+                Ok(_) => {
+                    pantry.invalidate();
+                    new_items.update(|items| {
+                        if let Some(i) = items.iter().position(|v| v.id == id) {
+                            items.remove(i);
+                        }
+                    })
+                }
                 Err(err) => toasts.push(api_error_to_toast(&err, "deleting pantry item")),
             }
         }
@@ -329,7 +700,7 @@ pub fn Pantry() -> impl IntoView {
         modal_controller.close();
     };
 
-    let on_new_item_click = move |_| {
+    let new_item = move || {
         modal_controller.open(
             view! {
                 <NewItemModal
@@ -339,6 +710,8 @@ pub fn Pantry() -> impl IntoView {
             .into_view(),
         );
     };
+    let on_new_item_click = move |_| new_item();
+    use_shortcuts().register(Some("n"), "New Pantry Item", new_item);
 
     let on_locations_click = move |_| {
         modal_controller.open(
@@ -369,23 +742,173 @@ pub fn Pantry() -> impl IntoView {
         });
     };
 
-    let on_new_filters = move |new_filters| {
-        filters.update(|v| {
-            *v = new_filters;
-            v.page = 1;
+    let on_select = move |id: String, is_selected: bool| {
+        selected.update(|selected| {
+            if is_selected {
+                selected.insert(id);
+            } else {
+                selected.remove(&id);
+            }
+        });
+    };
+
+    let on_select_all = move |list: RwSignal<Vec<Item>>, is_selected: bool| {
+        let ids = list.get_untracked().into_iter().map(|v| v.id);
+        selected.update(|selected| {
+            for id in ids {
+                if is_selected {
+                    selected.insert(id);
+                } else {
+                    selected.remove(&id);
+                }
+            }
+        });
+    };
+
+    let all_selected = move |list: RwSignal<Vec<Item>>| {
+        Signal::derive(move || {
+            let selected = selected.get();
+            let list = list.get();
+            !list.is_empty() && list.iter().all(|v| selected.contains(&v.id))
+        })
+    };
+
+    let on_bulk_update = move |result: BulkEditResult| {
+        if result.action == BulkItemAction::Delete {
+            items.update(|v| remove_items(v, &result.previous));
+            new_items.update(|v| remove_items(v, &result.previous));
+            selected.update(|selected| {
+                for item in &result.previous {
+                    selected.remove(&item.id);
+                }
+            });
+        } else {
+            items.update(|v| replace_items(v, &result.updated));
+            new_items.update(|v| replace_items(v, &result.updated));
+        }
+    };
+
+    let on_bulk_action = move |result: Option<BulkEditResult>| {
+        if result.is_some() {
+            last_bulk.set(result);
+        }
+        modal_controller.close();
+    };
+
+    let on_bulk_click = move |kind: BulkEditKind| {
+        let selected = selected.get_untracked();
+        let selected_items = new_items
+            .get_untracked()
+            .into_iter()
+            .chain(items.get_untracked())
+            .filter(|v| selected.contains(&v.id))
+            .collect::<Vec<_>>();
+        modal_controller.open(view! {
+            <BulkEditModal
+                kind=kind
+                items=selected_items
+                on_update=on_bulk_update
+                on_action=on_bulk_action
+            />
         });
     };
 
+    let undo_bulk = create_action(move |result: &BulkEditResult| {
+        let api = api.get_untracked().expect("api expected to exist");
+        let result = result.clone();
+        async move {
+            let is_delete = result.action == BulkItemAction::Delete;
+            let (restored, failed) = undo_bulk_action(&api, result).await;
+            pantry.invalidate();
+            if is_delete {
+                new_items.update(|v| v.extend(restored));
+            } else {
+                items.update(|v| replace_items(v, &restored));
+                new_items.update(|v| replace_items(v, &restored));
+            }
+            if failed != 0 {
+                toasts.push(Toast {
+                    message: format!("Unable to undo changes for {failed} items"),
+                });
+            }
+            last_bulk.set(None);
+        }
+    });
+
+    let on_new_filters = move |new_filters: PantryFilter| {
+        let new_filters = PantryFilter {
+            page: 1,
+            ..new_filters
+        };
+        remember_scroll_position(
+            &scroll_key(
+                "/pantry",
+                &to_query_string(&new_filters),
+                paging.get_untracked(),
+            ),
+            0.0,
+        );
+        let navigator = use_navigate();
+        navigator(
+            &pantry_url(&new_filters, &table_view.get_untracked()),
+            Default::default(),
+        );
+    };
+
     let on_load_more = move || {
         filters.update(|v| {
             v.page += 1;
         });
+        let navigator = use_navigate();
+        navigator(
+            &pantry_url(&filters.get_untracked(), &table_view.get_untracked()),
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
     };
 
     let on_retry = move || {
         current_page.refetch();
     };
 
+    let on_page = move |page: usize| {
+        filters.update(|v| v.page = page);
+        remember_scroll_position(
+            &scroll_key(
+                "/pantry",
+                &to_query_string(&filters.get_untracked()),
+                PagingMode::Numbered,
+            ),
+            0.0,
+        );
+        let navigator = use_navigate();
+        navigator(
+            &pantry_url(&filters.get_untracked(), &table_view.get_untracked()),
+            Default::default(),
+        );
+    };
+
+    let on_paging_change = move |mode: PagingMode| {
+        paging.set(mode);
+        if let Some(login) = login.get_untracked() {
+            if let Err(err) = set_paging_mode(&login, mode) {
+                toasts.push(storage_error_to_toast(&err, "the paging mode"));
+            }
+        }
+        on_new_filters(filters.get_untracked());
+    };
+
+    // long lists only render the rows near the screen
+    let rows_ref = create_node_ref::<Tbody>();
+    let rows = use_virtual_rows(
+        rows_ref,
+        Signal::derive(move || items.with(Vec::len)),
+        PANTRY_ROW_HEIGHT,
+    );
+
     view! {
         <div class="rounded bg-base-200 p-4 mb-2">
             <h1 class="text-3xl font-bold mb-4">"Pantry"</h1>
@@ -394,27 +917,55 @@ pub fn Pantry() -> impl IntoView {
                 <button on:click=on_locations_click class="btn join-item btn-neutral">"Locations"</button>
             </div>
         </div>
+        {move || {
+            last_bulk.get().map(|result| view!{
+                <div class="alert mb-2 shadow-lg">
+                    <span>{format!("Changed {} items", result.previous.len())}</span>
+                    <div class="join">
+                        <button
+                            on:click=move |_| undo_bulk.dispatch(result.clone())
+                            class="btn btn-sm join-item"
+                            class:loading=move || undo_bulk.pending().get()
+                        >"Undo"</button>
+                        <button on:click=move |_| last_bulk.set(None) class="btn btn-sm join-item">"Dismiss"</button>
+                    </div>
+                </div>
+            })
+        }}
+        <Show when=move || !selected.get().is_empty()>
+            <BulkActionsBar
+                count=Signal::derive(move || selected.get().len())
+                bulk_action=on_bulk_click
+                clear_action=move || selected.update(|v| v.clear())
+            />
+        </Show>
         <Show when=move || !new_items.get().is_empty()>
             <div class="rounded bg-base-200 p-4 mb-2">
                 <h2 class="text-2xl font-bold mb-4">"New Items"</h2>
                 <div class="rounded bg-base-100 p-4">
                     <table class="table">
-                        <thead>
-                            <tr>
-                                <th></th>
-                                <th>"Name"</th>
-                                <th>"Expiry"</th>
-                                <th class="hidden sm:table-cell">"Quantity"</th>
-                                <th></th>
-                            </tr>
-                        </thead>
+                        <PantryTableHead
+                            columns=columns
+                            all_selected=all_selected(new_items)
+                            select_all_action=move |v| on_select_all(new_items, v)
+                        />
                         <tbody>
                         {move || {
                             // NOTE "For" component not used as it will not re-render on item edit
-                            new_items.get().into_iter().map(|item|{
+                            sorted(new_items).into_iter().map(|item|{
                                 view!{
                                     <PantryItemRow
                                         item=item.clone()
+                                        columns=columns.get()
+                                        location_name=location_name(&item)
+                                        selected={
+                                            let id = item.id.clone();
+                                            Signal::derive(move || selected.get().contains(&id))
+                                        }
+                                        select_action={
+                                            let id = item.id.clone();
+                                            move |v| on_select(id.clone(), v)
+                                        }
                                         edit_action={
                                             let item = item.clone();
                                             move || on_edit_new_item_click(item.clone())
@@ -432,24 +983,39 @@ pub fn Pantry() -> impl IntoView {
         <div class="rounded bg-base-200 p-4">
             <PantryFilterPanel filters=filters on_change=on_new_filters />
             <div class="divider" />
+            <PantryViewPanel table_view=table_view on_change=move |v| table_view.set(v) />
+            <ListSummary
+                loaded=Signal::derive(move || items.with(Vec::len))
+                noun="items"
+                paging=paging.into()
+                on_paging_change=on_paging_change
+            />
             <div class="rounded bg-base-100 p-4 mb-4">
                 <table class="table">
-                    <thead>
-                        <tr>
-                            <th></th>
-                            <th>"Name"</th>
-                            <th>"Expiry"</th>
-                            <th class="hidden sm:table-cell">"Quantity"</th>
-                            <th></th>
-                        </tr>
-                    </thead>
-                    <tbody>
+                    <PantryTableHead
+                        columns=columns
+                        all_selected=all_selected(items)
+                        select_all_action=move |v| on_select_all(items, v)
+                    />
+                    <tbody node_ref=rows_ref>
+                    <tr data-spacer="" aria-hidden="true" class="border-0" style=move || format!("height: {}px", rows.before()) />
                     {move || {
                         // NOTE "For" component not used as it will not re-render on item edit
-                        items.get().into_iter().map(|item|{
+                        let visible = rows.visible.get();
+                        sorted(items).into_iter().skip(visible.start).take(visible.len()).map(|item|{
                             view!{
                                 <PantryItemRow
                                     item=item.clone()
+                                    columns=columns.get()
+                                    location_name=location_name(&item)
+                                    selected={
+                                        let id = item.id.clone();
+                                        Signal::derive(move || selected.get().contains(&id))
+                                    }
+                                    select_action={
+                                        let id = item.id.clone();
+                                        move |v| on_select(id.clone(), v)
+                                    }
                                     edit_action={
                                         let item = item.clone();
                                         move || on_edit_item_click(item.clone())
@@ -459,14 +1025,27 @@ pub fn Pantry() -> impl IntoView {
                             }
                         }).collect_view()
                     }}
+                    <tr data-spacer="" aria-hidden="true" class="border-0" style=move || format!("height: {}px", rows.after()) />
                     </tbody>
                 </table>
-                <BufferedPageLoader
-                    items_state=loading_items_state
-                    items_per_page=Signal::derive( move || filters.get().per_page)
-                    load_more_action=on_load_more
-                    retry_action=on_retry
-                />
+                // with a sort everything is already loaded, so there are no pages to pick
+                {move || match (paging.get(), table_view.with(|v| v.sort.is_some())) {
+                    (PagingMode::Scroll, _) | (_, true) => view! {
+                        <BufferedPageLoader
+                            items_state=loading_items_state
+                            load_more_action=on_load_more
+                            retry_action=on_retry
+                        />
+                    },
+                    (PagingMode::Numbered, false) => view! {
+                        <NumberedPager
+                            items_state=loading_items_state
+                            page=Signal::derive(move || filters.get().page)
+                            on_page=on_page
+                            retry_action=on_retry
+                        />
+                    },
+                }}
             </div>
         </div>
     }
@@ -1,17 +1,206 @@
-use std::ops::Not;
+use std::collections::HashMap;
 
 use leptos::*;
-use leptos_router::{use_navigate, use_params_map};
+use leptos_router::{use_navigate, use_params_map, A};
+use mcc_frontend_core::{api::ApiError, storage};
 
 use crate::{
-    components::{collapse::*, input::DropdownConfirm},
+    components::{
+        collapse::*,
+        collections::AddToCollection,
+        gallery::{delete_recipe_gallery, use_recipe_gallery, ImageSource, ImageStrip, Lightbox},
+        input::DropdownConfirm,
+        markdown::Markdown,
+        recipe_history::RecipeHistoryPanel,
+        tags::TagLinks,
+    },
     contexts::prelude::{
-        use_api, use_login, use_modal_controller, use_toasts, CurrentApi, CurrentLogin,
+        use_api, use_login, use_modal_controller, use_search_index, use_shortcuts, use_toasts,
+        CurrentApi, CurrentLogin,
     },
-    helpers::{api_error_to_toast, logout_on_401},
+    helpers::{api_error_to_toast, load_linked_recipes, logout_on_401, storage_error_to_toast},
     modals::edit_recipe::*,
 };
-use mcc_frontend_types::{recipe::Recipe, Fraction, HourMinuteSecond};
+use mcc_frontend_types::{
+    recipe::{ingredient_sections, step_sections, Ingredient, Recipe, RecipeVariants},
+    sub_recipe::{combine_ingredients, expand_ingredients, linked_recipe_ids},
+    Fraction, HourMinuteSecond,
+};
+
+/// An ingredient's name, linking to the recipe when it is a sub-recipe
+#[component]
+pub fn IngredientName(ingredient: Ingredient) -> impl IntoView {
+    match ingredient.linked_recipe() {
+        Some(link) => view! { <A href=link.href() class="link">{link.title}</A> }.into_view(),
+        None => ingredient.name.into_view(),
+    }
+}
+
+/// Every ingredient needed, with sub-recipes expanded into their own ingredients
+#[component]
+fn AllIngredientsBox(recipe: RwSignal<Recipe>) -> impl IntoView {
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { set_login, .. } = use_login();
+
+    // only reload when the links change
+    let linked_ids = create_memo(move |_| {
+        let mut ids = recipe.with(|v| {
+            linked_recipe_ids(&v.ingredients)
+                .into_iter()
+                .collect::<Vec<_>>()
+        });
+        ids.sort();
+        ids
+    });
+    let linked_recipes = create_resource(
+        move || linked_ids.get(),
+        move |ids| async move {
+            if ids.is_empty() {
+                return None;
+            }
+            let api = api.get_untracked().expect("api expected to exist");
+            match load_linked_recipes(&api, &[recipe.get_untracked()]).await {
+                Ok(recipes) => Some(recipes),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading sub-recipes"));
+                    logout_on_401(&set_login, &err);
+                    None
+                }
+            }
+        },
+    );
+
+    move || {
+        let recipes = linked_recipes.get().flatten()?;
+        let expanded = recipe.with(|recipe| expand_ingredients(recipe, &recipes, 1.0));
+        let warnings = expanded
+            .cycles
+            .iter()
+            .map(|id| {
+                format!(
+                    "'{}' uses itself, so it was not expanded",
+                    title_of(&recipes, &recipe.get(), id)
+                )
+            })
+            .chain(
+                expanded
+                    .missing
+                    .iter()
+                    .map(|_| "A linked recipe no longer exists".to_owned()),
+            )
+            .collect::<Vec<_>>();
+        let ingredients = combine_ingredients(expanded.ingredients);
+        Some(view! {
+            <CollapsableBox title="All Ingredients" class="mt-4 border border-base-300 bg-base-100">
+                {warnings.into_iter().map(|warning| view! {
+                    <div class="alert alert-warning mb-2">{warning}</div>
+                }).collect_view()}
+                <table class="table table-compact table-zebra w-full">
+                    <tbody>
+                        {ingredients.into_iter().map(|ingredient| view! {
+                            <tr>
+                                <td class="whitespace-normal">
+                                    {format!("{} {}", Fraction::from(ingredient.amount), ingredient.unit_type)}
+                                </td>
+                                <td class="whitespace-normal"><IngredientName ingredient=ingredient.clone() /></td>
+                                <td class="whitespace-normal">{ingredient.description.unwrap_or_default()}</td>
+                            </tr>
+                        }).collect_view()}
+                    </tbody>
+                </table>
+            </CollapsableBox>
+        })
+    }
+}
+
+fn title_of(recipes: &HashMap<String, Recipe>, current: &Recipe, id: &str) -> String {
+    if current.id == id {
+        return current.title.clone();
+    }
+    recipes.get(id).map(|v| v.title.clone()).unwrap_or_default()
+}
+
+/// Links to the recipe this was copied from & copies made from it
+#[component]
+pub fn RecipeVariantsBox(
+    id: String,
+    /// whether links can be removed
+    #[prop(optional)]
+    edit_mode: bool,
+) -> impl IntoView {
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, .. } = use_login();
+    let login = login.get_untracked().expect("expected login to exist");
+    let variants = create_rw_signal(storage::read_recipe_variants(&login));
+    let login = store_value(login);
+    let id = store_value(id);
+
+    let update_variants = move |f: &dyn Fn(&mut RecipeVariants)| {
+        variants.update(|variants| {
+            f(variants);
+            if let Err(err) =
+                login.with_value(|login| storage::set_recipe_variants(login, variants))
+            {
+                toasts.push(storage_error_to_toast(&err, "recipe variants"));
+            }
+        });
+    };
+
+    // titles of linked recipes, removed recipes are forgotten
+    let linked = create_resource(
+        move || variants.get(),
+        move |variants| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            let id = id.get_value();
+            let mut linked = Vec::new();
+            let parent = variants.parent(&id).cloned();
+            for linked_id in parent.iter().cloned().chain(variants.variants(&id)) {
+                match api.get_recipe_by_id(linked_id.clone()).await {
+                    Ok(recipe) => linked.push((linked_id, recipe.title)),
+                    Err(ApiError::Response(err)) if err.status_code == 404 => {
+                        update_variants(&|variants| variants.remove(&linked_id));
+                    }
+                    Err(_) => linked.push((linked_id.clone(), linked_id)),
+                }
+            }
+            (parent, linked)
+        },
+    );
+
+    view! {
+        {move || linked.get().filter(|(_, linked)| !linked.is_empty()).map(|(parent, linked)| {
+            let (parent, variants): (Vec<_>, Vec<_>) = linked
+                .into_iter()
+                .partition(|(linked_id, _)| Some(linked_id) == parent.as_ref());
+            view!{
+                <div class="mb-4 p-4 rounded bg-base-200">
+                    {parent.into_iter().map(|(parent_id, title)| view!{
+                        <div class="flex items-center gap-2 mb-2">
+                            <span>"Variant Of"</span>
+                            <A href=format!("/recipes/{parent_id}") class="link font-bold">{title}</A>
+                            <Show when=move || edit_mode>
+                                <button
+                                    on:click=move |_| update_variants(&|variants| variants.unlink(&id.get_value()))
+                                    class="btn btn-sm"
+                                >"Unlink"</button>
+                            </Show>
+                        </div>
+                    }).collect_view()}
+                    {(!variants.is_empty()).then(|| view!{
+                        <h2 class="text-xl font-bold mb-2">"Variants Of This Recipe"</h2>
+                        <div class="flex flex-wrap gap-2">
+                            {variants.into_iter().map(|(variant_id, title)| view!{
+                                <A href=format!("/recipes/{variant_id}") class="btn btn-sm">{title}</A>
+                            }).collect_view()}
+                        </div>
+                    })}
+                </div>
+            }
+        })}
+    }
+}
 
 #[component]
 fn RecipeContent(recipe: Recipe) -> impl IntoView {
@@ -21,13 +210,41 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
     let CurrentLogin { login, set_login } = use_login();
     let media_url = move || login.get().expect("expected login to exist").media_url;
     let recipe = create_rw_signal(recipe);
-    let edit_mode = create_rw_signal(false);
+    let search_index = use_search_index();
+
+    // keep the search index up to date with what was loaded or edited here
+    create_effect(move |_| recipe.with(|recipe| search_index.upsert(recipe)));
 
     let delete_action = create_action(move |_: &()| async move {
         let navigator = use_navigate();
         let api = api.get().expect("expected api to exist");
         match api.delete_recipe(&recipe.get().id).await {
             Ok(_) => {
+                let id = recipe.get_untracked().id;
+                search_index.remove(&id);
+                if let Some(login) = login.get_untracked() {
+                    let mut variants = storage::read_recipe_variants(&login);
+                    variants.remove(&id);
+                    let mut history = storage::read_recipe_history(&login);
+                    history.remove(&id);
+                    let mut collections = storage::read_collections(&login);
+                    collections.remove_recipe(&id);
+                    let mut drafts = storage::read_recipe_drafts(&login);
+                    drafts.0.remove(&id);
+                    let saved = [
+                        storage::set_recipe_variants(&login, &variants),
+                        storage::set_recipe_history(&login, &history),
+                        storage::set_collections(&login, &collections),
+                        storage::set_recipe_drafts(&login, &drafts),
+                        delete_recipe_gallery(&login, &id).await,
+                    ];
+                    if let Some(Err(err)) = saved.into_iter().find(Result::is_err) {
+                        toasts.push(storage_error_to_toast(
+                            &err,
+                            "the removal of the recipe's local data",
+                        ));
+                    }
+                }
                 navigator("/recipes", Default::default());
                 true
             }
@@ -46,64 +263,51 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
         modal_controller.close();
     };
 
-    let on_title_edit_action = move |new_title| {
-        if let Some(new_title) = new_title {
-            recipe.update(|r| r.title = new_title);
-        }
-        modal_controller.close();
-    };
-
-    let on_image_edit_action = move |new_image_id| {
-        if let Some(new_image_id) = new_image_id {
-            recipe.update(|r| r.image_id = new_image_id);
-        }
-        modal_controller.close();
-    };
-
-    let on_info_edit_action = move |new_info| {
-        if let Some(new_info) = new_info {
-            recipe.update(|r| r.info = new_info);
-        }
-        modal_controller.close();
-    };
-
-    let on_description_edit_action = move |new_description| {
-        if let Some(new_description) = new_description {
-            recipe.update(|r| r.short_description = Some(new_description));
+    let on_tags_edit_action = move |new_tags| {
+        if let Some(new_tags) = new_tags {
+            recipe.update(|r| r.tags = new_tags);
         }
         modal_controller.close();
     };
 
-    let on_long_description_edit_action = move |new_description| {
-        if let Some(new_description) = new_description {
-            recipe.update(|r| r.long_description = Some(new_description));
+    let print = move || {
+        let id = recipe.get_untracked().id;
+        let print_window = window()
+            .open_with_url_and_target(&format!("/recipes/{id}/print"), "_blank")
+            .unwrap();
+        if let Some(print_window) = print_window {
+            print_window.open().unwrap();
         }
-        modal_controller.close();
     };
+    let on_print_click = move |_| print();
 
-    let on_ingredients_edit_action = move |new_ingredients| {
-        if let Some(new_ingredients) = new_ingredients {
-            recipe.update(|r| r.ingredients = new_ingredients);
+    let on_duplicate_action = move |new_id: Option<String>| {
+        if let Some(new_id) = new_id {
+            let navigator = use_navigate();
+            navigator(&format!("/recipes/{new_id}"), Default::default());
         }
         modal_controller.close();
     };
 
-    let on_steps_edit_action = move |new_steps| {
-        if let Some(new_steps) = new_steps {
-            recipe.update(|r| r.steps = new_steps);
-        }
-        modal_controller.close();
+    let duplicate = move || {
+        modal_controller.open(view! {
+            <DuplicateRecipeModal
+                recipe=recipe.get_untracked()
+                on_action=on_duplicate_action
+            />
+        })
     };
+    let on_duplicate_click = move |_| duplicate();
 
-    let on_print_click = move |_| {
-        let id = recipe.get().id;
-        let print_window = window()
-            .open_with_url_and_target(&format!("/recipes/{id}/print"), "_blank")
-            .unwrap();
-        if let Some(print_window) = print_window {
-            print_window.open().unwrap();
-        }
-    };
+    let shortcuts = use_shortcuts();
+    let navigate = store_value(use_navigate());
+    shortcuts.register(Some("e"), "Edit Recipe", move || {
+        let id = recipe.get_untracked().id;
+        navigate
+            .with_value(|navigate| navigate(&format!("/recipes/{id}/edit"), Default::default()));
+    });
+    shortcuts.register(Some("p"), "Print", print);
+    shortcuts.register(Some("d"), "Duplicate Recipe", duplicate);
 
     let on_edit_labels_click = move |_| {
         modal_controller.open(
@@ -118,96 +322,23 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
         );
     };
 
-    let on_edit_title_click = move |_| {
-        modal_controller.open(
-            view! {
-                    <EditTitleModal
-                        id=recipe.get().id
-                        title=recipe.get().title
-                        on_action=on_title_edit_action
-                    />
-            }
-            .into_view(),
-        );
-    };
-
-    let on_edit_image_click = move |_| {
+    let on_edit_tags_click = move |_| {
         modal_controller.open(
             view! {
-                    <EditImageModal
+                    <EditTagsModal
                         id=recipe.get().id
-                        image_id=recipe.get().image_id
-                        on_action=on_image_edit_action
+                        tags=recipe.get().tags
+                        on_action=on_tags_edit_action
                     />
             }
             .into_view(),
         );
     };
 
-    let on_edit_info_click = move |_| {
-        modal_controller.open(
-            view! {
-                    <EditInfoModal
-                        id=recipe.get().id
-                        info=recipe.get().info
-                        on_action=on_info_edit_action
-                    />
-            }
-            .into_view(),
-        );
-    };
-
-    let on_edit_description_click = move |_| {
-        modal_controller.open(
-            view! {
-                    <EditDescriptionModal
-                        id=recipe.get().id
-                        description=recipe.get().short_description.unwrap_or_default()
-                        on_action=on_description_edit_action
-                    />
-            }
-            .into_view(),
-        );
-    };
-
-    let on_edit_long_description_click = move |_| {
-        modal_controller.open(
-            view! {
-                    <EditLongDescriptionModal
-                        id=recipe.get().id
-                        description=recipe.get().long_description.unwrap_or_default()
-                        on_action=on_long_description_edit_action
-                    />
-            }
-            .into_view(),
-        );
-    };
-
-    let on_edit_ingredients_click = move |_| {
-        modal_controller.open(
-            view! {
-                    <EditIngredientsModal
-                        id=recipe.get().id
-                        ingredients=recipe.get().ingredients
-                        on_action=on_ingredients_edit_action
-                    />
-            }
-            .into_view(),
-        );
-    };
-
-    let on_edit_steps_click = move |_| {
-        modal_controller.open(
-            view! {
-                    <EditStepsModal
-                        id=recipe.get().id
-                        steps=recipe.get().steps
-                        on_action=on_steps_edit_action
-                    />
-            }
-            .into_view(),
-        );
-    };
+    let gallery = use_recipe_gallery(recipe.get_untracked().id).gallery;
+    let gallery_images =
+        move || ImageSource::with_cover(recipe.get().image_id, gallery.with(|v| v.images_of(None)));
+    let lightbox_index = create_rw_signal(None::<usize>);
 
     view! {
         // image
@@ -215,10 +346,16 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
             {move || {
                 if let Some(image_id) = recipe.get().image_id.as_ref() {
                     view!{
-                        <img
-                            class="object-cover w-full h-full rounded-md"
-                            src={format!("{}/recipe-image/{}", media_url(), image_id)}
-                        />
+                        <button
+                            on:click=move |_| lightbox_index.set(Some(0))
+                            class="w-full h-full"
+                            aria-label="View images"
+                        >
+                            <img
+                                class="object-cover w-full h-full rounded-md"
+                                src={format!("{}/recipe-image/{}", media_url(), image_id)}
+                            />
+                        </button>
                     }.into_any()
                 } else {
                     view!{ <div class="w-full h-full bg-neutral rounded"></div>}.into_any()
@@ -230,36 +367,33 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
                         whitespace-nowrap overflow-hidden text-ellipsis py-2">
                     {move || recipe.get().title}
                 </h1>
-                <Show when=move || edit_mode.get()>
-                    <div class="join">
-                        <button on:click=on_edit_title_click class="btn join-item">"Edit"</button>
-                        <button on:click=on_edit_image_click class="btn join-item">"Edit Image"</button>
-                    </div>
-                </Show>
             </div>
         </div>
+        <Show when=move || lightbox_index.get().is_some() fallback=|| ()>
+            <Lightbox images=gallery_images() index=lightbox_index />
+        </Show>
+        // extra images
+        {move || {
+            let images = gallery.with(|v| v.images_of(None));
+            (!images.is_empty()).then(|| view! {
+                <div class="mb-4">
+                    <ImageStrip images=ImageSource::with_cover(None, images) />
+                </div>
+            })
+        }}
         // toolbar
         <div class="mb-4 flex flex-wrap gap-2">
             // general tools
             <div class="flex flex-wrap gap-2 mr-auto">
                 <button on:click=on_print_click class="btn shadow-lg">"Print"</button>
+                <button on:click=on_duplicate_click class="btn shadow-lg">"Duplicate"</button>
                 <button on:click=on_edit_labels_click class="btn shadow-lg">"Labels"</button>
+                <button on:click=on_edit_tags_click class="btn shadow-lg">"Tags"</button>
+                <AddToCollection recipe_id=recipe.get_untracked().id />
             </div>
             // edit tools
             <div class="flex flex-wrap gap-2">
-                <label
-                    class="swap btn shadow-lg"
-                    // class="btn-outline"
-                    class:btn-outline=move || edit_mode.get()
-                    >
-                    <input
-                        prop:checked=move || edit_mode.get()
-                        on:click=move |_| edit_mode.update(|mode| *mode=mode.not())
-                        type="checkbox"
-                    />
-                    <div class="swap-on">"View Mode"</div>
-                    <div class="swap-off">"Edit Mode"</div>
-                </label>
+                <A href=move || format!("/recipes/{}/edit", recipe.get().id) class="btn shadow-lg">"Edit"</A>
                 <DropdownConfirm
                     title="Remove"
                     confirm_aria=""
@@ -268,13 +402,17 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
                 />
             </div>
         </div>
+        // tags
+        <Show when=move || !recipe.get().tags.is_empty()>
+            <div class="mb-4">
+                <TagLinks tags=Signal::derive(move || recipe.get().tags) />
+            </div>
+        </Show>
+        <RecipeVariantsBox id=recipe.get_untracked().id />
         // info
         <div class="mb-4 p-4 rounded bg-base-200">
             <div class="flex mb-2">
                 <h2 class="text-xl font-bold mr-auto">"Info"</h2>
-                <Show when=move || edit_mode.get()>
-                    <button on:click=on_edit_info_click class="btn shadow-lg">"Edit"</button>
-                </Show>
             </div>
             <table class="table table-zebra w-full max-w-5xl">
                 <tbody>
@@ -330,21 +468,15 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
         <div class="mb-4 p-4 rounded bg-base-200">
             <div class="flex mb-2">
                 <h2 class="text-xl font-bold mr-auto">"Description"</h2>
-                <Show when=move || edit_mode.get()>
-                    <button on:click=on_edit_description_click class="btn shadow-lg">"Edit"</button>
-                </Show>
             </div>
-            <p>{move || recipe.get().short_description}</p>
+            <Markdown text=Signal::derive(move || recipe.get().short_description.unwrap_or_default()) />
         </div>
         // notes (long_description)
         <div class="mb-4 p-4 rounded bg-base-200">
             <div class="flex mb-2">
                 <h2 class="text-xl font-bold mr-auto">"Notes"</h2>
-                <Show when=move || edit_mode.get()>
-                    <button on:click=on_edit_long_description_click class="btn shadow-lg">"Edit"</button>
-                </Show>
             </div>
-            <pre class="whitespace-pre-line text-base font-sans">{move || recipe.get().long_description}</pre>
+            <Markdown text=Signal::derive(move || recipe.get().long_description.unwrap_or_default()) />
         </div>
         // ingredients and steps
         <div class="flex flex-col md:flex-row gap-4">
@@ -352,9 +484,6 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
             <div class="basis-full md:basis-3/4 lg:basis-11/12 p-4 rounded bg-base-200">
                 <div class="flex mb-2">
                     <h2 class="text-xl font-bold mr-auto">"Ingredients"</h2>
-                    <Show when=move || edit_mode.get()>
-                        <button on:click=on_edit_ingredients_click class="btn shadow-lg">"Edit"</button>
-                    </Show>
                 </div>
                 <table class="table table-compact table-zebra w-full">
                     <thead>
@@ -366,46 +495,68 @@ fn RecipeContent(recipe: Recipe) -> impl IntoView {
                     </thead>
                     <tbody>
                         {move || {
-                            recipe.get().ingredients.iter().map(|ingredient| {
+                            ingredient_sections(&recipe.get().ingredients).into_iter().map(|section| {
                                 view!{
-                                    <tr>
-                                        <td class="whitespace-normal">
-                                            {format!("{} {}", Fraction::from(ingredient.amount), {&ingredient.unit_type})}
-                                        </td>
-                                        <td class="whitespace-normal">{&ingredient.name}</td>
-                                        <td class="whitespace-normal">{&ingredient.description.to_owned().unwrap_or_default()}</td>
-                                    </tr>
+                                    {section.name.map(|name| view!{
+                                        <tr><th colspan="3" class="text-base">{name}</th></tr>
+                                    })}
+                                    {section.items.iter().map(|ingredient| {
+                                        view!{
+                                            <tr>
+                                                <td class="whitespace-normal">
+                                                    {format!("{} {}", Fraction::from(ingredient.amount), {&ingredient.unit_type})}
+                                                </td>
+                                                <td class="whitespace-normal"><IngredientName ingredient=ingredient.clone() /></td>
+                                                <td class="whitespace-normal">{&ingredient.description.to_owned().unwrap_or_default()}</td>
+                                            </tr>
+                                        }
+                                    }).collect_view()}
                                 }
-                            }).collect::<Vec<_>>()
+                            }).collect_view()
                         }}
                     </tbody>
                 </table>
+                <AllIngredientsBox recipe=recipe />
             </div>
             // steps
             <div class="w-full p-4 rounded bg-base-200">
                 <div class="flex mb-2">
                     <h2 class="text-xl font-bold mr-auto">"Steps"</h2>
-                    <Show when=move || edit_mode.get()>
-                        <button on:click=on_edit_steps_click class="btn shadow-lg">"Edit"</button>
-                    </Show>
                 </div>
                 <div class="flex flex-col gap-2">
                     {move || {
-                        recipe.get().steps.into_iter().enumerate().map(|(i, step)| {
+                        step_sections(&recipe.get().steps).into_iter().map(|section| {
                             view!{
-                                <CollapsableBox
-                                    title={step.title.unwrap_or_else(|| format!("Step {}", i+1))}
-                                    open=true
-                                    class="border border-base-300 bg-base-100"
-                                >
-                                    <pre class="whitespace-pre-line text-base font-sans">{step.description}</pre>
-                                </CollapsableBox>
+                                {section.name.map(|name| view!{ <h3 class="text-lg font-bold mt-2">{name}</h3> })}
+                                {section.items.into_iter().map(|(number, step)| {
+                                    view!{
+                                        <CollapsableBox
+                                            title={step.title.unwrap_or_else(|| format!("Step {number}"))}
+                                            open=true
+                                            class="border border-base-300 bg-base-100"
+                                        >
+                                            <Markdown text=step.description />
+                                            {
+                                                let images = gallery.with(|v| v.images_of(Some(number)));
+                                                (!images.is_empty()).then(|| view! {
+                                                    <div class="mt-2">
+                                                        <ImageStrip images=ImageSource::with_cover(None, images) />
+                                                    </div>
+                                                })
+                                            }
+                                        </CollapsableBox>
+                                    }
+                                }).collect_view()}
                             }
-                        }).collect::<Vec<_>>()
+                        }).collect_view()
                     }}
                 </div>
             </div>
         </div>
+        // history
+        <CollapsableBox title="History" class="mt-4 bg-base-200">
+            <RecipeHistoryPanel recipe=recipe />
+        </CollapsableBox>
     }
 }
 
@@ -418,11 +569,12 @@ pub fn RecipePage() -> impl IntoView {
     let CurrentApi { api, .. } = use_api();
     let CurrentLogin { set_login, .. } = use_login();
 
+    // keyed on the id, as moving between recipes (e.g. variants) keeps this page
     let recipe = create_resource(
-        || {},
-        move |_| async move {
+        move || id.get(),
+        move |id| async move {
             let api = api.get_untracked().expect("api expected to exist");
-            let id = id.get_untracked().expect("id expected to exist");
+            let id = id.expect("id expected to exist");
             match api.get_recipe_by_id(id).await {
                 Ok(recipe) => Some(recipe),
                 Err(err) => {
@@ -0,0 +1,565 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Local, Utc};
+use leptos::*;
+use leptos_router::{use_navigate, use_params_map};
+use mcc_frontend_core::{image_store, storage};
+use mcc_frontend_types::{
+    draft::{DraftError, DraftField, RecipeDraft, StoredDraft, MAX_TITLE_LEN},
+    recipe::{saved_step_numbers, Recipe, Step},
+};
+use wasm_bindgen::{closure::Closure, JsCast};
+
+use crate::{
+    components::{
+        gallery::{use_recipe_gallery, GalleryEditor},
+        input::LabelSelector,
+        markdown::MarkdownInput,
+        recipe_editor::{keyed, row_values, InfoEditor, IngredientsEditor, Keyed, StepsEditor},
+    },
+    contexts::prelude::{
+        use_api, use_login, use_modal_controller, use_search_index, use_shortcuts, use_toasts,
+        CurrentApi, CurrentLogin,
+    },
+    helpers::{api_error_to_toast, logout_on_401, storage_error_to_toast},
+    modals::edit_recipe::EditImageModal,
+    pages::recipe::RecipeVariantsBox,
+};
+
+const LEAVE_MESSAGE: &str = "Leave without saving? Your changes are kept as a draft.";
+
+/// Asks before leaving the page while there are unsaved changes
+#[component]
+fn UnsavedChangesGuard(#[prop(into)] dirty: Signal<bool>) -> impl IntoView {
+    let unload_handle = window_event_listener(ev::beforeunload, move |ev| {
+        if dirty.get_untracked() {
+            ev.prevent_default();
+            ev.set_return_value(LEAVE_MESSAGE);
+        }
+    });
+
+    // the router follows links from a click listener on the window,
+    // so catching clicks while capturing allows leaving to be cancelled first
+    let on_click = Closure::<dyn Fn(web_sys::MouseEvent)>::new(move |ev: web_sys::MouseEvent| {
+        if !dirty.get_untracked() {
+            return;
+        }
+        let is_link = ev
+            .target()
+            .and_then(|v| v.dyn_into::<web_sys::Element>().ok())
+            .and_then(|v| v.closest("a[href]").ok().flatten())
+            .is_some();
+        if is_link && !window().confirm_with_message(LEAVE_MESSAGE).unwrap_or(true) {
+            ev.prevent_default();
+            ev.stop_propagation();
+        }
+    });
+    _ = window().add_event_listener_with_callback_and_bool(
+        "click",
+        on_click.as_ref().unchecked_ref(),
+        true,
+    );
+
+    on_cleanup(move || {
+        unload_handle.remove();
+        _ = window().remove_event_listener_with_callback_and_bool(
+            "click",
+            on_click.as_ref().unchecked_ref(),
+            true,
+        );
+    });
+}
+
+#[component]
+fn EditorSection(
+    field: DraftField,
+    #[prop(into)] changed: Signal<Vec<DraftField>>,
+    #[prop(into)] errors: Signal<Vec<DraftError>>,
+    children: Children,
+) -> impl IntoView {
+    let field_errors = move || {
+        errors.with(|errors| {
+            errors
+                .iter()
+                .filter(|v| v.field == field)
+                .map(|v| v.message.clone())
+                .collect::<Vec<_>>()
+        })
+    };
+
+    view! {
+        <div class="mb-4 p-4 rounded bg-base-200">
+            <div class="flex items-center gap-2 mb-2">
+                <h2 class="text-xl font-bold mr-auto">{field.label()}</h2>
+                <Show when=move || changed.with(|v| v.contains(&field))>
+                    <span class="badge badge-warning">"Unsaved"</span>
+                </Show>
+            </div>
+            {children()}
+            <ul class="text-error text-sm mt-2">
+                {move || field_errors().into_iter().map(|v| view!{ <li>{v}</li> }).collect_view()}
+            </ul>
+        </div>
+    }
+}
+
+/// New number of each saved step still in the rows, by its saved number
+fn new_step_numbers(
+    rows: &[Keyed<Step>],
+    saved_numbers: StoredValue<HashMap<usize, usize>>,
+) -> HashMap<usize, usize> {
+    rows.iter()
+        .filter(|row| {
+            row.value
+                .with_untracked(|step| step.section_name().is_none())
+        })
+        .enumerate()
+        .filter_map(|(index, row)| {
+            saved_numbers
+                .with_value(|v| v.get(&row.key).copied())
+                .map(|saved| (saved, index + 1))
+        })
+        .collect()
+}
+
+#[component]
+fn RecipeEditor(recipe: Recipe) -> impl IntoView {
+    let toasts = use_toasts();
+    let modal_controller = use_modal_controller();
+    let search_index = use_search_index();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, set_login } = use_login();
+    let media_url = login
+        .get_untracked()
+        .expect("expected login to exist")
+        .media_url;
+    let login = store_value(login.get_untracked().expect("expected login to exist"));
+    let navigate = store_value(use_navigate());
+    let id = recipe.id.clone();
+    let recipe_href = format!("/recipes/{id}");
+
+    let original = create_rw_signal(RecipeDraft::from(&recipe));
+    let stored = login.with_value(|login| storage::read_recipe_drafts(login).0.remove(&id));
+    let restored_at = stored.as_ref().map(|v| v.saved_at);
+    let changed_elsewhere = stored
+        .as_ref()
+        .is_some_and(|v| original.with_untracked(|original| &v.base != original));
+    let start = stored
+        .map(|v| v.draft)
+        .unwrap_or_else(|| original.get_untracked());
+    let show_restored = create_rw_signal(restored_at.is_some());
+    let image_id = create_rw_signal(recipe.image_id.clone());
+    let recipe = store_value(recipe);
+
+    let title = create_rw_signal(start.title.clone());
+    let info = create_rw_signal(start.info.clone());
+    let short_description = create_rw_signal(start.short_description.clone());
+    let long_description = create_rw_signal(start.long_description.clone());
+    let tags = create_rw_signal(start.tags.clone());
+    let labels = create_rw_signal(start.labels.clone());
+    let ingredients = create_rw_signal(keyed(start.ingredients.clone()));
+    let steps = create_rw_signal(keyed(start.steps.clone()));
+    let gallery = use_recipe_gallery(id.clone());
+
+    // gallery images are attached to steps by their saved number,
+    // so each row remembers its saved number to move the images along once saved
+    let saved_numbers = store_value(HashMap::<usize, usize>::new());
+    let remember_saved_numbers = move |saved: &[Step]| {
+        steps.with_untracked(|rows| {
+            let values = rows
+                .iter()
+                .map(|row| row.value.get_untracked())
+                .collect::<Vec<_>>();
+            let numbers = rows
+                .iter()
+                .zip(saved_step_numbers(saved, &values))
+                .filter_map(|(row, number)| number.map(|number| (row.key, number)))
+                .collect();
+            saved_numbers.set_value(numbers);
+        });
+    };
+    remember_saved_numbers(&original.with_untracked(|v| v.steps.clone()));
+
+    let load_draft = move |draft: RecipeDraft| {
+        title.set(draft.title);
+        info.set(draft.info);
+        short_description.set(draft.short_description);
+        long_description.set(draft.long_description);
+        tags.set(draft.tags);
+        labels.set(draft.labels);
+        ingredients.set(keyed(draft.ingredients));
+        steps.set(keyed(draft.steps));
+        remember_saved_numbers(&original.with_untracked(|v| v.steps.clone()));
+    };
+
+    let draft = Signal::derive(move || RecipeDraft {
+        title: title.get(),
+        info: info.get(),
+        short_description: short_description.get(),
+        long_description: long_description.get(),
+        tags: tags.get(),
+        labels: labels.get(),
+        ingredients: ingredients.with(|v| row_values(v)),
+        steps: steps.with(|v| row_values(v)),
+    });
+    let changed =
+        create_memo(move |_| draft.with(|draft| original.with(|v| draft.changed_fields(v))));
+    let errors = create_memo(move |_| draft.with(|draft| draft.validate()));
+    let dirty = Signal::derive(move || changed.with(|v| !v.is_empty()));
+
+    // autosave, keeping the draft until it is saved or discarded,
+    // a failure is only reported once until an autosave works again (as it is run on every change)
+    let draft_id = id.clone();
+    let autosave_failed = store_value(false);
+    create_effect(move |_| {
+        let draft = draft.get();
+        let base = original.get();
+        let is_dirty = dirty.get();
+        login.with_value(|login| {
+            let mut drafts = storage::read_recipe_drafts(login);
+            if is_dirty {
+                drafts.0.insert(
+                    draft_id.clone(),
+                    StoredDraft {
+                        saved_at: Utc::now(),
+                        base,
+                        draft,
+                    },
+                );
+            } else {
+                drafts.0.remove(&draft_id);
+            }
+            let saved = storage::set_recipe_drafts(login, &drafts);
+            if let (Err(err), false) = (&saved, autosave_failed.get_value()) {
+                toasts.push(storage_error_to_toast(err, "the draft"));
+            }
+            autosave_failed.set_value(saved.is_err());
+        });
+    });
+
+    spawn_local(async move {
+        let api = api.get_untracked().expect("api expected to be set");
+        if let Err(err) = search_index.load_tags(&api).await {
+            toasts.push(api_error_to_toast(&err, "loading tags"));
+        }
+    });
+    let existing_tags = Signal::derive(move || {
+        search_index
+            .tag_counts
+            .get()
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(tag, _)| tag)
+            .collect::<HashSet<_>>()
+    });
+    let existing_labels = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to be set");
+            match api.get_labels().await {
+                Ok(labels) => HashSet::from_iter(labels),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading labels"));
+                    HashSet::new()
+                }
+            }
+        },
+    );
+
+    let save_action = create_action(move |_: &()| {
+        let api = api.get_untracked().expect("api expected to be set");
+        let draft = draft.get_untracked();
+        let base = original.get_untracked();
+        let recipe = recipe.get_value();
+        async move {
+            let Some(update) = draft.to_update(&base) else {
+                return true;
+            };
+            match api.patch_update_recipe(recipe.id.clone(), &update).await {
+                Ok(_) => {
+                    login.with_value(|login| {
+                        let mut history = storage::read_recipe_history(login);
+                        history.push(recipe.clone(), Utc::now());
+                        if let Err(err) = storage::set_recipe_history(login, &history) {
+                            toasts.push(storage_error_to_toast(&err, "the recipe's history"));
+                        }
+                    });
+                    search_index.upsert(&draft.apply_to(&recipe));
+                    let numbers =
+                        steps.with_untracked(|rows| new_step_numbers(rows, saved_numbers));
+                    let mut removed = Vec::new();
+                    gallery.update(|gallery| removed = gallery.renumber_steps(&numbers));
+                    for image in removed {
+                        _ = image_store::delete_image(&image.key).await;
+                    }
+                    remember_saved_numbers(&draft.steps);
+                    original.set(draft);
+                    true
+                }
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "saving recipe"));
+                    logout_on_401(&set_login, &err);
+                    false
+                }
+            }
+        }
+    });
+
+    let back_href = recipe_href.clone();
+    create_effect(move |_| {
+        if save_action.value().get() == Some(true) {
+            navigate.with_value(|navigate| navigate(&back_href, Default::default()));
+        }
+    });
+
+    let can_save = move || dirty.get() && errors.with(|v| v.is_empty());
+    let save = move || {
+        if can_save() && !save_action.pending().get_untracked() {
+            save_action.dispatch(());
+        }
+    };
+
+    let discard = move || {
+        load_draft(original.get_untracked());
+        show_restored.set(false);
+    };
+
+    // nothing to confirm without changes
+    let confirm_discard = move || {
+        !dirty.get_untracked()
+            || window()
+                .confirm_with_message("Discard your unsaved changes?")
+                .unwrap_or(false)
+    };
+
+    let cancel = move || {
+        if !confirm_discard() {
+            return;
+        }
+        discard();
+        navigate.with_value(|navigate| navigate(&recipe_href, Default::default()));
+    };
+
+    let shortcuts = use_shortcuts();
+    shortcuts.register(None, "Save Recipe", save);
+    shortcuts.register(None, "Discard Changes", move || {
+        if confirm_discard() {
+            discard();
+        }
+    });
+
+    let on_image_edit_action = move |new_image_id: Option<Option<String>>| {
+        if let Some(new_image_id) = new_image_id {
+            image_id.set(new_image_id.clone());
+            recipe.update_value(|recipe| recipe.image_id = new_image_id);
+        }
+        modal_controller.close();
+    };
+
+    // images are attached to steps by their saved number, which does not count groups
+    let step_count = Signal::derive(move || {
+        original.with(|v| {
+            v.steps
+                .iter()
+                .filter(|step| step.section_name().is_none())
+                .count()
+        })
+    });
+
+    let on_edit_image_click = move |_| {
+        modal_controller.open(
+            view! {
+                <EditImageModal
+                    id=recipe.with_value(|v| v.id.clone())
+                    image_id=image_id.get_untracked()
+                    on_action=on_image_edit_action
+                />
+            }
+            .into_view(),
+        );
+    };
+
+    let on_tags_change = move |selected: HashSet<String>| {
+        // tags are matched exactly, so avoid stray whitespace creating near duplicates
+        let mut selected = selected
+            .into_iter()
+            .map(|v| v.trim().to_owned())
+            .filter(|v| !v.is_empty())
+            .collect::<Vec<_>>();
+        selected.sort();
+        tags.set(selected);
+    };
+
+    let on_labels_change = move |selected: HashSet<String>| {
+        let mut selected = selected.into_iter().collect::<Vec<_>>();
+        selected.sort();
+        labels.set(selected);
+    };
+
+    view! {
+        <UnsavedChangesGuard dirty=dirty />
+        // toolbar
+        <div class="sticky top-0 z-10 mb-4 p-2 flex flex-wrap items-center gap-2 rounded bg-base-300/[.8] backdrop-blur-sm">
+            <h1 class="mr-auto text-2xl font-bold">"Edit Recipe"</h1>
+            <span class="text-sm">
+                {move || match changed.with(|v| v.len()) {
+                    0 => "No Changes".to_owned(),
+                    1 => "1 Unsaved Change".to_owned(),
+                    n => format!("{n} Unsaved Changes"),
+                }}
+            </span>
+            <button on:click=move |_| cancel() class="btn shadow-lg">"Cancel"</button>
+            <button
+                on:click=move |_| save()
+                class="btn btn-primary shadow-lg"
+                class:loading=move || save_action.pending().get()
+                disabled=move || !can_save()
+            >
+                "Save"
+            </button>
+        </div>
+        <Show when=move || show_restored.get()>
+            <div class="alert mb-4">
+                <span class="mr-auto">
+                    {format!(
+                        "Restored unsaved changes from {}.",
+                        restored_at.unwrap_or_default().with_timezone(&Local).format("%Y-%m-%d %H:%M")
+                    )}
+                    {changed_elsewhere.then_some(" The recipe has been changed since, saving will replace those changes.")}
+                </span>
+                <button on:click=move |_| discard() class="btn btn-sm">"Discard Draft"</button>
+                <button on:click=move |_| show_restored.set(false) class="btn btn-sm">"Keep"</button>
+            </div>
+        </Show>
+        <Show when=move || errors.with(|v| !v.is_empty())>
+            <div class="alert alert-error mb-4">
+                <span>{move || format!("{} problems need fixing before saving", errors.with(|v| v.len()))}</span>
+            </div>
+        </Show>
+        <EditorSection field=DraftField::Title changed=changed errors=errors>
+            <input
+                prop:value=move || title.get()
+                on:input=move |ev| title.set(event_target_value(&ev))
+                type="text"
+                class="input input-bordered w-full"
+                placeholder="e.g. Pizza"
+                required=true
+                maxlength=MAX_TITLE_LEN
+            />
+        </EditorSection>
+        <div class="mb-4 p-4 rounded bg-base-200">
+            <div class="flex items-center gap-2 mb-2">
+                <h2 class="text-xl font-bold mr-auto">"Image"</h2>
+                <button on:click=on_edit_image_click class="btn shadow-lg">"Edit Image"</button>
+            </div>
+            {move || match image_id.get() {
+                Some(image_id) => view!{
+                    <img
+                        class="object-cover w-full h-48 rounded-md"
+                        src={format!("{media_url}/recipe-image/{image_id}")}
+                    />
+                }.into_view(),
+                None => view!{ <p>"No Image"</p> }.into_view(),
+            }}
+            <h3 class="text-lg font-bold mt-4 mb-2">"Gallery"</h3>
+            <GalleryEditor
+                id=recipe.with_value(|v| v.id.clone())
+                state=gallery
+                image_id=image_id.into()
+                step_count=step_count
+                on_cover_change=move |new_image_id| on_image_edit_action(Some(new_image_id))
+            />
+            <p class="text-sm mt-2">
+                "Image changes are saved straight away. Only the cover is uploaded, gallery images are kept in this browser: other devices will not see them and the browser may remove them to free up space."
+            </p>
+        </div>
+        <EditorSection field=DraftField::Info changed=changed errors=errors>
+            <InfoEditor info=info />
+        </EditorSection>
+        <EditorSection field=DraftField::Description changed=changed errors=errors>
+            <input
+                prop:value=move || short_description.get()
+                on:input=move |ev| short_description.set(event_target_value(&ev))
+                type="text"
+                class="input input-bordered w-full"
+                placeholder="e.g. Very tasty Pizza, make lots of it..."
+                maxlength=256
+            />
+        </EditorSection>
+        <EditorSection field=DraftField::Notes changed=changed errors=errors>
+            <MarkdownInput
+                value=long_description
+                on_input=move |value| long_description.set(value)
+                class="h-56"
+                placeholder="e.g. It was a great recipe when paired with..."
+            />
+        </EditorSection>
+        <EditorSection field=DraftField::Tags changed=changed errors=errors>
+            <LabelSelector
+                labels=existing_tags
+                allow_new=true
+                selected=Signal::derive(move || HashSet::from_iter(tags.get()))
+                on_change=on_tags_change
+                list_id="tags"
+                unstyled=true
+                placeholder="e.g. Weeknight..."
+            />
+        </EditorSection>
+        <EditorSection field=DraftField::Labels changed=changed errors=errors>
+            <LabelSelector
+                labels=Signal::derive(move || existing_labels.get().unwrap_or_default())
+                allow_new=true
+                selected=Signal::derive(move || HashSet::from_iter(labels.get()))
+                on_change=on_labels_change
+            />
+        </EditorSection>
+        <EditorSection field=DraftField::Ingredients changed=changed errors=errors>
+            <IngredientsEditor ingredients=ingredients />
+        </EditorSection>
+        <EditorSection field=DraftField::Steps changed=changed errors=errors>
+            <StepsEditor steps=steps />
+        </EditorSection>
+        <RecipeVariantsBox id=id edit_mode=true />
+    }
+}
+
+/// Edit every part of a recipe at once, saving it in one go
+#[component]
+pub fn RecipeEditPage() -> impl IntoView {
+    let params = use_params_map();
+    let id = Signal::derive(move || params.get().get("id").cloned());
+
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { set_login, .. } = use_login();
+
+    let recipe = create_resource(
+        move || id.get(),
+        move |id| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            let id = id.expect("id expected to exist");
+            match api.get_recipe_by_id(id).await {
+                Ok(recipe) => Some(recipe),
+                Err(err) => {
+                    toasts.push(api_error_to_toast(&err, "loading recipe"));
+                    logout_on_401(&set_login, &err);
+                    None
+                }
+            }
+        },
+    );
+
+    view! {
+        {move || {
+            if let Some(recipe) = recipe.get() {
+                if let Some(recipe) = recipe {
+                    view! { <><RecipeEditor recipe={recipe} /></>}
+                } else {
+                    view! { <><div>"Failed To Load :("</div></>}
+                }
+            } else {
+                view! { <><div>"Loading..."</div></>}
+            }
+        }}
+    }
+}
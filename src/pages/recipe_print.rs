@@ -1,27 +1,205 @@
-use crate::contexts::prelude::{use_api, use_login, CurrentApi, CurrentLogin};
+use crate::{
+    components::{
+        collections::use_collections,
+        gallery::{use_recipe_gallery, GalleryImg, ImageSource},
+        markdown::Markdown,
+        qr_code::QrCode,
+    },
+    contexts::prelude::{use_api, use_label_meta, use_login, use_toasts, CurrentApi, CurrentLogin},
+    helpers::{load_all, load_linked_recipes, load_recipes, server_labels, storage_error_to_toast},
+};
 use leptos::*;
-use leptos_router::use_params_map;
-use mcc_frontend_types::{recipe::Recipe, Fraction, HourMinuteSecond};
+use leptos_router::{use_location, use_navigate, use_params_map, NavigateOptions};
+use mcc_frontend_core::{
+    query::{from_query_string, to_query_string},
+    storage::{read_label_metadata, read_print_options, set_print_options},
+};
+use mcc_frontend_types::{
+    cookbook::{ingredient_index, PrintLayout, PrintOptions},
+    image::recipe_image_url,
+    query::{PrintQuery, RecipesFilter},
+    recipe::{ingredient_sections, step_sections, Recipe},
+    units::UnitSystem,
+    Fraction, HourMinuteSecond,
+};
+use std::{collections::HashMap, rc::Rc};
 
+/// Url of a print page, with the query strings it takes (e.g. a filter)
+pub fn print_url(path: &str, queries: &[String]) -> String {
+    let query = queries
+        .iter()
+        .filter(|v| !v.is_empty())
+        .cloned()
+        .collect::<Vec<_>>()
+        .join("&");
+    format!("{path}?{query}")
+}
+
+/// Open a print page in a new window
+pub fn open_print(url: &str) {
+    let print_window = window().open_with_url_and_target(url, "_blank").unwrap();
+    if let Some(print_window) = print_window {
+        print_window.open().unwrap();
+    }
+}
+
+/// The print options, from the url or else those last used.
+/// They are remembered & kept in the url as they change, `other_queries` being the rest of it.
+fn use_print_options(other_queries: Vec<String>) -> RwSignal<PrintOptions> {
+    let location = use_location();
+    let pathname = location.pathname.get_untracked();
+    let toasts = use_toasts();
+    let CurrentLogin { login, .. } = use_login();
+    // the url takes priority, so a print can be shared
+    let options = create_rw_signal({
+        let from_url = from_query_string::<PrintOptions>(&location.search.get_untracked());
+        if from_url != PrintOptions::default() {
+            from_url
+        } else {
+            login
+                .get_untracked()
+                .and_then(|login| read_print_options(&login))
+                .unwrap_or_default()
+        }
+    });
+    create_effect(move |_| {
+        let options = options.get();
+        if let Some(login) = login.get_untracked() {
+            if let Err(err) = set_print_options(&login, &options) {
+                toasts.push(storage_error_to_toast(&err, "the print options"));
+            }
+        }
+        let mut queries = other_queries.clone();
+        queries.push(to_query_string(&options));
+        let url = print_url(&pathname, &queries);
+        if url != format!("{pathname}?{}", location.search.get_untracked()) {
+            let navigator = use_navigate();
+            navigator(
+                &url,
+                NavigateOptions {
+                    replace: true,
+                    scroll: false,
+                    ..Default::default()
+                },
+            );
+        }
+    });
+    options
+}
+
+/// Text size of each layout
+fn layout_class(layout: PrintLayout) -> &'static str {
+    match layout {
+        PrintLayout::Standard => "p-2",
+        PrintLayout::Compact => "p-2 text-sm",
+        PrintLayout::LargePrint => "p-2 text-xl",
+        PrintLayout::Card3x5 | PrintLayout::Card4x6 => "p-2 print:p-0 text-xs",
+    }
+}
+
+/// Paper size of index cards, there being no class to set it
 #[component]
-fn RecipePrintContent(recipe: Recipe) -> impl IntoView {
+fn PageSize(layout: Signal<PrintLayout>) -> impl IntoView {
+    move || {
+        layout.get().page_size().map(|size| {
+            view! { <style>{format!("@page {{ size: {size}; margin: 0.25in; }}")}</style> }
+        })
+    }
+}
+
+#[component]
+fn RecipePrintContent(
+    recipe: Recipe,
+    /// recipes linked as sub-recipes (recursively), expanded when scaled
+    linked: Rc<HashMap<String, Recipe>>,
+    options: Signal<PrintOptions>,
+) -> impl IntoView {
     let CurrentLogin { login, .. } = use_login();
     let media_url = move || login.get().expect("expected login to exist").media_url;
+    let gallery = use_recipe_gallery(recipe.id.clone()).gallery;
+    let layout = Signal::derive(move || options.get().layout);
+    let is_card = move || layout.get().is_card();
+    let images_of = move |step: Option<usize>| {
+        move || {
+            let images = gallery.with(|v| v.images_of(step));
+            (options.get().gallery && !is_card() && !images.is_empty()).then(|| view! {
+                <div class="grid grid-cols-3 gap-2 mb-2 break-inside-avoid">
+                    {images.into_iter().map(|image| view! {
+                        <GalleryImg source=ImageSource::Local(image.key) class="h-32 w-full object-cover rounded" />
+                    }).collect_view()}
+                </div>
+            })
+        }
+    };
+    let image_id = recipe.image_id.clone();
+    let recipe_url = format!(
+        "{}/recipes/{}",
+        window().location().origin().unwrap_or_default(),
+        recipe.id
+    );
+    let yields = recipe
+        .info
+        .yields
+        .as_ref()
+        .map(|v| v.value)
+        .unwrap_or_default();
+    let scaled_yields = move || match yields {
+        0 => 0,
+        value => options.get().yields(value),
+    };
+    // cards only have room for a line of the recipe's info
+    let card_info = {
+        let info = recipe.info.clone();
+        move || {
+            let total_time = HourMinuteSecond::from_secs(info.prep_time + info.cook_time).as_hms();
+            match &info.yields {
+                Some(yields) => format!("{} {} · {total_time}", scaled_yields(), yields.unit_type),
+                None => total_time,
+            }
+        }
+    };
+    let labels = recipe.labels.clone();
+    let has_labels = !labels.is_empty();
+    let printed = recipe.clone();
 
     view! {
         {move || {
-                recipe.image_id.as_ref().map(|image_id| view!{
-                            <figure class="h-64 w-full mb-4">
-                                <img
-                                    class="object-cover w-full h-full rounded"
-                                    src={format!("{}/recipe-image/{}", media_url(), image_id)}
-                                />
-                            </figure>
-                    })
-
-            }}
-        <h1 class="text-3xl font-bold mb-4">{recipe.title}</h1>
-        <div class=" mb-4">
+            image_id.as_ref().filter(|_| options.get().image && !is_card()).map(|image_id| view!{
+                <figure class=move || match layout.get() {
+                    PrintLayout::Compact => "h-32 w-full mb-4",
+                    _ => "h-64 w-full mb-4",
+                }>
+                    <img
+                        class="object-cover w-full h-full rounded"
+                        src={format!("{}/recipe-image/{}", media_url(), image_id)}
+                    />
+                </figure>
+            })
+        }}
+        <div class="flex gap-4 justify-between">
+            <div>
+                <h1 class=move || match is_card() {
+                    true => "text-xl font-bold mb-2",
+                    false => "text-3xl font-bold mb-4",
+                }>{recipe.title}</h1>
+                <p class="mb-2" class:hidden=move || !is_card()>{card_info}</p>
+                <div class="flex flex-wrap gap-1 mb-2" class:hidden=move || !options.get().labels || !has_labels>
+                    {labels.iter().map(|label| view! {
+                        <span class="badge badge-outline">{label.clone()}</span>
+                    }).collect_view()}
+                </div>
+            </div>
+            {move || options.get().qr_code.then(|| view! {
+                <QrCode
+                    text=recipe_url.clone()
+                    class=match is_card() {
+                        true => "w-16 h-16 shrink-0",
+                        false => "w-24 h-24 shrink-0",
+                    }
+                />
+            })}
+        </div>
+        <div class="mb-4" class:hidden=is_card>
             <table class="table table-compact table-zebra w-full max-w-2xl">
                 <tbody>
                     {
@@ -45,7 +223,7 @@ fn RecipePrintContent(recipe: Recipe) -> impl IntoView {
                                 }
                             }}
                             <tr class="text-center">
-                                <td>{info.yields.clone().unwrap_or_default().value}</td>
+                                <td>{scaled_yields}</td>
                                 <td><input prop:checked=info.freezable type="checkbox" class="checkbox" disabled=true/></td>
                                 <td><input prop:checked=info.microwave_only type="checkbox" class="checkbox" disabled=true/></td>
                             </tr>
@@ -66,20 +244,25 @@ fn RecipePrintContent(recipe: Recipe) -> impl IntoView {
             {
                 if let Some(source) = recipe.info.source {
                     if !source.is_empty() {
-                        Some(view!{ <p class="text-sm my-2">"Source: " {source}</p>})
+                        Some(view!{ <p class="text-sm my-2" class:hidden=move || !options.get().source>"Source: " {source}</p>})
                     } else { None }
                 } else { None }
             }
         </div>
         <div class="mb-4">
             <h2 class="text-xl font-bold mb-1">"Description"</h2>
-            <p>{recipe.short_description}</p>
+            <Markdown text=recipe.short_description.unwrap_or_default() />
         </div>
-        <div class="mb-4">
+        {images_of(None)}
+        <div class="mb-4" class:hidden=move || is_card() || !options.get().notes>
             <h2 class="text-xl font-bold mb-1">"Notes"</h2>
-            <pre class="whitespace-pre-line text-base font-sans">{recipe.long_description}</pre>
+            <Markdown text=recipe.long_description.unwrap_or_default() />
         </div>
-        <div class="mb-4">
+        <div class=move || match layout.get() {
+            PrintLayout::Compact => "columns-2 gap-6",
+            _ => "",
+        }>
+        <div class="mb-4 break-inside-avoid">
             <h2 class="text-xl font-bold mb-1">{"Ingredients"}</h2>
             <table class="table table-compact table-zebra w-full">
                 <thead>
@@ -90,38 +273,244 @@ fn RecipePrintContent(recipe: Recipe) -> impl IntoView {
                     </tr>
                 </thead>
                 <tbody>
-                    {
-                        recipe.ingredients.iter().map(|ingredient| {
+                    {move || {
+                        let ingredients = options.get().ingredients(&printed, &linked);
+                        ingredient_sections(&ingredients).into_iter().map(|section| {
                             view!{
-                                <tr>
-                                    <td class="whitespace-normal">{format!("{} {}", Fraction::from(ingredient.amount), {&ingredient.unit_type})}</td>
-                                    <td class="whitespace-normal">{&ingredient.name}</td>
-                                    <td class="whitespace-normal">{&ingredient.description.clone().unwrap_or_default()}</td>
-                                </tr>
+                                {section.name.map(|name| view!{
+                                    <tr><th colspan="3" class="text-base">{name}</th></tr>
+                                })}
+                                {section.items.into_iter().map(|ingredient| {
+                                    view!{
+                                        <tr>
+                                            <td class="whitespace-normal">{format!("{} {}", Fraction::from(ingredient.amount), {&ingredient.unit_type})}</td>
+                                            <td class="whitespace-normal">{ingredient.display_name()}</td>
+                                            <td class="whitespace-normal">{&ingredient.description.clone().unwrap_or_default()}</td>
+                                        </tr>
+                                    }
+                                }).collect_view()}
                             }
-                        }).collect::<Vec<_>>()
-                    }
+                        }).collect_view()
+                    }}
                 </tbody>
             </table>
         </div>
         <div class="mb-4">
             <h2 class="text-xl font-bold mb-1">"Steps"</h2>
-            <ul>
             {
-                recipe.steps.iter().enumerate().map(|(i, step)| {
+                step_sections(&recipe.steps).into_iter().map(|section| {
                     view!{
-                        <li class="mb-2">
-                            <h2 class="text-l font-bold mb-2">{&step.title.clone().unwrap_or_else(|| format!("Step {}", i+1))}</h2>
-                            <pre class="whitespace-pre-line text-base font-sans">{&step.description}</pre>
-                        </li>
+                        {section.name.map(|name| view!{ <h3 class="text-lg font-bold mb-1 border-b">{name}</h3> })}
+                        <ul>
+                        {
+                            section.items.into_iter().map(|(number, step)| {
+                                view!{
+                                    <li class="mb-2">
+                                        <h2 class="text-l font-bold mb-2">{step.title.unwrap_or_else(|| format!("Step {number}"))}</h2>
+                                        <Markdown text=step.description />
+                                        {images_of(Some(number))}
+                                    </li>
+                                }
+                            }).collect_view()
+                        }
+                        </ul>
                     }
-                }).collect::<Vec<_>>()
+                }).collect_view()
             }
-            </ul>
+        </div>
+        </div>
+    }
+}
+
+/// Print button & options, hidden once printed
+#[component]
+fn PrintControls(options: RwSignal<PrintOptions>) -> impl IntoView {
+    let toggle = move |label: &'static str,
+                       get: fn(&PrintOptions) -> bool,
+                       set: fn(&mut PrintOptions, bool)| {
+        view! {
+            <label class="label cursor-pointer gap-2">
+                <input
+                    prop:checked=move || options.with(get)
+                    on:change=move |ev| options.update(|options| set(options, event_target_checked(&ev)))
+                    type="checkbox"
+                    class="checkbox checkbox-sm"
+                />
+                <span class="label-text">{label}</span>
+            </label>
+        }
+    };
+
+    view! {
+        <button
+            on:click=move |_| window().print().unwrap()
+            class="btn btn-primary my-4 mx-auto block print:hidden"
+        >
+            "Click Here To Print Or: "
+            <kbd data-theme="light" class="kbd">"ctrl"</kbd>
+            "+"
+            <kbd data-theme="light" class="kbd">"p"</kbd>
+        </button>
+        <div class="flex flex-wrap justify-center items-center gap-4 mb-2 print:hidden">
+            <label class="label gap-2">
+                <span class="label-text">"Layout"</span>
+                <select
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(layout) = PrintLayout::ALL.into_iter().find(|v| v.label() == value) {
+                            options.update(|options| options.layout = layout);
+                        }
+                    }
+                    class="select select-bordered select-sm"
+                >
+                    {PrintLayout::ALL.into_iter().map(|layout| view! {
+                        <option
+                            value=layout.label()
+                            prop:selected=move || options.get().layout == layout
+                        >{layout.label()}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="label gap-2">
+                <span class="label-text">"Scale"</span>
+                <select
+                    on:change=move |ev| {
+                        if let Ok(scale) = event_target_value(&ev).parse() {
+                            options.update(|options| options.scale = scale);
+                        }
+                    }
+                    class="select select-bordered select-sm"
+                >
+                    {PrintOptions::SCALES.into_iter().map(|scale| view! {
+                        <option
+                            value=scale.to_string()
+                            prop:selected=move || options.get().scale == scale
+                        >{format!("x{}", Fraction::from(scale))}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <label class="label gap-2">
+                <span class="label-text">"Units"</span>
+                <select
+                    on:change=move |ev| {
+                        let value = event_target_value(&ev);
+                        if let Some(units) = UnitSystem::ALL.into_iter().find(|v| v.label() == value) {
+                            options.update(|options| options.units = units);
+                        }
+                    }
+                    class="select select-bordered select-sm"
+                >
+                    {UnitSystem::ALL.into_iter().map(|units| view! {
+                        <option
+                            value=units.label()
+                            prop:selected=move || options.get().units == units
+                        >{units.label()}</option>
+                    }).collect_view()}
+                </select>
+            </label>
+            <div class="join items-center">
+                <button
+                    on:click=move |_| options.update(|v| v.font_size = v.step_font_size(false))
+                    class="btn btn-sm join-item"
+                    aria-label="Smaller Text"
+                >"A-"</button>
+                <span class="join-item px-2 text-sm">{move || format!("{}%", options.get().font_size)}</span>
+                <button
+                    on:click=move |_| options.update(|v| v.font_size = v.step_font_size(true))
+                    class="btn btn-sm join-item"
+                    aria-label="Larger Text"
+                >"A+"</button>
+            </div>
+        </div>
+        <div class="flex flex-wrap justify-center gap-4 mb-4 print:hidden">
+            {toggle("Image", |v| v.image, |v, checked| v.image = checked)}
+            {toggle("Gallery Images", |v| v.gallery, |v, checked| v.gallery = checked)}
+            {toggle("Notes", |v| v.notes, |v, checked| v.notes = checked)}
+            {toggle("Source", |v| v.source, |v, checked| v.source = checked)}
+            {toggle("Labels", |v| v.labels, |v, checked| v.labels = checked)}
+            {toggle("QR Code", |v| v.qr_code, |v, checked| v.qr_code = checked)}
         </div>
     }
 }
 
+/// The options' text size, applied to what is printed but not the controls
+fn font_size_style(options: RwSignal<PrintOptions>) -> impl Fn() -> String {
+    move || format!("font-size: {}%", options.get().font_size)
+}
+
+/// Recipes printed together as a cookbook, a cover page & contents followed by
+/// each recipe starting on a new page, then an index of the ingredients used
+#[component]
+fn Cookbook(
+    title: String,
+    description: Option<String>,
+    /// url of the cover image
+    cover: Option<String>,
+    recipes: Vec<Recipe>,
+    /// recipes linked as sub-recipes, see [`load_linked_recipes`]
+    linked: HashMap<String, Recipe>,
+    options: Signal<PrintOptions>,
+) -> impl IntoView {
+    let linked = Rc::new(linked);
+    let layout = Signal::derive(move || options.get().layout);
+    let contents = recipes
+        .iter()
+        .map(|recipe| (recipe.id.clone(), recipe.title.clone()))
+        .collect::<Vec<_>>();
+    let index = ingredient_index(&recipes)
+        .into_iter()
+        .map(|(name, positions)| {
+            let links = positions
+                .into_iter()
+                .map(|position| (position + 1, contents[position].0.clone()))
+                .collect::<Vec<_>>();
+            (name, links)
+        })
+        .collect::<Vec<_>>();
+
+    view! {
+        <section class="break-after-page min-h-screen print:min-h-0 flex flex-col justify-center text-center gap-4">
+            {cover.map(|src| view! {
+                <figure class="h-96 w-full" class:hidden=move || layout.get().is_card()>
+                    <img class="object-cover w-full h-full rounded" src=src />
+                </figure>
+            })}
+            <h1 class="text-5xl font-bold">{title}</h1>
+            {description.map(|text| view! { <Markdown text=text /> })}
+            <p>{format!("{} Recipes", recipes.len())}</p>
+        </section>
+        <nav class="break-after-page">
+            <h2 class="text-3xl font-bold mb-4">"Contents"</h2>
+            <ol class="list-decimal list-inside">
+                {contents.into_iter().map(|(id, title)| view! {
+                    <li class="mb-1"><a href=format!("#recipe-{id}") class="link link-hover">{title}</a></li>
+                }).collect_view()}
+            </ol>
+        </nav>
+        {recipes.into_iter().map(|recipe| view! {
+            <article id=format!("recipe-{}", recipe.id) class="break-before-page">
+                <RecipePrintContent recipe=recipe linked=linked.clone() options=options />
+            </article>
+        }).collect_view()}
+        <section class="break-before-page">
+            <h2 class="text-3xl font-bold mb-4">"Index"</h2>
+            <ul class=move || match layout.get().is_card() {
+                true => "",
+                false => "columns-2 gap-6",
+            }>
+                {index.into_iter().map(|(name, links)| view! {
+                    <li class="mb-1 break-inside-avoid">
+                        {name} " "
+                        {links.into_iter().map(|(number, id)| view! {
+                            <a href=format!("#recipe-{id}") class="link link-hover mr-1">{number}</a>
+                        }).collect_view()}
+                    </li>
+                }).collect_view()}
+            </ul>
+        </section>
+    }
+}
+
 #[component]
 pub fn RecipePrint() -> impl IntoView {
     let params = use_params_map();
@@ -133,35 +522,202 @@ pub fn RecipePrint() -> impl IntoView {
         move |_| async move {
             let api = api.get_untracked().expect("api expected to exist");
             let id = id.get_untracked().expect("id expected to exist");
-            match api.get_recipe_by_id(id).await {
-                Ok(recipe) => Some(recipe),
-                Err(_) => None,
-            }
+            let recipe = api.get_recipe_by_id(id).await.ok()?;
+            let linked = load_linked_recipes(&api, std::slice::from_ref(&recipe))
+                .await
+                .ok()?;
+            Some((recipe, linked))
         },
     );
 
+    let options = use_print_options(Vec::new());
+    let layout = Signal::derive(move || options.get().layout);
+
     view! {
-        <div class="p-2" data-theme="light">
-            <button
-                on:click=move |_| window().print().unwrap()
-                class="btn btn-primary my-4 mx-auto block print:hidden"
-            >
-                "Click Here To Print Or: "
-                <kbd data-theme="light" class="kbd">"ctrl"</kbd>
-                "+"
-                <kbd data-theme="light" class="kbd">"p"</kbd>
-            </button>
-            {move || {
-                if let Some(recipe) = recipe.get() {
-                    if let Some(recipe) = recipe {
-                        view!{ <><RecipePrintContent recipe=recipe/></>}
+        <div class=move || layout_class(layout.get()) data-theme="light">
+            <PageSize layout=layout />
+            <PrintControls options=options />
+            <div style=font_size_style(options)>
+                {move || {
+                    if let Some(recipe) = recipe.get() {
+                        if let Some((recipe, linked)) = recipe {
+                            view!{ <><RecipePrintContent recipe=recipe linked=Rc::new(linked) options=options.into()/></>}
+                        } else {
+                            view!{ <><div>"Failed To Load :("</div></>}
+                        }
                     } else {
-                        view!{ <><div>"Failed To Load :("</div></>}
+                        view!{ <><div>"Loading..."</div></>}
+                    }
+                }}
+            </div>
+        </div>
+    }
+}
+
+/// Url of the image of the first recipe with one
+fn first_image(media_url: &str, recipes: &[Recipe]) -> Option<String> {
+    recipes
+        .iter()
+        .find_map(|v| v.image_id.as_ref())
+        .map(|image_id| recipe_image_url(media_url, image_id))
+}
+
+/// Recipes printed as a cookbook, picked by id or matching a filter (see [`PrintQuery`])
+#[component]
+pub fn CookbookPrint() -> impl IntoView {
+    let location = use_location();
+    let search = location.search.get_untracked();
+    let filters = from_query_string::<RecipesFilter>(&search);
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, .. } = use_login();
+    let label_meta = use_label_meta();
+    let query = from_query_string::<PrintQuery>(&search);
+    // the filter is only used without ids
+    let filter_query = match query.ids.is_empty() {
+        true => to_query_string(&filters),
+        false => String::new(),
+    };
+    let options = use_print_options(vec![to_query_string(&query), filter_query]);
+    let layout = Signal::derive(move || options.get().layout);
+    let query = store_value(query);
+    let filters = store_value(filters);
+
+    let recipes = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            let ids = query.with_value(|v| v.ids.clone());
+            let recipes = match ids.is_empty() {
+                false => load_recipes(&api, &ids).await.ok()?.0,
+                true => {
+                    // the label metadata is read here, as this window is opened without it loaded
+                    let metadata = login
+                        .get_untracked()
+                        .map(|login| read_label_metadata(&login))
+                        .unwrap_or_default();
+                    let filters = filters.get_value();
+                    let labels = server_labels(&api, &label_meta, &metadata, &filters.labels)
+                        .await
+                        .ok()?;
+                    let recipes = load_all(|page, per_page| {
+                        let filters = RecipesFilter {
+                            page,
+                            per_page,
+                            labels: labels.clone(),
+                            ..filters.clone()
+                        };
+                        let api = api.clone();
+                        async move { api.get_recipes(&filters).await }
+                    })
+                    .await
+                    .ok()?;
+                    // tags & parent labels are checked here, as they are on the recipes page
+                    let selected = filters.labels.clone().unwrap_or_default();
+                    recipes
+                        .into_iter()
+                        .filter(|recipe| {
+                            filters.matches_tags(recipe)
+                                && metadata.matches(&selected, &recipe.labels)
+                        })
+                        .collect::<Vec<_>>()
+                }
+            };
+            let linked = load_linked_recipes(&api, &recipes).await.ok()?;
+            Some((recipes, linked))
+        },
+    );
+
+    view! {
+        <div class=move || layout_class(layout.get()) data-theme="light">
+            <PageSize layout=layout />
+            <PrintControls options=options />
+            <div style=font_size_style(options)>
+            {move || match recipes.get() {
+                None => view! { <div>"Loading..."</div> }.into_view(),
+                Some(None) => view! { <div>"Failed To Load :("</div> }.into_view(),
+                Some(Some((recipes, linked))) => {
+                    let media_url = login
+                        .get_untracked()
+                        .expect("expected login to exist")
+                        .media_url;
+                    view! {
+                        <Cookbook
+                            title=query.with_value(|v| v.cookbook.clone()).unwrap_or_else(|| "Recipes".to_owned())
+                            description=None
+                            cover=first_image(&media_url, &recipes)
+                            recipes=recipes
+                            linked=linked
+                            options=options.into()
+                        />
+                    }
+                    .into_view()
+                }
+            }}
+            </div>
+        </div>
+    }
+}
+/// A collection printed as a cookbook
+#[component]
+pub fn CollectionPrint() -> impl IntoView {
+    let params = use_params_map();
+    let id = Signal::derive(move || params.get().get("id").cloned().unwrap_or_default());
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, .. } = use_login();
+    let collection = use_collections()
+        .collections
+        .with_untracked(|v| v.get(&id.get_untracked()).cloned());
+    let collection = store_value(collection);
+
+    let recipes = create_resource(
+        || {},
+        move |_| async move {
+            let api = api.get_untracked().expect("api expected to exist");
+            let ids = collection
+                .with_value(|v| v.as_ref().map(|v| v.recipe_ids.clone()))
+                .unwrap_or_default();
+            let (recipes, _) = load_recipes(&api, &ids).await.ok()?;
+            let linked = load_linked_recipes(&api, &recipes).await.ok()?;
+            Some((recipes, linked))
+        },
+    );
+
+    let options = use_print_options(Vec::new());
+    let layout = Signal::derive(move || options.get().layout);
+
+    view! {
+        <div class=move || layout_class(layout.get()) data-theme="light">
+            <PageSize layout=layout />
+            <PrintControls options=options />
+            <div style=font_size_style(options)>
+            {move || match (collection.get_value(), recipes.get()) {
+                (None, _) => view! { <div>"Collection Not Found"</div> }.into_view(),
+                (_, None) => view! { <div>"Loading..."</div> }.into_view(),
+                (_, Some(None)) => view! { <div>"Failed To Load :("</div> }.into_view(),
+                (Some(collection), Some(Some((recipes, linked)))) => {
+                    let media_url = login
+                        .get_untracked()
+                        .expect("expected login to exist")
+                        .media_url;
+                    let cover = collection
+                        .cover_recipe(|id| recipes.iter().any(|v| v.id == id && v.image_id.is_some()))
+                        .and_then(|id| recipes.iter().find(|v| v.id == id))
+                        .and_then(|v| v.image_id.as_ref())
+                        .map(|image_id| recipe_image_url(&media_url, image_id));
+                    view! {
+                        <Cookbook
+                            title=collection.name
+                            description=collection.description
+                            cover=cover
+                            recipes=recipes
+                            linked=linked
+                            options=options.into()
+                        />
                     }
-                } else {
-                    view!{ <><div>"Loading..."</div></>}
+                    .into_view()
                 }
             }}
+            </div>
         </div>
     }
 }
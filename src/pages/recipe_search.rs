@@ -0,0 +1,135 @@
+use leptos::*;
+use leptos_router::{use_location, use_navigate, NavigateOptions, A};
+use mcc_frontend_core::query::from_query_string;
+use mcc_frontend_types::{
+    query::SearchQuery,
+    search::{SearchField, SearchResult},
+};
+
+use crate::{
+    components::search::{search_href, Highlighted},
+    contexts::prelude::{
+        use_api, use_login, use_search_index, use_toasts, CurrentApi, CurrentLogin,
+    },
+    helpers::{api_error_to_toast, logout_on_401},
+};
+
+#[component]
+fn SearchResultRow(result: SearchResult, media_url: String) -> impl IntoView {
+    let (title, others): (Vec<_>, Vec<_>) = result
+        .matches
+        .into_iter()
+        .partition(|v| v.field == SearchField::Title);
+    let title_highlights = title
+        .into_iter()
+        .next()
+        .map(|v| v.highlights)
+        .unwrap_or_default();
+
+    view! {
+        <A href=format!("/recipes/{}", result.id) class="flex gap-4 p-2 rounded hover:bg-base-300">
+            <div class="w-20 h-20 shrink-0 rounded bg-base-300">
+                {result.image_id.map(|image_id| view!{
+                    <img
+                        class="object-cover w-full h-full rounded"
+                        src=format!("{}/recipe-image/{}", media_url, image_id)
+                    />
+                })}
+            </div>
+            <div class="min-w-0">
+                <h2 class="text-xl font-bold">
+                    <Highlighted text=result.title highlights=title_highlights />
+                </h2>
+                <ul class="text-sm">
+                    {others.into_iter().map(|v| view!{
+                        <li class="truncate">
+                            <span class="font-bold">{v.field.label()}": "</span>
+                            <Highlighted text=v.text highlights=v.highlights />
+                        </li>
+                    }).collect_view()}
+                </ul>
+            </div>
+        </A>
+    }
+}
+
+#[component]
+pub fn RecipeSearch() -> impl IntoView {
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { login, set_login } = use_login();
+    let search_index = use_search_index();
+    let location = use_location();
+    let query = Signal::derive(move || from_query_string::<SearchQuery>(&location.search.get()).q);
+
+    let refresh_action = create_action(move |_: &()| async move {
+        let api = api.get_untracked().expect("api expected to exist");
+        if let Err(err) = search_index.refresh(&api).await {
+            toasts.push(api_error_to_toast(&err, "updating search index"));
+            logout_on_401(&set_login, &err);
+        }
+    });
+    // the stored index can be searched straight away, while it is brought up to date
+    refresh_action.dispatch(());
+
+    let results = create_memo(move |_| search_index.index.with(|index| index.search(&query.get())));
+
+    let on_input = move |ev| {
+        let navigate = use_navigate();
+        navigate(
+            &search_href(&event_target_value(&ev)),
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
+    };
+
+    let on_rebuild = move |_| {
+        search_index.clear();
+        refresh_action.dispatch(());
+    };
+
+    view! {
+        <div class="rounded bg-base-200 p-4 mb-2">
+            <h1 class="text-3xl font-bold mb-4">"Search Recipes"</h1>
+            <input
+                prop:value=move || query.get()
+                on:input=on_input
+                type="search"
+                class="input input-bordered w-full mb-2"
+                placeholder="e.g. tahini, chopped onion..."
+                aria-label="Search recipes"
+                autofocus=true
+            />
+            <div class="flex items-center gap-2 text-sm">
+                <span>"Searches titles, ingredients, steps, descriptions & sources."</span>
+                <span class="ml-auto">{move || format!("{} Recipes Indexed", search_index.index.with(|v| v.len()))}</span>
+                <Show when=move || search_index.refreshing.get()>
+                    <span class="loading loading-spinner loading-sm" aria-label="Updating index"></span>
+                </Show>
+                <button
+                    on:click=on_rebuild
+                    disabled=move || search_index.refreshing.get()
+                    class="btn btn-sm"
+                >"Rebuild"</button>
+            </div>
+        </div>
+        <div class="rounded bg-base-200 p-4">
+            {move || {
+                let media_url = login.get().expect("expected login to exist").media_url;
+                let results = results.get();
+                if query.get().trim().is_empty() {
+                    view!{ <div>"Type above to search."</div>}.into_view()
+                } else if results.is_empty() {
+                    view!{ <div>"No recipes found."</div>}.into_view()
+                } else {
+                    results.into_iter().map(|result| view!{
+                        <SearchResultRow result=result media_url=media_url.clone() />
+                    }).collect_view()
+                }
+            }}
+        </div>
+    }
+}
@@ -0,0 +1,93 @@
+use leptos::*;
+use leptos_router::A;
+
+use crate::{
+    components::tags::tag_href,
+    contexts::prelude::{
+        use_api, use_login, use_search_index, use_toasts, CurrentApi, CurrentLogin,
+    },
+    helpers::{api_error_to_toast, logout_on_401},
+};
+
+// text sizes for the tag cloud, from least to most used
+const CLOUD_SIZES: [&str; 5] = ["text-sm", "text-base", "text-lg", "text-xl", "text-2xl"];
+
+/// Pick a text size for a tag, relative to the most used tag
+fn cloud_size(count: usize, max_count: usize) -> &'static str {
+    let i = (count * CLOUD_SIZES.len()).div_ceil(max_count.max(1));
+    CLOUD_SIZES[i.clamp(1, CLOUD_SIZES.len()) - 1]
+}
+
+#[component]
+pub fn RecipeTags() -> impl IntoView {
+    let toasts = use_toasts();
+    let CurrentApi { api, .. } = use_api();
+    let CurrentLogin { set_login, .. } = use_login();
+    let sort_by_count = create_rw_signal(false);
+
+    let search_index = use_search_index();
+    let failed = create_rw_signal(false);
+    spawn_local(async move {
+        let api = api.get_untracked().expect("api expected to exist");
+        if let Err(err) = search_index.load_tags(&api).await {
+            toasts.push(api_error_to_toast(&err, "loading tags"));
+            logout_on_401(&set_login, &err);
+            failed.set(true);
+        }
+    });
+    let tags = move || match (search_index.tag_counts.get(), failed.get()) {
+        (Some(tags), _) => Some(Some(tags)),
+        (None, true) => Some(None),
+        (None, false) => None,
+    };
+
+    let sorted_tags = move || {
+        let mut tags = search_index.tag_counts.get().unwrap_or_default();
+        if sort_by_count.get() {
+            tags.sort_by_key(|(_, count)| std::cmp::Reverse(*count));
+        }
+        tags
+    };
+
+    view! {
+        <div class="rounded bg-base-200 p-4 mb-2">
+            <h1 class="text-3xl font-bold mb-4">"Recipe Tags"</h1>
+            <label class="label cursor-pointer justify-start gap-2">
+                <input
+                    prop:checked=move || sort_by_count.get()
+                    on:input=move |ev| sort_by_count.set(event_target_checked(&ev))
+                    type="checkbox"
+                    class="checkbox"
+                />
+                <span class="label-text">"Most Used First"</span>
+            </label>
+        </div>
+        <div class="rounded bg-base-200 p-4">
+            {move || match tags() {
+                None => view!{ <div class="loading loading-ring loading-lg"></div>}.into_view(),
+                Some(None) => view!{ <div>"Failed To Load :("</div>}.into_view(),
+                Some(Some(tags)) if tags.is_empty() => view!{
+                    <div>"No tags yet, add some from a recipe page."</div>
+                }.into_view(),
+                Some(Some(_)) => {
+                    let tags = sorted_tags();
+                    let max_count = tags.iter().map(|(_, count)| *count).max().unwrap_or_default();
+                    // class="text-sm text-base text-lg text-xl text-2xl"
+                    view!{
+                        <div class="flex flex-wrap items-baseline gap-4">
+                            {tags.into_iter().map(|(tag, count)| view!{
+                                <A
+                                    href=tag_href(&tag)
+                                    class=format!("link link-hover {}", cloud_size(count, max_count))
+                                >
+                                    {tag}
+                                    <span class="badge badge-sm ml-1">{count}</span>
+                                </A>
+                            }).collect_view()}
+                        </div>
+                    }.into_view()
+                }
+            }}
+        </div>
+    }
+}
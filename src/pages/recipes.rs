@@ -5,18 +5,28 @@ use crate::{
         collapse::*,
         image_links::*,
         input::{LabelSelector, ThreeStateSelect},
-        loading::{BufferedPageLoader, LoadingItemsState},
+        loading::{BufferedPageLoader, ListSummary, LoadingItemsState, NumberedPager},
     },
     contexts::prelude::{
-        use_api, use_login, use_modal_controller, use_toasts, CurrentApi, CurrentLogin,
+        use_api, use_label_meta, use_login, use_modal_controller, use_search_index, use_shortcuts,
+        use_toasts, CurrentApi, CurrentLogin,
+    },
+    helpers::{
+        api_error_to_toast, load_filtered_pages, load_pages, logout_on_401,
+        remember_scroll_position, restore_scroll_position, scroll_key, server_labels,
+        storage_error_to_toast, track_scroll_position, FilteredPages,
     },
-    helpers::{api_error_to_toast, logout_on_401},
     modals::edit_recipe::NewRecipeModal,
+    pages::recipe_print::{open_print, print_url},
 };
 use leptos::ev::SubmitEvent;
 use leptos::*;
-use leptos_router::use_navigate;
-use mcc_frontend_types::query::RecipesFilter;
+use leptos_router::{use_location, use_navigate, NavigateOptions, A};
+use mcc_frontend_core::{
+    query::{from_query_string, to_query_string},
+    storage::{read_paging_mode, set_paging_mode},
+};
+use mcc_frontend_types::{page::PagingMode, query::RecipesFilter};
 
 #[component]
 fn RecipesFilterPanel<F>(
@@ -31,15 +41,20 @@ where
         || {},
         move |()| async move {
             let api = api.get_untracked().expect("api expected to exist");
-            match api.get_labels().await {
-                Ok(v) => v,
-                Err(_) => {
-                    vec![]
-                }
-            }
+            api.get_labels().await.unwrap_or_default()
         },
     );
-    let filters = create_rw_signal(filters.get_untracked());
+    let current_filters = filters;
+    // failures just leave the tags without suggestions
+    let search_index = use_search_index();
+    spawn_local(async move {
+        let api = api.get_untracked().expect("api expected to exist");
+        _ = search_index.load_tags(&api).await;
+    });
+    let label_meta = use_label_meta();
+    let filters = create_rw_signal(current_filters.get_untracked());
+    // follow changes made outside the panel, e.g. back navigation
+    create_effect(move |_| filters.set(current_filters.get()));
 
     let on_search_submission = move |ev: SubmitEvent| {
         ev.prevent_default();
@@ -94,7 +109,7 @@ where
                         <span class="label-text">"Labels"</span>
                         <div class="w-80 max-h-28">
                             <LabelSelector
-                                labels=Signal::derive( move || HashSet::from_iter(labels.get().unwrap_or_default().into_iter()))
+                                labels=Signal::derive( move || label_meta.metadata.get().with_parents(&HashSet::from_iter(labels.get().unwrap_or_default())))
                                 allow_new=false
                                 compact=true
                                 selected=Signal::derive( move || filters.get().labels.unwrap_or_default())
@@ -103,6 +118,23 @@ where
                         </div>
                     </label>
                 </div>
+                <div class="form-control">
+                    <label class="label">
+                        <span class="label-text">"Tags"</span>
+                        <div class="w-80 max-h-28">
+                            <LabelSelector
+                                labels=Signal::derive( move || search_index.tag_counts.get().unwrap_or_default().into_iter().map(|(tag, _)| tag).collect())
+                                allow_new=false
+                                compact=true
+                                selected=Signal::derive( move || filters.get().tags.unwrap_or_default())
+                                on_change=move |t| filters.update(|f| f.tags = Some(t))
+                                list_id="tags"
+                                unstyled=true
+                                placeholder="e.g. Weeknight..."
+                            />
+                        </div>
+                    </label>
+                </div>
             </CollapsableBox>
             <button
                 type="submit"
@@ -120,21 +152,101 @@ pub fn Recipes() -> impl IntoView {
     let modal_controller = use_modal_controller();
     let CurrentApi { api, .. } = use_api();
     let CurrentLogin { login, set_login } = use_login();
+    let location = use_location();
+    let pathname = location.pathname.get_untracked();
+    let label_meta = use_label_meta();
+    let paging = create_rw_signal(
+        login
+            .get_untracked()
+            .map(|login| read_paging_mode(&login))
+            .unwrap_or_default(),
+    );
 
-    let filters = create_rw_signal(RecipesFilter::default());
+    let filters = create_rw_signal(from_query_string::<RecipesFilter>(
+        &location.search.get_untracked(),
+    ));
+    // when set every page up to the current one is loaded, e.g. after navigating back
+    let restoring = create_rw_signal(true);
     let (items, set_items) = create_signal::<Vec<ImageLinkItem>>(Vec::default());
 
+    // follow the url, for back/forward navigation
+    create_effect(move |_| {
+        let search = location.search.get();
+        if location.pathname.get_untracked() != pathname {
+            return;
+        }
+        let from_url = from_query_string::<RecipesFilter>(&search);
+        if from_url != filters.get_untracked() {
+            restoring.set(true);
+            filters.set(from_url);
+        }
+    });
+
+    let current_scroll_key = move || {
+        scroll_key(
+            "/recipes",
+            &to_query_string(&filters.get_untracked()),
+            paging.get_untracked(),
+        )
+    };
+    track_scroll_position(current_scroll_key, restoring.into());
+
+    let filtered = store_value(FilteredPages::default());
     let fetch_recipes = create_resource(
         move || filters.get(),
         move |filters| {
             let api = api.get_untracked().expect("api expected to exist");
+            let first_page =
+                if restoring.get_untracked() && paging.get_untracked() == PagingMode::Scroll {
+                    1
+                } else {
+                    filters.page
+                };
+            let metadata = label_meta.metadata.get_untracked();
             async move {
-                match api.get_recipes(&filters).await {
+                let response = async {
+                    let labels =
+                        server_labels(&api, &label_meta, &metadata, &filters.labels).await?;
+                    let load = |page: usize, per_page: usize| {
+                        let filters = RecipesFilter {
+                            page,
+                            per_page,
+                            labels: labels.clone(),
+                            ..filters.clone()
+                        };
+                        let api = api.clone();
+                        async move { api.get_recipes(&filters).await }
+                    };
+                    // tags & parent labels are not sent to the server, so are checked here
+                    let has_tags = filters.tags.as_ref().is_some_and(|tags| !tags.is_empty());
+                    if !has_tags && labels == filters.labels {
+                        return load_pages(first_page, filters.page, filters.per_page, load).await;
+                    }
+                    let selected = filters.labels.clone().unwrap_or_default();
+                    load_filtered_pages(
+                        filtered,
+                        RecipesFilter {
+                            page: 1,
+                            ..filters.clone()
+                        },
+                        first_page,
+                        filters.page,
+                        filters.per_page,
+                        load,
+                        |recipe| {
+                            filters.matches_tags(recipe)
+                                && metadata.matches(&selected, &recipe.labels)
+                        },
+                    )
+                    .await
+                }
+                .await;
+                match response {
                     Ok(v) => Some(v),
                     Err(err) => {
                         toasts.push(api_error_to_toast(
                             &err,
-                            &format!("loading recipes page {}", &filters.page),
+                            &format!("loading recipes page {}", filters.page),
                         ));
                         logout_on_401(&set_login, &err);
                         None
@@ -147,34 +259,35 @@ pub fn Recipes() -> impl IntoView {
     // update the items when recipes are fetched
     create_effect(move |_| {
         // XXX this is not great, but it works (just ensure any reads everything is x.get_untracked()
-        let media_url = login
-            .get_untracked()
-            .expect("expected login to exist")
-            .media_url;
-        if let Some(Some(recipes)) = fetch_recipes.get() {
+        if let Some(Some((recipes, _))) = fetch_recipes.get() {
+            let is_restore = restoring.get_untracked();
             set_items.update(|v| {
-                if filters.get_untracked().page == 1 {
+                if is_restore
+                    || filters.get_untracked().page == 1
+                    || paging.get_untracked() == PagingMode::Numbered
+                {
                     v.clear();
                 }
-                v.extend(recipes.iter().map(|recipe| {
-                    ImageLinkItem {
-                        key: recipe.id.clone(),
-                        href: format!("/recipes/{}", recipe.id),
-                        title: recipe.title.clone(),
-                        image_src: recipe
-                            .image_id
-                            .as_ref()
-                            .map(|v| format!("{}/recipe-image/{}", media_url, v)),
-                    }
+                v.extend(recipes.iter().map(|recipe| ImageLinkItem {
+                    key: recipe.id.clone(),
+                    href: format!("/recipes/{}", recipe.id),
+                    title: recipe.title.clone(),
+                    image_id: recipe.image_id.clone(),
+                    tags: recipe.tags.clone(),
+                    labels: recipe.labels.clone(),
                 }));
             });
+            if is_restore {
+                restore_scroll_position(&current_scroll_key());
+                restoring.set(false);
+            }
         }
     });
 
     let loading_items_state = Signal::derive(move || {
         match (fetch_recipes.loading().get(), fetch_recipes.get().flatten()) {
             (true, _) => LoadingItemsState::Loading,
-            (false, Some(items)) => LoadingItemsState::Loaded(items.len()),
+            (false, Some((_, has_more))) => LoadingItemsState::Loaded { has_more },
             (false, None) => LoadingItemsState::Failed,
         }
     });
@@ -188,13 +301,26 @@ pub fn Recipes() -> impl IntoView {
     };
 
     let on_new_filters = move |new_filters: RecipesFilter| {
-        filters.update(|v| {
-            *v = new_filters;
-            v.page = 1;
-        });
+        let new_filters = RecipesFilter {
+            page: 1,
+            ..new_filters
+        };
+        remember_scroll_position(
+            &scroll_key(
+                "/recipes",
+                &to_query_string(&new_filters),
+                paging.get_untracked(),
+            ),
+            0.0,
+        );
+        let navigator = use_navigate();
+        navigator(
+            &format!("/recipes?{}", to_query_string(&new_filters)),
+            Default::default(),
+        );
     };
 
-    let on_new_recipe_click = move |_| {
+    let new_recipe = move || {
         modal_controller.open(
             view! {
                 <NewRecipeModal
@@ -204,17 +330,63 @@ pub fn Recipes() -> impl IntoView {
             .into_view(),
         )
     };
+    let on_new_recipe_click = move |_| new_recipe();
+    use_shortcuts().register(Some("n"), "New Recipe", new_recipe);
 
     let on_load_more = move || {
         filters.update(|v| {
             v.page += 1;
         });
+        let navigator = use_navigate();
+        navigator(
+            &format!("/recipes?{}", to_query_string(&filters.get_untracked())),
+            NavigateOptions {
+                replace: true,
+                scroll: false,
+                ..Default::default()
+            },
+        );
     };
 
     let on_retry = move || {
         fetch_recipes.refetch();
     };
 
+    let on_page = move |page: usize| {
+        filters.update(|v| v.page = page);
+        remember_scroll_position(
+            &scroll_key(
+                "/recipes",
+                &to_query_string(&filters.get_untracked()),
+                PagingMode::Numbered,
+            ),
+            0.0,
+        );
+        let navigator = use_navigate();
+        navigator(
+            &format!("/recipes?{}", to_query_string(&filters.get_untracked())),
+            Default::default(),
+        );
+    };
+
+    // every recipe matching the filters, not just those loaded
+    let on_print_click = move |_| {
+        open_print(&print_url(
+            "/recipes/print",
+            &[to_query_string(&filters.get_untracked())],
+        ));
+    };
+
+    let on_paging_change = move |mode: PagingMode| {
+        paging.set(mode);
+        if let Some(login) = login.get_untracked() {
+            if let Err(err) = set_paging_mode(&login, mode) {
+                toasts.push(storage_error_to_toast(&err, "the paging mode"));
+            }
+        }
+        on_new_filters(filters.get_untracked());
+    };
+
     view! {
         <div class="rounded bg-base-200 p-4 mb-2">
             <h1 class="text-3xl font-bold mb-4">"Recipes"</h1>
@@ -223,18 +395,41 @@ pub fn Recipes() -> impl IntoView {
                     on:click=on_new_recipe_click
                     class="btn btn-neutral shadow-lg join-item"
                 >"New Recipe"</button>
+                <A href="/recipes/tags" class="btn btn-neutral shadow-lg join-item">"Tags"</A>
+                <A href="/recipes/search" class="btn btn-neutral shadow-lg join-item">"Search"</A>
+                <button
+                    on:click=on_print_click
+                    class="btn btn-neutral shadow-lg join-item"
+                >"Print"</button>
             </div>
         </div>
         <div class="p-4 rounded bg-base-200">
             <RecipesFilterPanel filters=filters.read_only() update_filters=on_new_filters />
             <div class="divider" />
-            <ImageLinksBox items={items} />
-            <BufferedPageLoader
-                items_state=loading_items_state
-                items_per_page=Signal::derive( move || filters.get().per_page)
-                load_more_action=on_load_more
-                retry_action=on_retry
+            <ListSummary
+                loaded=Signal::derive(move || items.with(Vec::len))
+                noun="recipes"
+                paging=paging.into()
+                on_paging_change=on_paging_change
             />
+            <ImageLinksBox items={items} />
+            {move || match paging.get() {
+                PagingMode::Scroll => view! {
+                    <BufferedPageLoader
+                        items_state=loading_items_state
+                        load_more_action=on_load_more
+                        retry_action=on_retry
+                    />
+                },
+                PagingMode::Numbered => view! {
+                    <NumberedPager
+                        items_state=loading_items_state
+                        page=Signal::derive(move || filters.get().page)
+                        on_page=on_page
+                        retry_action=on_retry
+                    />
+                },
+            }}
         </div>
     }
 }